@@ -7,7 +7,6 @@
 /// 4. Message ordering guarantees
 /// 5. Atomic transaction delivery
 /// 6. Sequence number validation
-
 use pq_core::{PQSession, BidirectionalSession, MessageEnvelope, MessageType};
 use sha2::{Sha256, Digest};
 
@@ -107,7 +106,7 @@ fn main() {
     // Create envelopes for the exchanged messages
     let envelope_alice_to_bob = MessageEnvelope::new(
         alice_seq1,
-        1000 + (alice_seq1 as u64),
+        1000 + alice_seq1,
         alice_id,
         txn1_id,
         MessageType::Data,
@@ -118,7 +117,7 @@ fn main() {
 
     let envelope_bob_to_alice = MessageEnvelope::new(
         bob_seq1,
-        1000 + (bob_seq1 as u64),
+        1000 + bob_seq1,
         bob_id,
         txn2_id,
         MessageType::Data,
@@ -6,11 +6,14 @@
 /// - Message ordering and sequence numbers
 /// - Acknowledgment-based reliability
 /// - Per-message forward secrecy via chain key ratcheting
-
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex, RwLock};
+use crate::identity::DeviceCertificate;
+use crate::protocol::PQError;
+use crate::sig::dilithium::{Dilithium, DilithiumPublicKey, DilithiumSecretKey, DilithiumSignature};
+use crate::sig::Verifier;
 
 /// Message type enumeration for protocol control flow
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -70,7 +73,8 @@ pub struct MessageEnvelope {
     /// AES-GCM nonce (12 bytes)
     pub nonce: [u8; 12],
     
-    /// Dilithium signature over (sequence_number || timestamp || payload)
+    /// Dilithium signature over [`signed_data`](Self::signed_data), or
+    /// empty when the session does not sign envelopes
     pub signature: Vec<u8>,
     
     /// For Ack messages: received_up_to_seq (highest seq confirmed)
@@ -81,6 +85,7 @@ pub struct MessageEnvelope {
 }
 
 impl MessageEnvelope {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         sequence_number: u64,
         timestamp: u64,
@@ -105,6 +110,29 @@ impl MessageEnvelope {
         }
     }
 
+    /// The bytes covered by the envelope signature: every header field the
+    /// receiver acts on, including the ACK fields, followed by the payload.
+    /// `ack_up_to` is a presence byte and a value, `missing_seqs` a count and
+    /// the sequence numbers.
+    pub fn signed_data(&self) -> Vec<u8> {
+        let ack_len = 1 + 8 + 4 + 8 * self.missing_seqs.len();
+        let mut data = Vec::with_capacity(8 + 8 + 32 + 16 + 1 + 12 + ack_len + self.payload.len());
+        data.extend_from_slice(&self.sequence_number.to_be_bytes());
+        data.extend_from_slice(&self.timestamp.to_be_bytes());
+        data.extend_from_slice(&self.sender_id);
+        data.extend_from_slice(&self.transaction_id.to_be_bytes());
+        data.push(self.message_type);
+        data.extend_from_slice(&self.nonce);
+        data.push(self.ack_up_to.is_some() as u8);
+        data.extend_from_slice(&self.ack_up_to.unwrap_or(0).to_be_bytes());
+        data.extend_from_slice(&(self.missing_seqs.len() as u32).to_be_bytes());
+        for seq in &self.missing_seqs {
+            data.extend_from_slice(&seq.to_be_bytes());
+        }
+        data.extend_from_slice(&self.payload);
+        data
+    }
+
     pub fn as_data_message_type(&self) -> bool {
         self.message_type == MessageType::Data as u8
     }
@@ -151,7 +179,7 @@ impl PendingTransaction {
 }
 
 /// Receiver state: buffers incoming messages per sender
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct ReceiverState {
     /// Last confirmed sequence number per sender (None = no message yet)
     pub last_seen_seq: Option<u64>,
@@ -199,7 +227,7 @@ impl ReceiverState {
 }
 
 /// Sender state: tracks outgoing messages and acknowledgments
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct SenderState {
     /// Next sequence number to use
     pub seq_counter: u64,
@@ -273,11 +301,24 @@ pub struct BidirectionalSession {
     pub receiver_state: Arc<RwLock<ReceiverState>>,
 
     // === Identity ===
-    /// SHA-256 hash of our public key (sender_id in messages)
+    /// SHA-256 hash of our public key (sender_id in messages); for a
+    /// multi-device identity, of the master key rather than the device key
     pub my_id: [u8; 32],
     
-    /// SHA-256 hash of peer's public key (to validate sender_id)
+    /// SHA-256 hash of peer's public key (to validate sender_id); for a
+    /// multi-device identity, of the master key rather than the device key
     pub peer_id: [u8; 32],
+
+    /// Keys for signing outgoing and verifying incoming envelopes, if set
+    signing: Option<EnvelopeSigning>,
+}
+
+/// Our Dilithium secret key and the peer's public key, used to sign every
+/// outgoing envelope and verify every incoming one.
+struct EnvelopeSigning {
+    scheme: Dilithium,
+    secret_key: DilithiumSecretKey,
+    peer_public_key: DilithiumPublicKey,
 }
 
 impl BidirectionalSession {
@@ -297,9 +338,33 @@ impl BidirectionalSession {
             receiver_state: Arc::new(RwLock::new(ReceiverState::new())),
             my_id,
             peer_id,
+            signing: None,
         }
     }
 
+    /// Create a session between two multi-device identities, identified by
+    /// the master identities behind the device certificates exchanged in
+    /// the handshake (see [`PQSession::peer_device`](crate::PQSession::peer_device)).
+    pub fn between_devices(
+        tx_chain_key: [u8; 32],
+        rx_chain_key: [u8; 32],
+        my_device: &DeviceCertificate,
+        peer_device: &DeviceCertificate,
+    ) -> Self {
+        Self::new(tx_chain_key, rx_chain_key, my_device.identity(), peer_device.identity())
+    }
+
+    /// Signs every envelope sent with `secret_key` and rejects received
+    /// envelopes that do not verify under `peer_public_key`.
+    pub fn with_envelope_signing(mut self, secret_key: DilithiumSecretKey, peer_public_key: DilithiumPublicKey) -> Self {
+        self.signing = Some(EnvelopeSigning {
+            scheme: Dilithium::new(),
+            secret_key,
+            peer_public_key,
+        });
+        self
+    }
+
     /// Send a message with atomicity guarantees
     /// 
     /// Returns: sequence number assigned to this message
@@ -321,9 +386,8 @@ impl BidirectionalSession {
         sender.seq_counter = seq + 1;
 
         let nonce = [0u8; 12];  // TODO: derive from chain key + seq
-        let signature = vec![];  // TODO: sign with Dilithium
 
-        let envelope = MessageEnvelope::new(
+        let mut envelope = MessageEnvelope::new(
             seq,
             timestamp,
             self.my_id,
@@ -331,8 +395,14 @@ impl BidirectionalSession {
             MessageType::Data,
             payload.to_vec(),
             nonce,
-            signature,
+            vec![],
         );
+        if let Some(signing) = &self.signing {
+            let signature = signing.scheme
+                .sign(&envelope.signed_data(), &signing.secret_key)
+                .map_err(|_| PQError::InvalidSignature)?;
+            envelope.signature = signature.as_bytes().to_vec();
+        }
 
         sender.record_message(seq, envelope.clone(), timestamp);
 
@@ -346,6 +416,73 @@ impl BidirectionalSession {
             return Err(crate::protocol::PQError::Other);
         }
 
+        if let Some(signing) = &self.signing {
+            let signature = DilithiumSignature::from_bytes(&envelope.signature)
+                .map_err(|_| PQError::InvalidSignature)?;
+            let valid = signing.scheme
+                .verify(&envelope.signed_data(), &signature, &signing.peer_public_key)
+                .map_err(|_| PQError::InvalidSignature)?;
+            if !valid {
+                return Err(PQError::InvalidSignature);
+            }
+        }
+
+        self.accept(envelope)
+    }
+
+    /// Receive a batch of envelopes, verifying their signatures together
+    /// across the rayon thread pool.
+    ///
+    /// Envelopes from another sender or with a bad signature are dropped and
+    /// their indices returned; the rest are processed as by
+    /// [`receive`](Self::receive), in sequence order. Dropped envelopes show
+    /// up as gaps in [`get_ack_state`](Self::get_ack_state), so the peer
+    /// retransmits them.
+    pub fn receive_batch(&self, envelopes: Vec<MessageEnvelope>) -> Result<Vec<usize>, PQError> {
+        let mut rejected: Vec<usize> = Vec::new();
+        let mut candidates: Vec<usize> = Vec::new();
+        for (index, envelope) in envelopes.iter().enumerate() {
+            if envelope.sender_id == self.peer_id {
+                candidates.push(index);
+            } else {
+                rejected.push(index);
+            }
+        }
+
+        if let Some(signing) = &self.signing {
+            let mut checked = Vec::with_capacity(candidates.len());
+            for index in candidates {
+                match DilithiumSignature::from_bytes(&envelopes[index].signature) {
+                    Ok(signature) => checked.push((index, envelopes[index].signed_data(), signature)),
+                    Err(_) => rejected.push(index),
+                }
+            }
+
+            let items: Vec<_> = checked
+                .iter()
+                .map(|(_, data, signature)| (data.as_slice(), signature, &signing.peer_public_key))
+                .collect();
+            let report = signing.scheme.verify_batch(&items);
+            rejected.extend(report.failed().into_iter().map(|i| checked[i].0));
+        }
+
+        rejected.sort_unstable();
+        let mut accepted: Vec<MessageEnvelope> = envelopes
+            .into_iter()
+            .enumerate()
+            .filter(|(index, _)| rejected.binary_search(index).is_err())
+            .map(|(_, envelope)| envelope)
+            .collect();
+        accepted.sort_by_key(|envelope| envelope.sequence_number);
+        for envelope in accepted {
+            self.accept(envelope)?;
+        }
+
+        Ok(rejected)
+    }
+
+    /// Sequence and buffer an authenticated envelope
+    fn accept(&self, envelope: MessageEnvelope) -> Result<(), crate::protocol::PQError> {
         let mut receiver = self.receiver_state.write().map_err(|_| crate::protocol::PQError::Other)?;
 
         // Check for duplicate
@@ -0,0 +1,553 @@
+//! Compact post-quantum identity certificates.
+//!
+//! A certificate binds a signature public key to a subject name, a validity
+//! window and a set of key usages, and is signed by an issuer: another
+//! certificate's key, or the subject's own key for a self-signed root.
+//! [`TrustAnchors`] validates a chain, leaf first, up to one of a
+//! configured set of roots, and [`PQSession`](crate::PQSession) can carry a
+//! chain in its handshake in place of a bare key.
+//!
+//! Certificates are generic over the signature scheme like the session is;
+//! every certificate of a chain uses the same scheme.
+//!
+//! Format (version 1), all integers big-endian, times in Unix seconds:
+//!
+//! ```text
+//! magic "PQCT" (4) | version 0x01 (1) | serial (8) | key algorithm id (2) | signature algorithm id (2)
+//! | not before (8) | not after (8) | key usage (1) | subject length (2) | subject
+//! | issuer length (2) | issuer | public key length (2) | public key
+//! | signature length (2) | signature
+//! ```
+//!
+//! Algorithm ids are [`SignatureAlgorithm::id`]. The signature covers every
+//! byte before the signature length.
+//!
+//! ```
+//! use pq_core::cert::{Certificate, CertificateParams, KeyUsage, TrustAnchors, Validity};
+//! use pq_core::sig::dilithium::Dilithium;
+//!
+//! let scheme = Dilithium::new();
+//! let (root_pk, root_sk) = scheme.keygen().unwrap();
+//! let root = Certificate::self_signed(
+//!     &scheme,
+//!     CertificateParams::new("Example Root", Validity::for_days(3650), KeyUsage::CERT_SIGN),
+//!     &root_pk,
+//!     &root_sk,
+//! )
+//! .unwrap();
+//!
+//! let (pk, _sk) = scheme.keygen().unwrap();
+//! let leaf = Certificate::issue(
+//!     &scheme,
+//!     CertificateParams::new("server.example", Validity::for_days(90), KeyUsage::DIGITAL_SIGNATURE),
+//!     &pk,
+//!     &root,
+//!     &root_sk,
+//! )
+//! .unwrap();
+//!
+//! let anchors = TrustAnchors::new(Dilithium::new()).with_anchor(root);
+//! let chain = [Certificate::from_bytes(&scheme, &leaf.to_bytes()).unwrap()];
+//! assert_eq!(anchors.validate_now(&chain).unwrap().subject(), "server.example");
+//! ```
+
+use rand::rngs::OsRng;
+use rand::RngCore;
+use thiserror::Error;
+use crate::sig::dilithium::Dilithium;
+use crate::sig::{SignatureAlgorithm, SignatureError, Signer, Verifier};
+
+/// Identifies a certificate.
+pub const MAGIC: [u8; 4] = *b"PQCT";
+/// The format version written by this module.
+pub const VERSION: u8 = 1;
+/// The longest chain, leaf included, that [`TrustAnchors`] will follow.
+pub const MAX_CHAIN_LENGTH: usize = 8;
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum CertificateError {
+    #[error("Not a certificate")]
+    BadMagic,
+    #[error("Unsupported certificate version: {0}")]
+    UnsupportedVersion(u8),
+    #[error("Certificate is truncated")]
+    Truncated,
+    #[error("Certificate is malformed")]
+    Malformed,
+    #[error("Field longer than 65535 bytes")]
+    FieldTooLong,
+    #[error("Certificate algorithm does not match the signature scheme")]
+    AlgorithmMismatch,
+    #[error("Certificate for {0} is expired or not yet valid")]
+    OutsideValidity(String),
+    #[error("Certificate for {0} lacks the required key usage")]
+    KeyUsage(String),
+    #[error("Certificate for {0} was not issued by the next certificate in the chain")]
+    IssuerMismatch(String),
+    #[error("Certificate signature does not verify")]
+    BadSignature,
+    #[error("Certificate chain is empty")]
+    EmptyChain,
+    #[error("Certificate chain is longer than {MAX_CHAIN_LENGTH} certificates")]
+    ChainTooLong,
+    #[error("Certificate chain does not lead to a trust anchor")]
+    UntrustedChain,
+    #[error("Handshake key does not match the certificate")]
+    KeyMismatch,
+    #[error("Certificate is for {0}, not the expected peer")]
+    SubjectMismatch(String),
+    #[error("Signature error: {0}")]
+    Signature(#[from] SignatureError),
+}
+
+/// What a certified key may be used for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct KeyUsage(u8);
+
+impl KeyUsage {
+    pub const NONE: KeyUsage = KeyUsage(0);
+    /// Authenticating handshakes and signing messages.
+    pub const DIGITAL_SIGNATURE: KeyUsage = KeyUsage(0x01);
+    /// Issuing certificates.
+    pub const CERT_SIGN: KeyUsage = KeyUsage(0x02);
+
+    const ALL_BITS: u8 = 0x03;
+
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// Returns `None` if `bits` has an undefined flag set.
+    pub const fn from_bits(bits: u8) -> Option<Self> {
+        if bits & !Self::ALL_BITS != 0 {
+            return None;
+        }
+        Some(KeyUsage(bits))
+    }
+
+    pub const fn contains(self, other: KeyUsage) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl std::ops::BitOr for KeyUsage {
+    type Output = KeyUsage;
+
+    fn bitor(self, rhs: KeyUsage) -> KeyUsage {
+        KeyUsage(self.0 | rhs.0)
+    }
+}
+
+/// The window, in Unix seconds, during which a certificate is valid. Both
+/// ends are inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Validity {
+    pub not_before: u64,
+    pub not_after: u64,
+}
+
+impl Validity {
+    pub fn new(not_before: u64, not_after: u64) -> Self {
+        Validity { not_before, not_after }
+    }
+
+    /// Valid from now for `days` days.
+    pub fn for_days(days: u64) -> Self {
+        let now = unix_time();
+        Validity::new(now, now.saturating_add(days.saturating_mul(SECONDS_PER_DAY)))
+    }
+
+    pub fn contains(&self, time: u64) -> bool {
+        self.not_before <= time && time <= self.not_after
+    }
+}
+
+/// The fields of a certificate chosen by its issuer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CertificateParams {
+    pub subject: String,
+    pub serial: u64,
+    pub validity: Validity,
+    pub key_usage: KeyUsage,
+}
+
+impl CertificateParams {
+    /// Parameters with a random serial number.
+    pub fn new(subject: impl Into<String>, validity: Validity, key_usage: KeyUsage) -> Self {
+        CertificateParams {
+            subject: subject.into(),
+            serial: OsRng.next_u64(),
+            validity,
+            key_usage,
+        }
+    }
+}
+
+/// A signed certificate for a key of scheme `S`.
+pub struct Certificate<S: Verifier = Dilithium> {
+    serial: u64,
+    subject: String,
+    issuer: String,
+    validity: Validity,
+    key_usage: KeyUsage,
+    algorithm: SignatureAlgorithm,
+    signature_algorithm: SignatureAlgorithm,
+    public_key: S::PublicKey,
+    signature: S::Signature,
+    /// The signed bytes, kept as received so verification never depends on
+    /// re-encoding
+    tbs: Vec<u8>,
+}
+
+impl<S: Verifier> Clone for Certificate<S> {
+    fn clone(&self) -> Self {
+        Certificate {
+            serial: self.serial,
+            subject: self.subject.clone(),
+            issuer: self.issuer.clone(),
+            validity: self.validity,
+            key_usage: self.key_usage,
+            algorithm: self.algorithm,
+            signature_algorithm: self.signature_algorithm,
+            public_key: self.public_key.clone(),
+            signature: self.signature.clone(),
+            tbs: self.tbs.clone(),
+        }
+    }
+}
+
+impl<S: Verifier> std::fmt::Debug for Certificate<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Certificate")
+            .field("serial", &self.serial)
+            .field("subject", &self.subject)
+            .field("issuer", &self.issuer)
+            .field("validity", &self.validity)
+            .field("key_usage", &self.key_usage)
+            .field("algorithm", &self.algorithm)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S: Verifier> PartialEq for Certificate<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.tbs == other.tbs && self.signature.as_ref() == other.signature.as_ref()
+    }
+}
+
+impl<S: Verifier> Eq for Certificate<S> {}
+
+impl<S: Signer> Certificate<S> {
+    /// Issues a certificate for `pk` signed by `sk` itself, with the subject
+    /// as its own issuer.
+    pub fn self_signed(
+        scheme: &S,
+        params: CertificateParams,
+        pk: &S::PublicKey,
+        sk: &S::SecretKey,
+    ) -> Result<Self, CertificateError> {
+        let issuer = params.subject.clone();
+        Self::sign(scheme, params, issuer, pk, sk)
+    }
+
+    /// Issues a certificate for `subject_pk`, signed by `issuer_sk`, the
+    /// secret key of `issuer`. The issuer must be allowed to sign
+    /// certificates.
+    pub fn issue(
+        scheme: &S,
+        params: CertificateParams,
+        subject_pk: &S::PublicKey,
+        issuer: &Certificate<S>,
+        issuer_sk: &S::SecretKey,
+    ) -> Result<Self, CertificateError> {
+        if !issuer.key_usage.contains(KeyUsage::CERT_SIGN) {
+            return Err(CertificateError::KeyUsage(issuer.subject.clone()));
+        }
+        Self::sign(scheme, params, issuer.subject.clone(), subject_pk, issuer_sk)
+    }
+
+    fn sign(
+        scheme: &S,
+        params: CertificateParams,
+        issuer: String,
+        pk: &S::PublicKey,
+        sk: &S::SecretKey,
+    ) -> Result<Self, CertificateError> {
+        let algorithm = scheme.algorithm();
+        let tbs = encode_tbs(&params, &issuer, algorithm, pk.as_ref())?;
+        let signature = scheme.sign(&tbs, sk)?;
+        if signature.as_ref().len() > u16::MAX as usize {
+            return Err(CertificateError::FieldTooLong);
+        }
+
+        Ok(Certificate {
+            serial: params.serial,
+            subject: params.subject,
+            issuer,
+            validity: params.validity,
+            key_usage: params.key_usage,
+            algorithm,
+            signature_algorithm: algorithm,
+            public_key: pk.clone(),
+            signature,
+            tbs,
+        })
+    }
+}
+
+impl<S: Verifier> Certificate<S> {
+    /// Decodes a certificate whose keys and signature belong to `scheme`.
+    /// The signature is not checked; see [`TrustAnchors::validate`].
+    pub fn from_bytes(scheme: &S, bytes: &[u8]) -> Result<Self, CertificateError> {
+        let mut reader = Reader { bytes, pos: 0 };
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(CertificateError::BadMagic);
+        }
+        let version = reader.u8()?;
+        if version != VERSION {
+            return Err(CertificateError::UnsupportedVersion(version));
+        }
+
+        let serial = reader.u64()?;
+        let algorithm = SignatureAlgorithm::try_from(reader.u16()?)?;
+        let signature_algorithm = SignatureAlgorithm::try_from(reader.u16()?)?;
+        if algorithm != scheme.algorithm() || signature_algorithm != scheme.algorithm() {
+            return Err(CertificateError::AlgorithmMismatch);
+        }
+        let validity = Validity::new(reader.u64()?, reader.u64()?);
+        let key_usage = KeyUsage::from_bits(reader.u8()?).ok_or(CertificateError::Malformed)?;
+        let subject = reader.string()?;
+        let issuer = reader.string()?;
+        let public_key = scheme.public_key_from_bytes(reader.field()?)?;
+        let tbs = bytes[..reader.pos].to_vec();
+        let signature = scheme.signature_from_bytes(reader.field()?)?;
+        if reader.pos != bytes.len() {
+            return Err(CertificateError::Malformed);
+        }
+
+        Ok(Certificate {
+            serial,
+            subject,
+            issuer,
+            validity,
+            key_usage,
+            algorithm,
+            signature_algorithm,
+            public_key,
+            signature,
+            tbs,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let signature = self.signature.as_ref();
+        let mut out = Vec::with_capacity(self.tbs.len() + 2 + signature.len());
+        out.extend_from_slice(&self.tbs);
+        out.extend_from_slice(&(signature.len() as u16).to_be_bytes());
+        out.extend_from_slice(signature);
+        out
+    }
+
+    pub fn serial(&self) -> u64 {
+        self.serial
+    }
+
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+
+    pub fn issuer(&self) -> &str {
+        &self.issuer
+    }
+
+    pub fn validity(&self) -> Validity {
+        self.validity
+    }
+
+    pub fn key_usage(&self) -> KeyUsage {
+        self.key_usage
+    }
+
+    /// The algorithm of the certified key.
+    pub fn algorithm(&self) -> SignatureAlgorithm {
+        self.algorithm
+    }
+
+    /// The algorithm of the issuer's signature.
+    pub fn signature_algorithm(&self) -> SignatureAlgorithm {
+        self.signature_algorithm
+    }
+
+    pub fn public_key(&self) -> &S::PublicKey {
+        &self.public_key
+    }
+
+    pub fn signature(&self) -> &S::Signature {
+        &self.signature
+    }
+
+    /// Checks that `issuer` is named as this certificate's issuer and that
+    /// its key signed this certificate.
+    pub fn verify_issued_by(&self, scheme: &S, issuer: &Certificate<S>) -> Result<(), CertificateError> {
+        if issuer.subject != self.issuer {
+            return Err(CertificateError::IssuerMismatch(self.subject.clone()));
+        }
+        if !scheme.verify(&self.tbs, &self.signature, &issuer.public_key)? {
+            return Err(CertificateError::BadSignature);
+        }
+        Ok(())
+    }
+
+    fn check_validity(&self, time: u64) -> Result<(), CertificateError> {
+        if !self.validity.contains(time) {
+            return Err(CertificateError::OutsideValidity(self.subject.clone()));
+        }
+        Ok(())
+    }
+}
+
+/// The root certificates a chain must lead to.
+pub struct TrustAnchors<S: Verifier = Dilithium> {
+    scheme: S,
+    anchors: Vec<Certificate<S>>,
+}
+
+impl<S: Verifier> TrustAnchors<S> {
+    pub fn new(scheme: S) -> Self {
+        TrustAnchors { scheme, anchors: Vec::new() }
+    }
+
+    pub fn with_anchor(mut self, anchor: Certificate<S>) -> Self {
+        self.add(anchor);
+        self
+    }
+
+    pub fn add(&mut self, anchor: Certificate<S>) {
+        self.anchors.push(anchor);
+    }
+
+    pub fn anchors(&self) -> &[Certificate<S>] {
+        &self.anchors
+    }
+
+    /// Validates `chain` at the current time; see [`validate`](Self::validate).
+    pub fn validate_now<'c>(&self, chain: &'c [Certificate<S>]) -> Result<&'c Certificate<S>, CertificateError> {
+        self.validate(chain, unix_time())
+    }
+
+    /// Validates `chain` at `time` and returns its leaf.
+    ///
+    /// The chain starts with the leaf, which must allow
+    /// [`KeyUsage::DIGITAL_SIGNATURE`], and each following certificate
+    /// issues the one before it and must allow [`KeyUsage::CERT_SIGN`]. It
+    /// is trusted once a certificate is itself an anchor or is signed by
+    /// one, so the root may be left out. Every certificate up to and
+    /// including the anchor must be valid at `time`.
+    pub fn validate<'c>(&self, chain: &'c [Certificate<S>], time: u64) -> Result<&'c Certificate<S>, CertificateError> {
+        let leaf = chain.first().ok_or(CertificateError::EmptyChain)?;
+        if chain.len() > MAX_CHAIN_LENGTH {
+            return Err(CertificateError::ChainTooLong);
+        }
+        if !leaf.key_usage.contains(KeyUsage::DIGITAL_SIGNATURE) {
+            return Err(CertificateError::KeyUsage(leaf.subject.clone()));
+        }
+
+        for (index, cert) in chain.iter().enumerate() {
+            cert.check_validity(time)?;
+            if index > 0 && !cert.key_usage.contains(KeyUsage::CERT_SIGN) {
+                return Err(CertificateError::KeyUsage(cert.subject.clone()));
+            }
+            if self.anchors.contains(cert) {
+                return Ok(leaf);
+            }
+
+            let anchor = self.anchors.iter().find(|anchor| {
+                anchor.key_usage.contains(KeyUsage::CERT_SIGN) && cert.verify_issued_by(&self.scheme, anchor).is_ok()
+            });
+            if let Some(anchor) = anchor {
+                anchor.check_validity(time)?;
+                return Ok(leaf);
+            }
+
+            let issuer = chain.get(index + 1).ok_or(CertificateError::UntrustedChain)?;
+            cert.verify_issued_by(&self.scheme, issuer)?;
+        }
+        Err(CertificateError::UntrustedChain)
+    }
+}
+
+/// The current time in Unix seconds.
+pub(crate) fn unix_time() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn encode_tbs(
+    params: &CertificateParams,
+    issuer: &str,
+    algorithm: SignatureAlgorithm,
+    pk: &[u8],
+) -> Result<Vec<u8>, CertificateError> {
+    let mut out = Vec::with_capacity(40 + params.subject.len() + issuer.len() + pk.len());
+    out.extend_from_slice(&MAGIC);
+    out.push(VERSION);
+    out.extend_from_slice(&params.serial.to_be_bytes());
+    out.extend_from_slice(&algorithm.id().to_be_bytes());
+    out.extend_from_slice(&algorithm.id().to_be_bytes());
+    out.extend_from_slice(&params.validity.not_before.to_be_bytes());
+    out.extend_from_slice(&params.validity.not_after.to_be_bytes());
+    out.push(params.key_usage.bits());
+    put_field(&mut out, params.subject.as_bytes())?;
+    put_field(&mut out, issuer.as_bytes())?;
+    put_field(&mut out, pk)?;
+    Ok(out)
+}
+
+pub(crate) fn put_field(out: &mut Vec<u8>, field: &[u8]) -> Result<(), CertificateError> {
+    let len = u16::try_from(field.len()).map_err(|_| CertificateError::FieldTooLong)?;
+    out.extend_from_slice(&len.to_be_bytes());
+    out.extend_from_slice(field);
+    Ok(())
+}
+
+pub(crate) struct Reader<'a> {
+    pub(crate) bytes: &'a [u8],
+    pub(crate) pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn take(&mut self, len: usize) -> Result<&'a [u8], CertificateError> {
+        let end = self.pos.checked_add(len).filter(|&end| end <= self.bytes.len());
+        let end = end.ok_or(CertificateError::Truncated)?;
+        let out = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(out)
+    }
+
+    pub(crate) fn u8(&mut self) -> Result<u8, CertificateError> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub(crate) fn u16(&mut self) -> Result<u16, CertificateError> {
+        Ok(u16::from_be_bytes(self.take(2)?.try_into().expect("2 bytes")))
+    }
+
+    pub(crate) fn u64(&mut self) -> Result<u64, CertificateError> {
+        Ok(u64::from_be_bytes(self.take(8)?.try_into().expect("8 bytes")))
+    }
+
+    /// A length-prefixed byte string.
+    pub(crate) fn field(&mut self) -> Result<&'a [u8], CertificateError> {
+        let len = self.u16()? as usize;
+        self.take(len)
+    }
+
+    pub(crate) fn string(&mut self) -> Result<String, CertificateError> {
+        let field = self.field()?;
+        String::from_utf8(field.to_vec()).map_err(|_| CertificateError::Malformed)
+    }
+}
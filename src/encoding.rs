@@ -0,0 +1,412 @@
+//! SubjectPublicKeyInfo and PKCS#8 encodings (DER and PEM) for KEM and
+//! signature keys.
+//!
+//! Algorithms are identified by the NIST OIDs from the IETF LAMPS drafts
+//! (`draft-ietf-lamps-kyber-certificates`, `draft-ietf-lamps-dilithium-certificates`,
+//! `draft-ietf-lamps-x509-slhdsa`), so ML-KEM, ML-DSA and SLH-DSA keys
+//! round-trip with OpenSSL 3.5 and BoringSSL. ML-KEM and ML-DSA private keys
+//! are exported in the `expandedKey` form; on import the `seed`,
+//! `expandedKey` and `both` forms are accepted. SLH-DSA private keys have a
+//! single form, the raw key.
+//!
+//! KEM keys are encoded through [`PublicKey`] and [`SecretKey`], which
+//! carry or are given their [`KemAlgorithm`]. Signature keys are encoded
+//! through the per-parameter-set key types of [`ml_dsa`](crate::sig::ml_dsa)
+//! and [`slh_dsa`](crate::sig::slh_dsa), which refuse keys of another
+//! parameter set.
+//!
+//! Only algorithms with a registered OID can be encoded. Round-3 Kyber and
+//! the X25519 hybrids have none, and report
+//! [`EncodingError::UnsupportedAlgorithm`]. The same holds for Dilithium:
+//! it implements a pre-standard FIPS 204 draft whose keys are not
+//! byte-compatible with ML-DSA, so labelling them with the ML-DSA OIDs
+//! would produce keys other tooling silently misreads. Falcon has no OID
+//! until FIPS 206 is published.
+
+use pkcs8::der::asn1::{AnyRef, BitStringRef, OctetStringRef};
+use pkcs8::der::pem::LineEnding;
+use pkcs8::der::{Decode, Document, Encode, SecretDocument, Tag, TagNumber, Tagged};
+use pkcs8::{ObjectIdentifier, PrivateKeyInfo};
+use spki::{AlgorithmIdentifierRef, SubjectPublicKeyInfoRef};
+use subtle::ConstantTimeEq;
+use thiserror::Error;
+use zeroize::Zeroizing;
+use crate::kem::kem::{KemError, PublicKey, SecretKey};
+use crate::kem::{registry, KemAlgorithm};
+use crate::sig::ml_dsa::{
+    MlDsa44, MlDsa44PublicKey, MlDsa44SecretKey, MlDsa65, MlDsa65PublicKey, MlDsa65SecretKey, MlDsa87,
+    MlDsa87PublicKey, MlDsa87SecretKey,
+};
+use crate::sig::slh_dsa::{
+    SlhDsaSha2_128sPublicKey, SlhDsaSha2_128sSecretKey, SlhDsaSha2_128fPublicKey, SlhDsaSha2_128fSecretKey,
+    SlhDsaSha2_192sPublicKey, SlhDsaSha2_192sSecretKey, SlhDsaSha2_192fPublicKey, SlhDsaSha2_192fSecretKey,
+    SlhDsaSha2_256sPublicKey, SlhDsaSha2_256sSecretKey, SlhDsaSha2_256fPublicKey, SlhDsaSha2_256fSecretKey,
+    SlhDsaShake128sPublicKey, SlhDsaShake128sSecretKey, SlhDsaShake128fPublicKey, SlhDsaShake128fSecretKey,
+    SlhDsaShake192sPublicKey, SlhDsaShake192sSecretKey, SlhDsaShake192fPublicKey, SlhDsaShake192fSecretKey,
+    SlhDsaShake256sPublicKey, SlhDsaShake256sSecretKey, SlhDsaShake256fPublicKey, SlhDsaShake256fSecretKey,
+};
+use crate::sig::{SignatureAlgorithm, SignatureError};
+
+/// Object identifiers from the NIST Computer Security Objects Register.
+pub mod oid {
+    use pkcs8::ObjectIdentifier;
+
+    pub const ML_KEM_512: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.4.1");
+    pub const ML_KEM_768: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.4.2");
+    pub const ML_KEM_1024: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.4.3");
+    pub const ML_DSA_44: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.3.17");
+    pub const ML_DSA_65: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.3.18");
+    pub const ML_DSA_87: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.3.19");
+    pub const SLH_DSA_SHA2_128S: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.3.20");
+    pub const SLH_DSA_SHA2_128F: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.3.21");
+    pub const SLH_DSA_SHA2_192S: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.3.22");
+    pub const SLH_DSA_SHA2_192F: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.3.23");
+    pub const SLH_DSA_SHA2_256S: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.3.24");
+    pub const SLH_DSA_SHA2_256F: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.3.25");
+    pub const SLH_DSA_SHAKE_128S: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.3.26");
+    pub const SLH_DSA_SHAKE_128F: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.3.27");
+    pub const SLH_DSA_SHAKE_192S: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.3.28");
+    pub const SLH_DSA_SHAKE_192F: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.3.29");
+    pub const SLH_DSA_SHAKE_256S: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.3.30");
+    pub const SLH_DSA_SHAKE_256F: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.3.31");
+}
+
+const PUBLIC_KEY_LABEL: &str = "PUBLIC KEY";
+const PRIVATE_KEY_LABEL: &str = "PRIVATE KEY";
+
+#[derive(Debug, Error)]
+pub enum EncodingError {
+    #[error("Malformed DER or PEM: {0}")]
+    Der(#[from] pkcs8::der::Error),
+    #[error("Unexpected PEM label: {0}")]
+    UnexpectedLabel(String),
+    #[error("{0} has no registered OID")]
+    UnsupportedAlgorithm(String),
+    #[error("Unknown algorithm OID: {0}")]
+    UnknownOid(ObjectIdentifier),
+    #[error("Public key is not tagged with an algorithm")]
+    MissingAlgorithm,
+    #[error("Invalid key length")]
+    InvalidKeyLength,
+    #[error("Private key seed does not match the expanded key")]
+    InconsistentKey,
+    #[error("Expected a {expected} key, found OID {found}")]
+    WrongAlgorithm { expected: SignatureAlgorithm, found: ObjectIdentifier },
+    #[error("Key generation from seed failed: {0}")]
+    Kem(#[from] KemError),
+    #[error("Key generation from seed failed: {0}")]
+    Signature(#[from] SignatureError),
+}
+
+impl KemAlgorithm {
+    /// The algorithm's OID, if one has been registered.
+    pub fn oid(self) -> Option<ObjectIdentifier> {
+        match self {
+            KemAlgorithm::MlKem512 => Some(oid::ML_KEM_512),
+            KemAlgorithm::MlKem768 => Some(oid::ML_KEM_768),
+            KemAlgorithm::MlKem1024 => Some(oid::ML_KEM_1024),
+            _ => None,
+        }
+    }
+
+    /// Looks up an algorithm by its OID.
+    pub fn from_oid(oid: ObjectIdentifier) -> Option<Self> {
+        Self::ALL.into_iter().find(|alg| alg.oid() == Some(oid))
+    }
+}
+
+impl SignatureAlgorithm {
+    /// The algorithm's OID, if one has been registered.
+    pub fn oid(self) -> Option<ObjectIdentifier> {
+        match self {
+            SignatureAlgorithm::MlDsa44 => Some(oid::ML_DSA_44),
+            SignatureAlgorithm::MlDsa65 => Some(oid::ML_DSA_65),
+            SignatureAlgorithm::MlDsa87 => Some(oid::ML_DSA_87),
+            SignatureAlgorithm::SlhDsaSha2_128s => Some(oid::SLH_DSA_SHA2_128S),
+            SignatureAlgorithm::SlhDsaSha2_128f => Some(oid::SLH_DSA_SHA2_128F),
+            SignatureAlgorithm::SlhDsaSha2_192s => Some(oid::SLH_DSA_SHA2_192S),
+            SignatureAlgorithm::SlhDsaSha2_192f => Some(oid::SLH_DSA_SHA2_192F),
+            SignatureAlgorithm::SlhDsaSha2_256s => Some(oid::SLH_DSA_SHA2_256S),
+            SignatureAlgorithm::SlhDsaSha2_256f => Some(oid::SLH_DSA_SHA2_256F),
+            SignatureAlgorithm::SlhDsaShake128s => Some(oid::SLH_DSA_SHAKE_128S),
+            SignatureAlgorithm::SlhDsaShake128f => Some(oid::SLH_DSA_SHAKE_128F),
+            SignatureAlgorithm::SlhDsaShake192s => Some(oid::SLH_DSA_SHAKE_192S),
+            SignatureAlgorithm::SlhDsaShake192f => Some(oid::SLH_DSA_SHAKE_192F),
+            SignatureAlgorithm::SlhDsaShake256s => Some(oid::SLH_DSA_SHAKE_256S),
+            SignatureAlgorithm::SlhDsaShake256f => Some(oid::SLH_DSA_SHAKE_256F),
+            SignatureAlgorithm::Dilithium2 | SignatureAlgorithm::Dilithium3 | SignatureAlgorithm::Dilithium5 => None,
+            SignatureAlgorithm::Falcon512 | SignatureAlgorithm::Falcon1024 => None,
+            SignatureAlgorithm::Dilithium2Ed25519 | SignatureAlgorithm::Dilithium3Ed25519 => None,
+        }
+    }
+
+    /// Looks up an algorithm by its OID.
+    pub fn from_oid(oid: ObjectIdentifier) -> Option<Self> {
+        Self::ALL.into_iter().find(|alg| alg.oid() == Some(oid))
+    }
+}
+
+fn kem_oid(algorithm: KemAlgorithm) -> Result<ObjectIdentifier, EncodingError> {
+    algorithm.oid().ok_or_else(|| EncodingError::UnsupportedAlgorithm(algorithm.to_string()))
+}
+
+fn signature_oid(algorithm: SignatureAlgorithm) -> Result<ObjectIdentifier, EncodingError> {
+    algorithm.oid().ok_or_else(|| EncodingError::UnsupportedAlgorithm(algorithm.to_string()))
+}
+
+fn check_signature_oid(algorithm: SignatureAlgorithm, found: ObjectIdentifier) -> Result<(), EncodingError> {
+    if signature_oid(algorithm)? != found {
+        return Err(EncodingError::WrongAlgorithm { expected: algorithm, found });
+    }
+    Ok(())
+}
+
+/// Encodes a signature public key of `algorithm` as a DER
+/// SubjectPublicKeyInfo. Also used for the keys certified in [`crate::x509`].
+pub(crate) fn signature_public_key_der(algorithm: SignatureAlgorithm, key: &[u8]) -> Result<Vec<u8>, EncodingError> {
+    encode_spki(signature_oid(algorithm)?, key)
+}
+
+fn encode_spki(oid: ObjectIdentifier, key: &[u8]) -> Result<Vec<u8>, EncodingError> {
+    let spki = SubjectPublicKeyInfoRef {
+        algorithm: AlgorithmIdentifierRef { oid, parameters: None },
+        subject_public_key: BitStringRef::from_bytes(key)?,
+    };
+    Ok(spki.to_der()?)
+}
+
+fn decode_spki(der: &[u8]) -> Result<(ObjectIdentifier, &[u8]), EncodingError> {
+    let spki = SubjectPublicKeyInfoRef::from_der(der)?;
+    let key = spki.subject_public_key.as_bytes().ok_or(EncodingError::InvalidKeyLength)?;
+    Ok((spki.algorithm.oid, key))
+}
+
+fn encode_pkcs8(oid: ObjectIdentifier, private_key: &[u8]) -> Result<Zeroizing<Vec<u8>>, EncodingError> {
+    let info = PrivateKeyInfo::new(AlgorithmIdentifierRef { oid, parameters: None }, private_key);
+    Ok(Zeroizing::new(info.to_der()?))
+}
+
+fn public_pem(der: Vec<u8>) -> Result<String, EncodingError> {
+    Ok(Document::try_from(der)?.to_pem(PUBLIC_KEY_LABEL, LineEnding::LF)?)
+}
+
+fn public_der_from_pem(pem: &str) -> Result<Document, EncodingError> {
+    let (label, doc) = Document::from_pem(pem)?;
+    if label != PUBLIC_KEY_LABEL {
+        return Err(EncodingError::UnexpectedLabel(label.to_string()));
+    }
+    Ok(doc)
+}
+
+fn private_pem(der: &[u8]) -> Result<Zeroizing<String>, EncodingError> {
+    Ok(SecretDocument::try_from(der)?.to_pem(PRIVATE_KEY_LABEL, LineEnding::LF)?)
+}
+
+fn private_der_from_pem(pem: &str) -> Result<SecretDocument, EncodingError> {
+    let (label, doc) = SecretDocument::from_pem(pem)?;
+    if label != PRIVATE_KEY_LABEL {
+        return Err(EncodingError::UnexpectedLabel(label.to_string()));
+    }
+    Ok(doc)
+}
+
+/// The three forms of a FIPS 203/204 private key:
+/// `CHOICE { seed [0] OCTET STRING, expandedKey OCTET STRING, both SEQUENCE { seed, expandedKey } }`.
+enum PrivateKeyForm<'a> {
+    Seed(&'a [u8]),
+    Expanded(&'a [u8]),
+    Both(&'a [u8], &'a [u8]),
+}
+
+fn parse_private_key_form(bytes: &[u8]) -> Result<PrivateKeyForm<'_>, EncodingError> {
+    let any = AnyRef::from_der(bytes)?;
+    let seed_tag = Tag::ContextSpecific { constructed: false, number: TagNumber::N0 };
+    match any.tag() {
+        tag if tag == seed_tag => Ok(PrivateKeyForm::Seed(any.value())),
+        Tag::OctetString => Ok(PrivateKeyForm::Expanded(any.value())),
+        Tag::Sequence => Ok(any.sequence(|reader| {
+            let seed = OctetStringRef::decode(reader)?;
+            let expanded = OctetStringRef::decode(reader)?;
+            Ok(PrivateKeyForm::Both(seed.as_bytes(), expanded.as_bytes()))
+        })?),
+        tag => Err(tag.value_error().into()),
+    }
+}
+
+impl PublicKey {
+    /// Encodes the key as a DER SubjectPublicKeyInfo. The key must be tagged
+    /// with an algorithm that has an OID.
+    pub fn to_public_key_der(&self) -> Result<Vec<u8>, EncodingError> {
+        let algorithm = self.algorithm().ok_or(EncodingError::MissingAlgorithm)?;
+        encode_spki(kem_oid(algorithm)?, self.as_ref())
+    }
+
+    /// Encodes the key as a `PUBLIC KEY` PEM document.
+    pub fn to_public_key_pem(&self) -> Result<String, EncodingError> {
+        public_pem(self.to_public_key_der()?)
+    }
+
+    /// Decodes a DER SubjectPublicKeyInfo into a tagged key.
+    pub fn from_public_key_der(der: &[u8]) -> Result<Self, EncodingError> {
+        let (oid, key) = decode_spki(der)?;
+        let algorithm = KemAlgorithm::from_oid(oid).ok_or(EncodingError::UnknownOid(oid))?;
+        if key.len() != registry::new_kem(algorithm).public_key_bytes() {
+            return Err(EncodingError::InvalidKeyLength);
+        }
+        Ok(PublicKey::tagged(algorithm, key.to_vec()))
+    }
+
+    /// Decodes a `PUBLIC KEY` PEM document.
+    pub fn from_public_key_pem(pem: &str) -> Result<Self, EncodingError> {
+        Self::from_public_key_der(public_der_from_pem(pem)?.as_bytes())
+    }
+}
+
+impl SecretKey {
+    /// Encodes the key as a DER PKCS#8 `PrivateKeyInfo` in the
+    /// `expandedKey` form. Secret keys are not tagged, so the caller names
+    /// the algorithm.
+    pub fn to_pkcs8_der(&self, algorithm: KemAlgorithm) -> Result<Zeroizing<Vec<u8>>, EncodingError> {
+        let oid = kem_oid(algorithm)?;
+        if self.as_ref().len() != registry::new_kem(algorithm).secret_key_bytes() {
+            return Err(EncodingError::InvalidKeyLength);
+        }
+        let expanded = Zeroizing::new(OctetStringRef::new(self.as_ref())?.to_der()?);
+        encode_pkcs8(oid, &expanded)
+    }
+
+    /// Encodes the key as a `PRIVATE KEY` PEM document.
+    pub fn to_pkcs8_pem(&self, algorithm: KemAlgorithm) -> Result<Zeroizing<String>, EncodingError> {
+        private_pem(&self.to_pkcs8_der(algorithm)?)
+    }
+
+    /// Decodes a DER PKCS#8 `PrivateKeyInfo`, returning the algorithm with
+    /// the key. Seed-only keys are expanded; keys carrying both a seed and
+    /// an expanded key must agree.
+    pub fn from_pkcs8_der(der: &[u8]) -> Result<(KemAlgorithm, Self), EncodingError> {
+        let info = PrivateKeyInfo::from_der(der)?;
+        let oid = info.algorithm.oid;
+        let algorithm = KemAlgorithm::from_oid(oid).ok_or(EncodingError::UnknownOid(oid))?;
+        let kem = registry::new_kem(algorithm);
+
+        let sk = match parse_private_key_form(info.private_key)? {
+            PrivateKeyForm::Seed(seed) => kem.keygen_from_seed(seed)?.1,
+            PrivateKeyForm::Expanded(expanded) => SecretKey::from_vec(expanded.to_vec()),
+            PrivateKeyForm::Both(seed, expanded) => {
+                let (_, sk) = kem.keygen_from_seed(seed)?;
+                if !bool::from(sk.as_ref().ct_eq(expanded)) {
+                    return Err(EncodingError::InconsistentKey);
+                }
+                sk
+            }
+        };
+        if sk.as_ref().len() != kem.secret_key_bytes() {
+            return Err(EncodingError::InvalidKeyLength);
+        }
+        Ok((algorithm, sk))
+    }
+
+    /// Decodes a `PRIVATE KEY` PEM document.
+    pub fn from_pkcs8_pem(pem: &str) -> Result<(KemAlgorithm, Self), EncodingError> {
+        Self::from_pkcs8_der(private_der_from_pem(pem)?.as_bytes())
+    }
+}
+
+/// Adds SPKI and PKCS#8 encodings to one signature parameter set's key
+/// types. `expanded` keys are wrapped in the FIPS 204 private key `CHOICE`,
+/// whose `seed` form is expanded with `$scheme::keygen_from_seed`; `raw` keys
+/// are the PKCS#8 private key as is.
+macro_rules! signature_key_encoding {
+    ($scheme:ident, $pk:ident, $sk:ident, $algorithm:expr, private_key: $form:ident) => {
+        impl $pk {
+            /// Encodes the key as a DER SubjectPublicKeyInfo.
+            pub fn to_public_key_der(&self) -> Result<Vec<u8>, EncodingError> {
+                signature_public_key_der($algorithm, self.as_bytes())
+            }
+
+            /// Encodes the key as a `PUBLIC KEY` PEM document.
+            pub fn to_public_key_pem(&self) -> Result<String, EncodingError> {
+                public_pem(self.to_public_key_der()?)
+            }
+
+            /// Decodes a DER SubjectPublicKeyInfo, which must be for this
+            /// parameter set.
+            pub fn from_public_key_der(der: &[u8]) -> Result<Self, EncodingError> {
+                let (oid, key) = decode_spki(der)?;
+                check_signature_oid($algorithm, oid)?;
+                Self::from_bytes(key).map_err(|_| EncodingError::InvalidKeyLength)
+            }
+
+            /// Decodes a `PUBLIC KEY` PEM document.
+            pub fn from_public_key_pem(pem: &str) -> Result<Self, EncodingError> {
+                Self::from_public_key_der(public_der_from_pem(pem)?.as_bytes())
+            }
+        }
+
+        impl $sk {
+            /// Encodes the key as a DER PKCS#8 `PrivateKeyInfo`.
+            pub fn to_pkcs8_der(&self) -> Result<Zeroizing<Vec<u8>>, EncodingError> {
+                let oid = signature_oid($algorithm)?;
+                signature_key_encoding!(@encode $form, oid, self.as_bytes())
+            }
+
+            /// Encodes the key as a `PRIVATE KEY` PEM document.
+            pub fn to_pkcs8_pem(&self) -> Result<Zeroizing<String>, EncodingError> {
+                private_pem(&self.to_pkcs8_der()?)
+            }
+
+            /// Decodes a DER PKCS#8 `PrivateKeyInfo`, which must be for this
+            /// parameter set.
+            pub fn from_pkcs8_der(der: &[u8]) -> Result<Self, EncodingError> {
+                let info = PrivateKeyInfo::from_der(der)?;
+                check_signature_oid($algorithm, info.algorithm.oid)?;
+                signature_key_encoding!(@decode $form, $scheme, info.private_key)
+            }
+
+            /// Decodes a `PRIVATE KEY` PEM document.
+            pub fn from_pkcs8_pem(pem: &str) -> Result<Self, EncodingError> {
+                Self::from_pkcs8_der(private_der_from_pem(pem)?.as_bytes())
+            }
+        }
+    };
+    (@encode expanded, $oid:expr, $key:expr) => {{
+        let expanded = Zeroizing::new(OctetStringRef::new($key)?.to_der()?);
+        encode_pkcs8($oid, &expanded)
+    }};
+    (@encode raw, $oid:expr, $key:expr) => {
+        encode_pkcs8($oid, $key)
+    };
+    (@decode expanded, $scheme:ident, $private_key:expr) => {{
+        let expanded = |bytes: &[u8]| Self::from_bytes(bytes).map_err(|_| EncodingError::InvalidKeyLength);
+        match parse_private_key_form($private_key)? {
+            PrivateKeyForm::Seed(seed) => Ok($scheme::new().keygen_from_seed(seed)?.1),
+            PrivateKeyForm::Expanded(bytes) => expanded(bytes),
+            PrivateKeyForm::Both(seed, bytes) => {
+                let (_, sk) = $scheme::new().keygen_from_seed(seed)?;
+                if !bool::from(sk.as_bytes().ct_eq(bytes)) {
+                    return Err(EncodingError::InconsistentKey);
+                }
+                Ok(sk)
+            }
+        }
+    }};
+    (@decode raw, $scheme:ident, $private_key:expr) => {
+        Self::from_bytes($private_key).map_err(|_| EncodingError::InvalidKeyLength)
+    };
+}
+
+signature_key_encoding!(MlDsa44, MlDsa44PublicKey, MlDsa44SecretKey, SignatureAlgorithm::MlDsa44, private_key: expanded);
+signature_key_encoding!(MlDsa65, MlDsa65PublicKey, MlDsa65SecretKey, SignatureAlgorithm::MlDsa65, private_key: expanded);
+signature_key_encoding!(MlDsa87, MlDsa87PublicKey, MlDsa87SecretKey, SignatureAlgorithm::MlDsa87, private_key: expanded);
+signature_key_encoding!(SlhDsaSha2_128s, SlhDsaSha2_128sPublicKey, SlhDsaSha2_128sSecretKey, SignatureAlgorithm::SlhDsaSha2_128s, private_key: raw);
+signature_key_encoding!(SlhDsaSha2_128f, SlhDsaSha2_128fPublicKey, SlhDsaSha2_128fSecretKey, SignatureAlgorithm::SlhDsaSha2_128f, private_key: raw);
+signature_key_encoding!(SlhDsaSha2_192s, SlhDsaSha2_192sPublicKey, SlhDsaSha2_192sSecretKey, SignatureAlgorithm::SlhDsaSha2_192s, private_key: raw);
+signature_key_encoding!(SlhDsaSha2_192f, SlhDsaSha2_192fPublicKey, SlhDsaSha2_192fSecretKey, SignatureAlgorithm::SlhDsaSha2_192f, private_key: raw);
+signature_key_encoding!(SlhDsaSha2_256s, SlhDsaSha2_256sPublicKey, SlhDsaSha2_256sSecretKey, SignatureAlgorithm::SlhDsaSha2_256s, private_key: raw);
+signature_key_encoding!(SlhDsaSha2_256f, SlhDsaSha2_256fPublicKey, SlhDsaSha2_256fSecretKey, SignatureAlgorithm::SlhDsaSha2_256f, private_key: raw);
+signature_key_encoding!(SlhDsaShake128s, SlhDsaShake128sPublicKey, SlhDsaShake128sSecretKey, SignatureAlgorithm::SlhDsaShake128s, private_key: raw);
+signature_key_encoding!(SlhDsaShake128f, SlhDsaShake128fPublicKey, SlhDsaShake128fSecretKey, SignatureAlgorithm::SlhDsaShake128f, private_key: raw);
+signature_key_encoding!(SlhDsaShake192s, SlhDsaShake192sPublicKey, SlhDsaShake192sSecretKey, SignatureAlgorithm::SlhDsaShake192s, private_key: raw);
+signature_key_encoding!(SlhDsaShake192f, SlhDsaShake192fPublicKey, SlhDsaShake192fSecretKey, SignatureAlgorithm::SlhDsaShake192f, private_key: raw);
+signature_key_encoding!(SlhDsaShake256s, SlhDsaShake256sPublicKey, SlhDsaShake256sSecretKey, SignatureAlgorithm::SlhDsaShake256s, private_key: raw);
+signature_key_encoding!(SlhDsaShake256f, SlhDsaShake256fPublicKey, SlhDsaShake256fSecretKey, SignatureAlgorithm::SlhDsaShake256f, private_key: raw);
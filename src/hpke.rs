@@ -0,0 +1,605 @@
+//! Hybrid Public Key Encryption (RFC 9180) over the crate's KEMs.
+//!
+//! Implements the four HPKE modes (Base, PSK, Auth, AuthPSK), the labeled
+//! key schedule, secret export and multi-message contexts whose nonces are
+//! derived from a per-context sequence number. The KDF is always
+//! HKDF-SHA256; the AEAD is AES-128-GCM, AES-256-GCM or export-only.
+//!
+//! KEMs ([`HpkeKem`]):
+//! - `DHKEM(X25519, HKDF-SHA256)` (`0x0020`), for interop with classical
+//!   HPKE peers. Checked against the RFC 9180 test vectors.
+//! - ML-KEM-512/768/1024 under their IANA codepoints `0x0040`-`0x0042`; the
+//!   encapsulation is the raw ML-KEM ciphertext and shared secret.
+//! - Round-3 Kyber and the X25519 hybrids, which have no IANA codepoint.
+//!   They use `0xF000 | KemAlgorithm::id()` and only interoperate with
+//!   PQ-Core.
+//!
+//! Auth and AuthPSK need a KEM with `AuthEncap`. Post-quantum KEMs have
+//! none, so those modes are only offered by the X25519 DHKEM and by the
+//! hybrids, where the sender's static X25519 key is mixed into the shared
+//! secret as in DHKEM: sender authentication is then classical only, while
+//! confidentiality keeps the hybrid guarantee.
+//!
+//! ```
+//! use pq_core::hpke::{Hpke, HpkeAead, HpkeKem, Psk};
+//! use pq_core::kem::KemAlgorithm;
+//!
+//! let hpke = Hpke::new(HpkeKem::Pq(KemAlgorithm::X25519Kyber512), HpkeAead::Aes256Gcm);
+//! let (pk_r, sk_r) = hpke.generate_key_pair().unwrap();
+//! let psk = Psk { psk: &[7u8; 32], id: b"2024-q3" };
+//!
+//! let (enc, mut sender) = hpke.setup_sender(&pk_r, b"app v1", Some(psk), None).unwrap();
+//! let mut recipient = hpke.setup_recipient(&enc, &sk_r, b"app v1", Some(psk), None).unwrap();
+//!
+//! let ct = sender.seal(b"header", b"first message").unwrap();
+//! assert_eq!(recipient.open(b"header", &ct).unwrap(), b"first message");
+//! assert_eq!(sender.export(b"exporter", 32).unwrap(), recipient.export(b"exporter", 32).unwrap());
+//! ```
+
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes128Gcm, Aes256Gcm};
+use hkdf::{Hkdf, HkdfExtract};
+use rand::rngs::OsRng;
+use sha2::Sha256;
+use thiserror::Error;
+use x25519_dalek::{PublicKey as X25519PublicKey, StaticSecret};
+use zeroize::Zeroizing;
+use crate::kem::kem::{Ciphertext, KemError, PublicKey, SecretKey};
+use crate::kem::{registry, Kem, KemAlgorithm};
+
+/// HKDF-SHA256, the only KDF offered.
+pub const KDF_HKDF_SHA256: u16 = 0x0001;
+
+const VERSION_LABEL: &[u8] = b"HPKE-v1";
+const NH: usize = 32;
+const NN: usize = 12;
+const X25519_BYTES: usize = 32;
+
+/// The HPKE mode, fixed by which optional inputs are given to the setup
+/// functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Base = 0x00,
+    Psk = 0x01,
+    Auth = 0x02,
+    AuthPsk = 0x03,
+}
+
+/// The KEM of an HPKE suite.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HpkeKem {
+    /// DHKEM(X25519, HKDF-SHA256)
+    X25519HkdfSha256,
+    /// One of the crate's KEMs
+    Pq(KemAlgorithm),
+}
+
+impl HpkeKem {
+    /// The HPKE KEM identifier.
+    pub const fn id(self) -> u16 {
+        match self {
+            HpkeKem::X25519HkdfSha256 => 0x0020,
+            HpkeKem::Pq(KemAlgorithm::MlKem512) => 0x0040,
+            HpkeKem::Pq(KemAlgorithm::MlKem768) => 0x0041,
+            HpkeKem::Pq(KemAlgorithm::MlKem1024) => 0x0042,
+            HpkeKem::Pq(algorithm) => 0xF000 | algorithm.id(),
+        }
+    }
+
+    /// Looks up a KEM by its HPKE identifier.
+    pub fn from_id(id: u16) -> Option<Self> {
+        std::iter::once(HpkeKem::X25519HkdfSha256)
+            .chain(KemAlgorithm::ALL.into_iter().map(HpkeKem::Pq))
+            .find(|kem| kem.id() == id)
+    }
+
+    /// Whether the KEM supports the Auth and AuthPSK modes.
+    pub fn supports_auth(self) -> bool {
+        match self {
+            HpkeKem::X25519HkdfSha256 => true,
+            HpkeKem::Pq(algorithm) => is_hybrid(algorithm),
+        }
+    }
+}
+
+impl std::fmt::Display for HpkeKem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HpkeKem::X25519HkdfSha256 => f.write_str("DHKEM(X25519, HKDF-SHA256)"),
+            HpkeKem::Pq(algorithm) => write!(f, "{algorithm}"),
+        }
+    }
+}
+
+/// The AEAD of an HPKE suite.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HpkeAead {
+    Aes128Gcm,
+    Aes256Gcm,
+    /// No encryption; the context can only [`export`](SenderContext::export).
+    ExportOnly,
+}
+
+impl HpkeAead {
+    /// The HPKE AEAD identifier.
+    pub const fn id(self) -> u16 {
+        match self {
+            HpkeAead::Aes128Gcm => 0x0001,
+            HpkeAead::Aes256Gcm => 0x0002,
+            HpkeAead::ExportOnly => 0xFFFF,
+        }
+    }
+
+    /// Looks up an AEAD by its HPKE identifier.
+    pub fn from_id(id: u16) -> Option<Self> {
+        [HpkeAead::Aes128Gcm, HpkeAead::Aes256Gcm, HpkeAead::ExportOnly]
+            .into_iter()
+            .find(|aead| aead.id() == id)
+    }
+
+    const fn key_bytes(self) -> usize {
+        match self {
+            HpkeAead::Aes128Gcm => 16,
+            HpkeAead::Aes256Gcm => 32,
+            HpkeAead::ExportOnly => 0,
+        }
+    }
+}
+
+/// A pre-shared key and its identifier, for the PSK and AuthPSK modes.
+#[derive(Clone, Copy)]
+pub struct Psk<'a> {
+    pub psk: &'a [u8],
+    pub id: &'a [u8],
+}
+
+#[derive(Debug, Error)]
+pub enum HpkeError {
+    #[error("PSK and PSK ID must both be non-empty")]
+    InvalidPsk,
+    #[error("{0} does not support the Auth modes")]
+    AuthUnsupported(HpkeKem),
+    #[error("Invalid key or encapsulation length")]
+    InvalidLength,
+    #[error("Diffie-Hellman produced the all-zero output")]
+    InvalidDhOutput,
+    #[error("Encryption failed")]
+    SealError,
+    #[error("Decryption failed")]
+    OpenError,
+    #[error("Sequence number overflow")]
+    MessageLimitReached,
+    #[error("Export-only contexts cannot seal or open")]
+    ExportOnly,
+    #[error("Requested export length is too long")]
+    ExportTooLong,
+    #[error("KEM operation failed: {0}")]
+    Kem(#[from] KemError),
+}
+
+/// An HPKE ciphersuite: a KEM and an AEAD, with HKDF-SHA256.
+#[derive(Debug, Clone, Copy)]
+pub struct Hpke {
+    kem: HpkeKem,
+    aead: HpkeAead,
+}
+
+impl Hpke {
+    pub fn new(kem: HpkeKem, aead: HpkeAead) -> Self {
+        Self { kem, aead }
+    }
+
+    pub fn kem(&self) -> HpkeKem {
+        self.kem
+    }
+
+    pub fn aead(&self) -> HpkeAead {
+        self.aead
+    }
+
+    /// Generates a recipient (or Auth-mode sender) keypair.
+    pub fn generate_key_pair(&self) -> Result<(PublicKey, SecretKey), HpkeError> {
+        match self.kem {
+            HpkeKem::X25519HkdfSha256 => Ok(x25519_key_pair(StaticSecret::random_from_rng(OsRng))),
+            HpkeKem::Pq(algorithm) => Ok(registry::new_kem(algorithm).keygen()?),
+        }
+    }
+
+    /// Derives a keypair from `ikm` with `DeriveKeyPair`:
+    /// `dkp_prk = LabeledExtract("", "dkp_prk", ikm)`, then
+    /// `LabeledExpand(dkp_prk, "sk", "", n)`. For the X25519 DHKEM the output
+    /// is the secret key (RFC 9180); for the crate's KEMs it is the
+    /// [`Kem::keygen_from_seed`] seed, as in the ML-KEM HPKE draft. `ikm` may
+    /// be any length, but should hold at least as much entropy as the secret
+    /// key.
+    pub fn derive_key_pair(&self, ikm: &[u8]) -> Result<(PublicKey, SecretKey), HpkeError> {
+        let suite_id = kem_suite_id(self.kem);
+        let dkp_prk = labeled_extract(&suite_id, b"", b"dkp_prk", &[ikm]);
+        match self.kem {
+            HpkeKem::X25519HkdfSha256 => {
+                let mut sk = Zeroizing::new([0u8; X25519_BYTES]);
+                labeled_expand(&suite_id, dkp_prk.as_ref(), b"sk", &[], sk.as_mut())?;
+                Ok(x25519_key_pair(StaticSecret::from(*sk)))
+            }
+            HpkeKem::Pq(algorithm) => {
+                let kem = registry::new_kem(algorithm);
+                let seed_len = kem.keygen_seed_bytes().ok_or(KemError::DeterministicUnsupported(algorithm))?;
+                let mut seed = Zeroizing::new(vec![0u8; seed_len]);
+                labeled_expand(&suite_id, dkp_prk.as_ref(), b"sk", &[], &mut seed)?;
+                Ok(kem.keygen_from_seed(&seed)?)
+            }
+        }
+    }
+
+    /// Sets up a sender context for `pk_r`, returning the encapsulation to
+    /// send alongside it. Passing `psk` selects a PSK mode; passing the
+    /// sender's secret key `sk_s` selects an Auth mode.
+    pub fn setup_sender(
+        &self,
+        pk_r: &PublicKey,
+        info: &[u8],
+        psk: Option<Psk<'_>>,
+        sk_s: Option<&SecretKey>,
+    ) -> Result<(Vec<u8>, SenderContext), HpkeError> {
+        self.setup_sender_inner(pk_r, info, psk, sk_s, None)
+    }
+
+    /// Like [`setup_sender`](Self::setup_sender), with the ephemeral
+    /// randomness fixed: `ikm_e` is the `DeriveKeyPair` input for the X25519
+    /// DHKEM and the encapsulation coins for the crate's KEMs. Only for test
+    /// vectors; reusing `ikm_e` breaks security.
+    pub fn setup_sender_deterministic(
+        &self,
+        pk_r: &PublicKey,
+        info: &[u8],
+        psk: Option<Psk<'_>>,
+        sk_s: Option<&SecretKey>,
+        ikm_e: &[u8],
+    ) -> Result<(Vec<u8>, SenderContext), HpkeError> {
+        self.setup_sender_inner(pk_r, info, psk, sk_s, Some(ikm_e))
+    }
+
+    /// Sets up a recipient context from the sender's encapsulation `enc`.
+    /// `psk` and the sender's public key `pk_s` must match what the sender
+    /// used.
+    pub fn setup_recipient(
+        &self,
+        enc: &[u8],
+        sk_r: &SecretKey,
+        info: &[u8],
+        psk: Option<Psk<'_>>,
+        pk_s: Option<&PublicKey>,
+    ) -> Result<RecipientContext, HpkeError> {
+        let mode = mode_for(psk.is_some(), pk_s.is_some());
+        let shared_secret = match pk_s {
+            None => self.decap(enc, sk_r, None)?,
+            Some(pk_s) => {
+                self.check_auth()?;
+                self.decap(enc, sk_r, Some(pk_s.as_ref()))?
+            }
+        };
+        Ok(RecipientContext(self.key_schedule(mode, &shared_secret, info, psk)?))
+    }
+
+    fn setup_sender_inner(
+        &self,
+        pk_r: &PublicKey,
+        info: &[u8],
+        psk: Option<Psk<'_>>,
+        sk_s: Option<&SecretKey>,
+        ikm_e: Option<&[u8]>,
+    ) -> Result<(Vec<u8>, SenderContext), HpkeError> {
+        let mode = mode_for(psk.is_some(), sk_s.is_some());
+        if sk_s.is_some() {
+            self.check_auth()?;
+        }
+        let (shared_secret, enc) = self.encap(pk_r, sk_s, ikm_e)?;
+        Ok((enc, SenderContext(self.key_schedule(mode, &shared_secret, info, psk)?)))
+    }
+
+    fn check_auth(&self) -> Result<(), HpkeError> {
+        if self.kem.supports_auth() { Ok(()) } else { Err(HpkeError::AuthUnsupported(self.kem)) }
+    }
+
+    /// `Encap` / `AuthEncap`, returning `(shared_secret, enc)`.
+    fn encap(
+        &self,
+        pk_r: &PublicKey,
+        sk_s: Option<&SecretKey>,
+        ikm_e: Option<&[u8]>,
+    ) -> Result<(Zeroizing<Vec<u8>>, Vec<u8>), HpkeError> {
+        match self.kem {
+            HpkeKem::X25519HkdfSha256 => {
+                let (pk_e, sk_e) = match ikm_e {
+                    Some(ikm) => self.derive_key_pair(ikm)?,
+                    None => self.generate_key_pair()?,
+                };
+                let pk_r = x25519_bytes(pk_r.as_ref())?;
+                let mut dh = Zeroizing::new(x25519(sk_e.as_ref(), &pk_r)?.to_vec());
+                let mut kem_context = [pk_e.as_ref(), &pk_r[..]].concat();
+                if let Some(sk_s) = sk_s {
+                    dh.extend_from_slice(x25519(sk_s.as_ref(), &pk_r)?.as_ref());
+                    kem_context.extend_from_slice(x25519_public(sk_s.as_ref())?.as_bytes());
+                }
+                let shared_secret = self.extract_and_expand(&dh, &kem_context)?;
+                Ok((shared_secret, pk_e.as_ref().to_vec()))
+            }
+            HpkeKem::Pq(algorithm) => {
+                let kem = registry::new_kem(algorithm);
+                let (ct, ss) = match ikm_e {
+                    Some(coins) => kem.encaps_deterministic(pk_r, coins)?,
+                    None => kem.encaps(pk_r)?,
+                };
+                let enc = ct.as_ref().to_vec();
+                let Some(sk_s) = sk_s else {
+                    return Ok((Zeroizing::new(ss.as_ref().to_vec()), enc));
+                };
+                let pk_s = hybrid_public_key(kem.as_ref(), sk_s)?;
+                let dh = x25519(&sk_s.as_ref()[..X25519_BYTES], &x25519_bytes(&pk_r.as_ref()[..X25519_BYTES])?)?;
+                let ikm = Zeroizing::new([ss.as_ref(), &dh[..]].concat());
+                let kem_context = [&enc[..], pk_r.as_ref(), &pk_s].concat();
+                Ok((self.extract_and_expand(&ikm, &kem_context)?, enc))
+            }
+        }
+    }
+
+    /// `Decap` / `AuthDecap`.
+    fn decap(&self, enc: &[u8], sk_r: &SecretKey, pk_s: Option<&[u8]>) -> Result<Zeroizing<Vec<u8>>, HpkeError> {
+        match self.kem {
+            HpkeKem::X25519HkdfSha256 => {
+                let pk_e = x25519_bytes(enc)?;
+                let pk_r = x25519_public(sk_r.as_ref())?;
+                let mut dh = Zeroizing::new(x25519(sk_r.as_ref(), &pk_e)?.to_vec());
+                let mut kem_context = [enc, pk_r.as_bytes()].concat();
+                if let Some(pk_s) = pk_s {
+                    dh.extend_from_slice(x25519(sk_r.as_ref(), &x25519_bytes(pk_s)?)?.as_ref());
+                    kem_context.extend_from_slice(pk_s);
+                }
+                self.extract_and_expand(&dh, &kem_context)
+            }
+            HpkeKem::Pq(algorithm) => {
+                let kem = registry::new_kem(algorithm);
+                if enc.len() != kem.ciphertext_bytes() {
+                    return Err(HpkeError::InvalidLength);
+                }
+                let ss = kem.decaps(&Ciphertext::from_vec(enc.to_vec()), sk_r)?;
+                let Some(pk_s) = pk_s else {
+                    return Ok(Zeroizing::new(ss.as_ref().to_vec()));
+                };
+                if pk_s.len() != kem.public_key_bytes() {
+                    return Err(HpkeError::InvalidLength);
+                }
+                let pk_r = hybrid_public_key(kem.as_ref(), sk_r)?;
+                let dh = x25519(&sk_r.as_ref()[..X25519_BYTES], &x25519_bytes(&pk_s[..X25519_BYTES])?)?;
+                let ikm = Zeroizing::new([ss.as_ref(), &dh[..]].concat());
+                let kem_context = [enc, &pk_r, pk_s].concat();
+                self.extract_and_expand(&ikm, &kem_context)
+            }
+        }
+    }
+
+    /// DHKEM's `ExtractAndExpand`, keyed under the KEM suite ID.
+    fn extract_and_expand(&self, dh: &[u8], kem_context: &[u8]) -> Result<Zeroizing<Vec<u8>>, HpkeError> {
+        let suite_id = kem_suite_id(self.kem);
+        let eae_prk = labeled_extract(&suite_id, b"", b"eae_prk", &[dh]);
+        let mut shared_secret = Zeroizing::new(vec![0u8; NH]);
+        labeled_expand(&suite_id, eae_prk.as_ref(), b"shared_secret", &[kem_context], &mut shared_secret)?;
+        Ok(shared_secret)
+    }
+
+    fn suite_id(&self) -> [u8; 10] {
+        let mut id = *b"HPKE\0\0\0\0\0\0";
+        id[4..6].copy_from_slice(&self.kem.id().to_be_bytes());
+        id[6..8].copy_from_slice(&KDF_HKDF_SHA256.to_be_bytes());
+        id[8..10].copy_from_slice(&self.aead.id().to_be_bytes());
+        id
+    }
+
+    fn key_schedule(&self, mode: Mode, shared_secret: &[u8], info: &[u8], psk: Option<Psk<'_>>) -> Result<Context, HpkeError> {
+        let (psk, psk_id) = match psk {
+            Some(Psk { psk, id }) if psk.is_empty() || id.is_empty() => return Err(HpkeError::InvalidPsk),
+            Some(Psk { psk, id }) => (psk, id),
+            None => (&[][..], &[][..]),
+        };
+        let suite_id = self.suite_id();
+        let psk_id_hash = labeled_extract(&suite_id, b"", b"psk_id_hash", &[psk_id]);
+        let info_hash = labeled_extract(&suite_id, b"", b"info_hash", &[info]);
+        let context = [&[mode as u8][..], psk_id_hash.as_ref(), info_hash.as_ref()].concat();
+
+        let secret = labeled_extract(&suite_id, shared_secret, b"secret", &[psk]);
+        let mut key = Zeroizing::new(vec![0u8; self.aead.key_bytes()]);
+        let mut base_nonce = [0u8; NN];
+        let mut exporter_secret = Zeroizing::new([0u8; NH]);
+        if self.aead != HpkeAead::ExportOnly {
+            labeled_expand(&suite_id, secret.as_ref(), b"key", &[&context], &mut key)?;
+            labeled_expand(&suite_id, secret.as_ref(), b"base_nonce", &[&context], &mut base_nonce)?;
+        }
+        labeled_expand(&suite_id, secret.as_ref(), b"exp", &[&context], exporter_secret.as_mut())?;
+
+        Ok(Context { suite_id, aead: self.aead, key, base_nonce, seq: 0, exporter_secret })
+    }
+}
+
+/// State shared by both ends of an HPKE context.
+struct Context {
+    suite_id: [u8; 10],
+    aead: HpkeAead,
+    key: Zeroizing<Vec<u8>>,
+    base_nonce: [u8; NN],
+    seq: u64,
+    exporter_secret: Zeroizing<[u8; NH]>,
+}
+
+impl Context {
+    /// `base_nonce XOR I2OSP(seq, Nn)`
+    fn nonce(&self) -> Result<[u8; NN], HpkeError> {
+        if self.seq == u64::MAX {
+            return Err(HpkeError::MessageLimitReached);
+        }
+        let mut nonce = self.base_nonce;
+        for (n, s) in nonce[NN - 8..].iter_mut().zip(self.seq.to_be_bytes()) {
+            *n ^= s;
+        }
+        Ok(nonce)
+    }
+
+    fn seal(&mut self, aad: &[u8], pt: &[u8]) -> Result<Vec<u8>, HpkeError> {
+        let nonce = self.nonce()?;
+        let payload = Payload { msg: pt, aad };
+        let ct = match self.aead {
+            HpkeAead::Aes128Gcm => Aes128Gcm::new_from_slice(&self.key).map_err(|_| HpkeError::SealError)?
+                .encrypt(&nonce.into(), payload),
+            HpkeAead::Aes256Gcm => Aes256Gcm::new_from_slice(&self.key).map_err(|_| HpkeError::SealError)?
+                .encrypt(&nonce.into(), payload),
+            HpkeAead::ExportOnly => return Err(HpkeError::ExportOnly),
+        }
+        .map_err(|_| HpkeError::SealError)?;
+        self.seq += 1;
+        Ok(ct)
+    }
+
+    fn open(&mut self, aad: &[u8], ct: &[u8]) -> Result<Vec<u8>, HpkeError> {
+        let nonce = self.nonce()?;
+        let payload = Payload { msg: ct, aad };
+        let pt = match self.aead {
+            HpkeAead::Aes128Gcm => Aes128Gcm::new_from_slice(&self.key).map_err(|_| HpkeError::OpenError)?
+                .decrypt(&nonce.into(), payload),
+            HpkeAead::Aes256Gcm => Aes256Gcm::new_from_slice(&self.key).map_err(|_| HpkeError::OpenError)?
+                .decrypt(&nonce.into(), payload),
+            HpkeAead::ExportOnly => return Err(HpkeError::ExportOnly),
+        }
+        .map_err(|_| HpkeError::OpenError)?;
+        self.seq += 1;
+        Ok(pt)
+    }
+
+    fn export(&self, exporter_context: &[u8], len: usize) -> Result<Zeroizing<Vec<u8>>, HpkeError> {
+        if len > 255 * NH {
+            return Err(HpkeError::ExportTooLong);
+        }
+        let mut out = Zeroizing::new(vec![0u8; len]);
+        labeled_expand(&self.suite_id, self.exporter_secret.as_ref(), b"sec", &[exporter_context], &mut out)?;
+        Ok(out)
+    }
+}
+
+/// The sending end of an HPKE context.
+pub struct SenderContext(Context);
+
+/// The receiving end of an HPKE context.
+pub struct RecipientContext(Context);
+
+impl SenderContext {
+    /// Encrypts the next message. Each call uses the next sequence number,
+    /// so messages must be opened in the order they were sealed.
+    pub fn seal(&mut self, aad: &[u8], pt: &[u8]) -> Result<Vec<u8>, HpkeError> {
+        self.0.seal(aad, pt)
+    }
+
+    /// Derives an exported secret of `len` bytes.
+    pub fn export(&self, exporter_context: &[u8], len: usize) -> Result<Zeroizing<Vec<u8>>, HpkeError> {
+        self.0.export(exporter_context, len)
+    }
+
+    /// The sequence number of the next message.
+    pub fn sequence_number(&self) -> u64 {
+        self.0.seq
+    }
+}
+
+impl RecipientContext {
+    /// Decrypts the next message. The sequence number only advances when
+    /// decryption succeeds.
+    pub fn open(&mut self, aad: &[u8], ct: &[u8]) -> Result<Vec<u8>, HpkeError> {
+        self.0.open(aad, ct)
+    }
+
+    /// Derives an exported secret of `len` bytes.
+    pub fn export(&self, exporter_context: &[u8], len: usize) -> Result<Zeroizing<Vec<u8>>, HpkeError> {
+        self.0.export(exporter_context, len)
+    }
+
+    /// The sequence number of the next message.
+    pub fn sequence_number(&self) -> u64 {
+        self.0.seq
+    }
+}
+
+fn mode_for(psk: bool, auth: bool) -> Mode {
+    match (psk, auth) {
+        (false, false) => Mode::Base,
+        (true, false) => Mode::Psk,
+        (false, true) => Mode::Auth,
+        (true, true) => Mode::AuthPsk,
+    }
+}
+
+fn is_hybrid(algorithm: KemAlgorithm) -> bool {
+    matches!(
+        algorithm,
+        KemAlgorithm::X25519Kyber512
+            | KemAlgorithm::X25519Kyber768
+            | KemAlgorithm::X25519Kyber1024
+            | KemAlgorithm::X25519MlKem512
+            | KemAlgorithm::X25519MlKem768
+            | KemAlgorithm::X25519MlKem1024
+    )
+}
+
+fn kem_suite_id(kem: HpkeKem) -> [u8; 5] {
+    let id = kem.id().to_be_bytes();
+    [b'K', b'E', b'M', id[0], id[1]]
+}
+
+/// `LabeledExtract(salt, label, ikm)`, with `ikm` given in parts.
+fn labeled_extract(suite_id: &[u8], salt: &[u8], label: &[u8], ikm: &[&[u8]]) -> Zeroizing<[u8; NH]> {
+    let mut extract = HkdfExtract::<Sha256>::new(Some(salt));
+    for part in [VERSION_LABEL, suite_id, label].into_iter().chain(ikm.iter().copied()) {
+        extract.input_ikm(part);
+    }
+    let (prk, _) = extract.finalize();
+    Zeroizing::new(prk.into())
+}
+
+/// `LabeledExpand(prk, label, info, L)`, with `info` given in parts.
+fn labeled_expand(suite_id: &[u8], prk: &[u8], label: &[u8], info: &[&[u8]], out: &mut [u8]) -> Result<(), HpkeError> {
+    let hk = Hkdf::<Sha256>::from_prk(prk).map_err(|_| HpkeError::InvalidLength)?;
+    let len = u16::try_from(out.len()).map_err(|_| HpkeError::ExportTooLong)?.to_be_bytes();
+    let mut parts: Vec<&[u8]> = vec![&len, VERSION_LABEL, suite_id, label];
+    parts.extend_from_slice(info);
+    hk.expand_multi_info(&parts, out).map_err(|_| HpkeError::ExportTooLong)
+}
+
+fn x25519_bytes(bytes: &[u8]) -> Result<[u8; X25519_BYTES], HpkeError> {
+    bytes.try_into().map_err(|_| HpkeError::InvalidLength)
+}
+
+fn x25519_key_pair(sk: StaticSecret) -> (PublicKey, SecretKey) {
+    let pk = X25519PublicKey::from(&sk);
+    (PublicKey::from_vec(pk.as_bytes().to_vec()), SecretKey::from_vec(sk.to_bytes().to_vec()))
+}
+
+fn x25519_public(sk: &[u8]) -> Result<X25519PublicKey, HpkeError> {
+    Ok(X25519PublicKey::from(&StaticSecret::from(*Zeroizing::new(x25519_bytes(sk)?))))
+}
+
+/// `DH(sk, pk)`, rejecting the all-zero output.
+fn x25519(sk: &[u8], pk: &[u8; X25519_BYTES]) -> Result<Zeroizing<[u8; X25519_BYTES]>, HpkeError> {
+    let sk = StaticSecret::from(*Zeroizing::new(x25519_bytes(sk)?));
+    let dh = sk.diffie_hellman(&X25519PublicKey::from(*pk));
+    if !dh.was_contributory() {
+        return Err(HpkeError::InvalidDhOutput);
+    }
+    Ok(Zeroizing::new(dh.to_bytes()))
+}
+
+/// Recovers a hybrid public key from its secret key, laid out as
+/// `x25519_sk || x25519_pk || pq_sk || pq_pk` (see [`crate::kem::hybrid`]).
+fn hybrid_public_key(kem: &dyn Kem, sk: &SecretKey) -> Result<Vec<u8>, HpkeError> {
+    let sk = sk.as_ref();
+    if sk.len() != kem.secret_key_bytes() {
+        return Err(HpkeError::InvalidLength);
+    }
+    let pq_pk_bytes = kem.public_key_bytes() - X25519_BYTES;
+    Ok([&sk[X25519_BYTES..2 * X25519_BYTES], &sk[sk.len() - pq_pk_bytes..]].concat())
+}
@@ -0,0 +1,514 @@
+//! Multi-device identities.
+//!
+//! A user's identity is a long-term Dilithium master key that stays
+//! offline. It certifies a signing key and a KEM key for each of the user's
+//! devices with a [`DeviceCertificate`], which expires, and withdraws
+//! certificates early by signing a [`RevocationList`]. Peers know the user
+//! by the master key alone, as an [`Identity`]: any device certified by it
+//! is accepted, so adding a device needs no change on the peer's side.
+//!
+//! [`KnownIdentities`] is the [`TrustStore`] for such peers: a
+//! [`PQSession`](crate::PQSession) configured with it accepts a handshake
+//! signed by any certified, unexpired and unrevoked device key, and reports
+//! the peer's [`IdentityId`] rather than the device key.
+//!
+//! The certified KEM key takes part in the handshake: a device session set
+//! up with [`PQSession::with_device`](crate::PQSession::with_device) sends
+//! its certificate in the hello, and the responder encapsulates to the
+//! certified KEM key as well as to the ephemeral one. Only the device
+//! holding both secret keys derives the session keys, so a stolen signing
+//! key alone is not enough to impersonate it. A responder's own KEM key is
+//! not used, as its reply is the last handshake message.
+//!
+//! Formats (version 1), all integers big-endian, times in Unix seconds,
+//! variable-length fields prefixed with a 2-byte length:
+//!
+//! ```text
+//! device certificate:
+//!   magic "PQDC" (4) | version 0x01 (1) | serial (8) | identity id (32)
+//!   | not before (8) | not after (8) | device name | signing algorithm id (2)
+//!   | signing key | KEM algorithm id (2) | KEM key | signature
+//!
+//! revocation list:
+//!   magic "PQRL" (4) | version 0x01 (1) | identity id (32) | sequence (8)
+//!   | count (2) | revoked serials (8 each) | signature
+//! ```
+//!
+//! Both are signed by the master key over every byte before the signature.
+//!
+//! ```
+//! use pq_core::cert::Validity;
+//! use pq_core::identity::MasterKey;
+//! use pq_core::kem::{Kem, MlKem768};
+//! use pq_core::sig::dilithium::Dilithium;
+//!
+//! let master = MasterKey::generate().unwrap();
+//! let mut identity = master.identity();
+//!
+//! let scheme = Dilithium::new();
+//! let (device_pk, _device_sk) = scheme.keygen().unwrap();
+//! let (kem_pk, _kem_sk) = MlKem768::new().keygen().unwrap();
+//! let phone = master
+//!     .certify_device("phone", &scheme, &device_pk, &kem_pk, Validity::for_days(365))
+//!     .unwrap();
+//! assert!(identity.verify_device(&phone).is_ok());
+//!
+//! // The phone is lost
+//! identity.apply_revocations(&master.revoke(1, &[phone.serial()]).unwrap()).unwrap();
+//! assert!(identity.verify_device(&phone).is_err());
+//! ```
+
+use std::collections::{BTreeSet, HashMap};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use crate::cert::{put_field, unix_time, CertificateError, Reader, Validity};
+use crate::kem::kem::PublicKey as KemPublicKey;
+use crate::kem::KemAlgorithm;
+use crate::sig::dilithium::{Dilithium, DilithiumPublicKey, DilithiumSecretKey, DilithiumSignature};
+use crate::sig::{SignatureAlgorithm, SignatureError, Verifier};
+use crate::trust::{PeerIdentity, TrustError, TrustStore, TrustedPeer};
+
+/// Identifies a device certificate.
+pub const DEVICE_MAGIC: [u8; 4] = *b"PQDC";
+/// Identifies a revocation list.
+pub const REVOCATION_MAGIC: [u8; 4] = *b"PQRL";
+/// The format version written by this module.
+pub const VERSION: u8 = 1;
+
+/// SHA-256 of the master public key.
+pub type IdentityId = [u8; 32];
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum IdentityError {
+    #[error("Malformed identity record: {0}")]
+    Encoding(#[from] CertificateError),
+    #[error("Record was signed for another identity")]
+    WrongIdentity,
+    #[error("Master key signature does not verify")]
+    BadSignature,
+    #[error("Device certificate for {0} is expired or not yet valid")]
+    Expired(String),
+    #[error("Device certificate for {0} has been revoked")]
+    Revoked(String),
+    #[error("Revocation list is not newer than the one applied")]
+    StaleRevocations,
+    #[error("KEM public key is not tagged with its algorithm")]
+    UntaggedKemKey,
+    #[error("Device key belongs to {0}, not the expected scheme")]
+    AlgorithmMismatch(SignatureAlgorithm),
+    #[error("Handshake key is not the certified device key")]
+    KeyMismatch,
+    #[error("Signature error: {0}")]
+    Signature(#[from] SignatureError),
+}
+
+/// The identity id of `master_key`.
+pub fn identity_id(master_key: &DilithiumPublicKey) -> IdentityId {
+    Sha256::digest(master_key.as_bytes()).into()
+}
+
+/// A master identity keypair, used to certify and revoke devices.
+pub struct MasterKey {
+    scheme: Dilithium,
+    public_key: DilithiumPublicKey,
+    secret_key: DilithiumSecretKey,
+}
+
+impl MasterKey {
+    pub fn generate() -> Result<Self, IdentityError> {
+        let scheme = Dilithium::new();
+        let (public_key, secret_key) = scheme.keygen()?;
+        Ok(MasterKey { scheme, public_key, secret_key })
+    }
+
+    /// Uses an existing master keypair, e.g. one loaded from offline storage.
+    pub fn from_keys(public_key: DilithiumPublicKey, secret_key: DilithiumSecretKey) -> Self {
+        MasterKey { scheme: Dilithium::new(), public_key, secret_key }
+    }
+
+    pub fn public_key(&self) -> &DilithiumPublicKey {
+        &self.public_key
+    }
+
+    pub fn id(&self) -> IdentityId {
+        identity_id(&self.public_key)
+    }
+
+    /// The public identity, as peers should record it.
+    pub fn identity(&self) -> Identity {
+        Identity::new(self.public_key.clone())
+    }
+
+    /// Certifies a device's signing key `device_pk`, of scheme `V`, and its
+    /// KEM key `kem_pk`, which must be tagged with its algorithm as keys
+    /// from [`Kem::keygen`](crate::kem::Kem::keygen) are.
+    pub fn certify_device<V: Verifier>(
+        &self,
+        name: impl Into<String>,
+        scheme: &V,
+        device_pk: &V::PublicKey,
+        kem_pk: &KemPublicKey,
+        validity: Validity,
+    ) -> Result<DeviceCertificate, IdentityError> {
+        let name = name.into();
+        let serial = OsRng.next_u64();
+        let identity = self.id();
+        let signing_algorithm = scheme.algorithm();
+        let kem_algorithm = kem_pk.algorithm().ok_or(IdentityError::UntaggedKemKey)?;
+
+        let mut tbs = Vec::with_capacity(80 + name.len() + device_pk.as_ref().len() + kem_pk.as_ref().len());
+        tbs.extend_from_slice(&DEVICE_MAGIC);
+        tbs.push(VERSION);
+        tbs.extend_from_slice(&serial.to_be_bytes());
+        tbs.extend_from_slice(&identity);
+        tbs.extend_from_slice(&validity.not_before.to_be_bytes());
+        tbs.extend_from_slice(&validity.not_after.to_be_bytes());
+        put_field(&mut tbs, name.as_bytes())?;
+        tbs.extend_from_slice(&signing_algorithm.id().to_be_bytes());
+        put_field(&mut tbs, device_pk.as_ref())?;
+        tbs.extend_from_slice(&kem_algorithm.id().to_be_bytes());
+        put_field(&mut tbs, kem_pk.as_ref())?;
+        let signature = self.scheme.sign(&tbs, &self.secret_key)?;
+
+        Ok(DeviceCertificate {
+            serial,
+            identity,
+            validity,
+            name,
+            signing_algorithm,
+            signing_key: device_pk.as_ref().to_vec(),
+            kem_key: KemPublicKey::tagged(kem_algorithm, kem_pk.as_ref().to_vec()),
+            signature,
+            tbs,
+        })
+    }
+
+    /// Signs the complete list of revoked device serials. `sequence` must
+    /// grow with every list issued, so that an older list cannot be
+    /// replayed to undo a revocation.
+    pub fn revoke(&self, sequence: u64, serials: &[u64]) -> Result<RevocationList, IdentityError> {
+        let serials: BTreeSet<u64> = serials.iter().copied().collect();
+        let count = u16::try_from(serials.len()).map_err(|_| CertificateError::FieldTooLong)?;
+        let identity = self.id();
+
+        let mut tbs = Vec::with_capacity(47 + 8 * serials.len());
+        tbs.extend_from_slice(&REVOCATION_MAGIC);
+        tbs.push(VERSION);
+        tbs.extend_from_slice(&identity);
+        tbs.extend_from_slice(&sequence.to_be_bytes());
+        tbs.extend_from_slice(&count.to_be_bytes());
+        for serial in &serials {
+            tbs.extend_from_slice(&serial.to_be_bytes());
+        }
+        let signature = self.scheme.sign(&tbs, &self.secret_key)?;
+
+        Ok(RevocationList { identity, sequence, serials, signature, tbs })
+    }
+}
+
+/// A device's signing and KEM keys, certified by its owner's master key.
+#[derive(Debug, Clone)]
+pub struct DeviceCertificate {
+    serial: u64,
+    identity: IdentityId,
+    validity: Validity,
+    name: String,
+    signing_algorithm: SignatureAlgorithm,
+    signing_key: Vec<u8>,
+    kem_key: KemPublicKey,
+    signature: DilithiumSignature,
+    /// The signed bytes, kept as received
+    tbs: Vec<u8>,
+}
+
+impl PartialEq for DeviceCertificate {
+    fn eq(&self, other: &Self) -> bool {
+        self.tbs == other.tbs && self.signature.as_bytes() == other.signature.as_bytes()
+    }
+}
+
+impl Eq for DeviceCertificate {}
+
+impl DeviceCertificate {
+    /// Decodes a device certificate. The signature is not checked; see
+    /// [`Identity::verify_device`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, IdentityError> {
+        let mut reader = Reader { bytes, pos: 0 };
+        if reader.take(DEVICE_MAGIC.len())? != DEVICE_MAGIC {
+            return Err(CertificateError::BadMagic.into());
+        }
+        let version = reader.u8()?;
+        if version != VERSION {
+            return Err(CertificateError::UnsupportedVersion(version).into());
+        }
+
+        let serial = reader.u64()?;
+        let identity: IdentityId = reader.take(32)?.try_into().expect("32 bytes");
+        let validity = Validity::new(reader.u64()?, reader.u64()?);
+        let name = reader.string()?;
+        let signing_algorithm = SignatureAlgorithm::try_from(reader.u16()?)?;
+        let signing_key = reader.field()?.to_vec();
+        let kem_algorithm = KemAlgorithm::from_id(reader.u16()?).ok_or(CertificateError::Malformed)?;
+        let kem_key = KemPublicKey::tagged(kem_algorithm, reader.field()?.to_vec());
+        let tbs = bytes[..reader.pos].to_vec();
+        let signature = Dilithium::new().signature_from_bytes(reader.field()?)?;
+        if reader.pos != bytes.len() {
+            return Err(CertificateError::Malformed.into());
+        }
+
+        Ok(DeviceCertificate {
+            serial,
+            identity,
+            validity,
+            name,
+            signing_algorithm,
+            signing_key,
+            kem_key,
+            signature,
+            tbs,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let signature = self.signature.as_bytes();
+        let mut out = Vec::with_capacity(self.tbs.len() + 2 + signature.len());
+        out.extend_from_slice(&self.tbs);
+        out.extend_from_slice(&(signature.len() as u16).to_be_bytes());
+        out.extend_from_slice(signature);
+        out
+    }
+
+    pub fn serial(&self) -> u64 {
+        self.serial
+    }
+
+    /// The identity that certified this device.
+    pub fn identity(&self) -> IdentityId {
+        self.identity
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn validity(&self) -> Validity {
+        self.validity
+    }
+
+    pub fn signing_algorithm(&self) -> SignatureAlgorithm {
+        self.signing_algorithm
+    }
+
+    /// The encoded device signing key.
+    pub fn signing_key(&self) -> &[u8] {
+        &self.signing_key
+    }
+
+    /// Decodes the device signing key for `scheme`.
+    pub fn signing_public_key<V: Verifier>(&self, scheme: &V) -> Result<V::PublicKey, IdentityError> {
+        if self.signing_algorithm != scheme.algorithm() {
+            return Err(IdentityError::AlgorithmMismatch(self.signing_algorithm));
+        }
+        Ok(scheme.public_key_from_bytes(&self.signing_key)?)
+    }
+
+    /// The device KEM key, tagged with its algorithm.
+    pub fn kem_public_key(&self) -> &KemPublicKey {
+        &self.kem_key
+    }
+}
+
+/// The device serials an identity has revoked, signed by its master key.
+#[derive(Debug, Clone)]
+pub struct RevocationList {
+    identity: IdentityId,
+    sequence: u64,
+    serials: BTreeSet<u64>,
+    signature: DilithiumSignature,
+    tbs: Vec<u8>,
+}
+
+impl PartialEq for RevocationList {
+    fn eq(&self, other: &Self) -> bool {
+        self.tbs == other.tbs && self.signature.as_bytes() == other.signature.as_bytes()
+    }
+}
+
+impl Eq for RevocationList {}
+
+impl RevocationList {
+    /// Decodes a revocation list. The signature is not checked; see
+    /// [`Identity::apply_revocations`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, IdentityError> {
+        let mut reader = Reader { bytes, pos: 0 };
+        if reader.take(REVOCATION_MAGIC.len())? != REVOCATION_MAGIC {
+            return Err(CertificateError::BadMagic.into());
+        }
+        let version = reader.u8()?;
+        if version != VERSION {
+            return Err(CertificateError::UnsupportedVersion(version).into());
+        }
+
+        let identity: IdentityId = reader.take(32)?.try_into().expect("32 bytes");
+        let sequence = reader.u64()?;
+        let count = reader.u16()?;
+        let serials = (0..count).map(|_| reader.u64()).collect::<Result<BTreeSet<u64>, _>>()?;
+        if serials.len() != count as usize {
+            return Err(CertificateError::Malformed.into());
+        }
+        let tbs = bytes[..reader.pos].to_vec();
+        let signature = Dilithium::new().signature_from_bytes(reader.field()?)?;
+        if reader.pos != bytes.len() {
+            return Err(CertificateError::Malformed.into());
+        }
+
+        Ok(RevocationList { identity, sequence, serials, signature, tbs })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let signature = self.signature.as_bytes();
+        let mut out = Vec::with_capacity(self.tbs.len() + 2 + signature.len());
+        out.extend_from_slice(&self.tbs);
+        out.extend_from_slice(&(signature.len() as u16).to_be_bytes());
+        out.extend_from_slice(signature);
+        out
+    }
+
+    pub fn identity(&self) -> IdentityId {
+        self.identity
+    }
+
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    pub fn contains(&self, serial: u64) -> bool {
+        self.serials.contains(&serial)
+    }
+}
+
+/// The public half of an identity: its master key and the latest
+/// revocation list applied to it.
+#[derive(Debug, Clone)]
+pub struct Identity {
+    master_key: DilithiumPublicKey,
+    id: IdentityId,
+    revocations: Option<RevocationList>,
+}
+
+impl Identity {
+    pub fn new(master_key: DilithiumPublicKey) -> Self {
+        let id = identity_id(&master_key);
+        Identity { master_key, id, revocations: None }
+    }
+
+    pub fn id(&self) -> IdentityId {
+        self.id
+    }
+
+    pub fn master_key(&self) -> &DilithiumPublicKey {
+        &self.master_key
+    }
+
+    /// Replaces the applied revocation list with `list` after checking its
+    /// signature. Fails with [`IdentityError::StaleRevocations`] unless
+    /// `list` has a higher sequence number than the current one.
+    pub fn apply_revocations(&mut self, list: &RevocationList) -> Result<(), IdentityError> {
+        self.check_signature(list.identity, &list.tbs, &list.signature)?;
+        if self.revocations.as_ref().is_some_and(|current| list.sequence <= current.sequence) {
+            return Err(IdentityError::StaleRevocations);
+        }
+        self.revocations = Some(list.clone());
+        Ok(())
+    }
+
+    pub fn is_revoked(&self, serial: u64) -> bool {
+        self.revocations.as_ref().is_some_and(|list| list.contains(serial))
+    }
+
+    /// Checks that the master key certified `device`, and that the
+    /// certificate is unrevoked and valid now.
+    pub fn verify_device(&self, device: &DeviceCertificate) -> Result<(), IdentityError> {
+        self.verify_device_at(device, unix_time())
+    }
+
+    /// Like [`verify_device`](Identity::verify_device), at `time`.
+    pub fn verify_device_at(&self, device: &DeviceCertificate, time: u64) -> Result<(), IdentityError> {
+        self.check_signature(device.identity, &device.tbs, &device.signature)?;
+        if self.is_revoked(device.serial) {
+            return Err(IdentityError::Revoked(device.name.clone()));
+        }
+        if !device.validity.contains(time) {
+            return Err(IdentityError::Expired(device.name.clone()));
+        }
+        Ok(())
+    }
+
+    fn check_signature(&self, identity: IdentityId, tbs: &[u8], signature: &DilithiumSignature) -> Result<(), IdentityError> {
+        if identity != self.id {
+            return Err(IdentityError::WrongIdentity);
+        }
+        if !Dilithium::new().verify(tbs, signature, &self.master_key)? {
+            return Err(IdentityError::BadSignature);
+        }
+        Ok(())
+    }
+}
+
+/// A [`TrustStore`] of multi-device identities. The peer must send a
+/// [`DeviceCertificate`] for its handshake key from one of the identities;
+/// with a peer name set, from the identity trusted under that name.
+#[derive(Debug, Clone, Default)]
+pub struct KnownIdentities {
+    identities: HashMap<IdentityId, Identity>,
+    names: HashMap<String, IdentityId>,
+}
+
+impl KnownIdentities {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Trusts `identity` as the only identity for `name`.
+    pub fn trust(mut self, name: impl Into<String>, identity: Identity) -> Self {
+        self.names.insert(name.into(), identity.id());
+        self.trust_any(identity)
+    }
+
+    /// Trusts `identity` for any peer name.
+    pub fn trust_any(mut self, identity: Identity) -> Self {
+        self.identities.insert(identity.id(), identity);
+        self
+    }
+
+    pub fn get(&self, id: &IdentityId) -> Option<&Identity> {
+        self.identities.get(id)
+    }
+
+    /// Applies `list` to the identity that signed it.
+    pub fn apply_revocations(&mut self, list: &RevocationList) -> Result<(), IdentityError> {
+        let identity = self.identities.get_mut(&list.identity).ok_or(IdentityError::WrongIdentity)?;
+        identity.apply_revocations(list)
+    }
+}
+
+impl<S: Verifier> TrustStore<S> for KnownIdentities {
+    fn verify_peer(&mut self, peer: &PeerIdentity<'_, S>) -> Result<TrustedPeer<S>, TrustError> {
+        let device = peer.device.ok_or(TrustError::UnknownIdentity)?;
+        if let Some(name) = peer.name {
+            let expected = self.names.get(name).ok_or(TrustError::UnknownIdentity)?;
+            if *expected != device.identity {
+                return Err(TrustError::IdentityChanged);
+            }
+        }
+        let identity = self.identities.get(&device.identity).ok_or(TrustError::UnknownIdentity)?;
+
+        identity.verify_device(device)?;
+        if device.signing_algorithm != peer.algorithm || device.signing_key != peer.public_key.as_ref() {
+            return Err(IdentityError::KeyMismatch.into());
+        }
+        Ok(TrustedPeer::Device(device.clone()))
+    }
+}
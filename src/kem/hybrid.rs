@@ -0,0 +1,258 @@
+//! Hybrid KEM running X25519 and a post-quantum KEM side by side.
+//!
+//! Public keys and ciphertexts are the concatenation of the X25519 part and
+//! the post-quantum part. The shared secret is derived with a SHA3-256
+//! combiner over both component secrets, both ciphertexts and both public
+//! keys, so it stays secret as long as either component is unbroken.
+//!
+//! Encodings:
+//! - public key: `x25519_pk (32) || pq_pk`
+//! - ciphertext: `x25519_ephemeral_pk (32) || pq_ct`
+//! - secret key: `x25519_sk (32) || x25519_pk (32) || pq_sk || pq_pk`
+//!
+//! Deterministic seeds and coins are likewise `x25519 (32) || pq`, and are
+//! supported whenever the post-quantum component supports them.
+
+use rand::rngs::OsRng;
+use sha3::{Digest, Sha3_256};
+use x25519_dalek::{PublicKey as X25519PublicKey, StaticSecret};
+use zeroize::Zeroizing;
+use super::kem::{Kem, KemAlgorithm, KemError, PublicKey, SecretKey, Ciphertext, SharedSecret};
+use super::kyber::{Kyber512, Kyber768, Kyber1024};
+use super::mlkem::{MlKem512, MlKem768, MlKem1024};
+
+/// Domain separation label for the combiner.
+const COMBINER_LABEL: &[u8] = b"PQ-Core X25519 hybrid KEM v1";
+
+const X25519_BYTES: usize = 32;
+
+/// Post-quantum KEMs that can be paired with X25519 in a [`HybridKem`].
+pub trait HybridComponent: Kem {
+    /// Identifier of the X25519 hybrid built on this KEM.
+    const HYBRID_ALGORITHM: KemAlgorithm;
+}
+
+impl HybridComponent for Kyber512 {
+    const HYBRID_ALGORITHM: KemAlgorithm = KemAlgorithm::X25519Kyber512;
+}
+impl HybridComponent for Kyber768 {
+    const HYBRID_ALGORITHM: KemAlgorithm = KemAlgorithm::X25519Kyber768;
+}
+impl HybridComponent for Kyber1024 {
+    const HYBRID_ALGORITHM: KemAlgorithm = KemAlgorithm::X25519Kyber1024;
+}
+impl HybridComponent for MlKem512 {
+    const HYBRID_ALGORITHM: KemAlgorithm = KemAlgorithm::X25519MlKem512;
+}
+impl HybridComponent for MlKem768 {
+    const HYBRID_ALGORITHM: KemAlgorithm = KemAlgorithm::X25519MlKem768;
+}
+impl HybridComponent for MlKem1024 {
+    const HYBRID_ALGORITHM: KemAlgorithm = KemAlgorithm::X25519MlKem1024;
+}
+#[cfg(feature = "native-kyber")]
+impl HybridComponent for super::native_kyber::NativeKyber512 {
+    const HYBRID_ALGORITHM: KemAlgorithm = KemAlgorithm::X25519Kyber512;
+}
+#[cfg(feature = "native-kyber")]
+impl HybridComponent for super::native_kyber::NativeKyber768 {
+    const HYBRID_ALGORITHM: KemAlgorithm = KemAlgorithm::X25519Kyber768;
+}
+#[cfg(feature = "native-kyber")]
+impl HybridComponent for super::native_kyber::NativeKyber1024 {
+    const HYBRID_ALGORITHM: KemAlgorithm = KemAlgorithm::X25519Kyber1024;
+}
+
+/// X25519 combined with the post-quantum KEM `K`.
+#[derive(Debug, Clone, Default)]
+pub struct HybridKem<K: HybridComponent> {
+    pq: K,
+}
+
+/// X25519 + Kyber512 hybrid.
+pub type X25519Kyber512 = HybridKem<Kyber512>;
+/// X25519 + Kyber768 hybrid.
+pub type X25519Kyber768 = HybridKem<Kyber768>;
+/// X25519 + Kyber1024 hybrid.
+pub type X25519Kyber1024 = HybridKem<Kyber1024>;
+/// X25519 + ML-KEM-512 hybrid.
+pub type X25519MlKem512 = HybridKem<MlKem512>;
+/// X25519 + ML-KEM-768 hybrid.
+pub type X25519MlKem768 = HybridKem<MlKem768>;
+/// X25519 + ML-KEM-1024 hybrid.
+pub type X25519MlKem1024 = HybridKem<MlKem1024>;
+
+impl<K: HybridComponent + Default> HybridKem<K> {
+    pub fn new() -> Self {
+        Self { pq: K::default() }
+    }
+}
+
+impl<K: HybridComponent> HybridKem<K> {
+    /// Wraps an existing post-quantum KEM instance.
+    pub fn with_component(pq: K) -> Self {
+        Self { pq }
+    }
+
+    fn split_public_key<'a>(&self, pk: &'a PublicKey) -> Result<(&'a [u8], &'a [u8]), KemError> {
+        pk.check_algorithm(K::HYBRID_ALGORITHM)?;
+        if pk.as_ref().len() != self.public_key_bytes() {
+            return Err(KemError::InvalidKeySize);
+        }
+        Ok(pk.as_ref().split_at(X25519_BYTES))
+    }
+
+    fn assemble_keypair(&self, x_sk: StaticSecret, pq_pk: &PublicKey, pq_sk: &SecretKey) -> (PublicKey, SecretKey) {
+        let x_pk = X25519PublicKey::from(&x_sk);
+
+        let mut pk = Vec::with_capacity(self.public_key_bytes());
+        pk.extend_from_slice(x_pk.as_bytes());
+        pk.extend_from_slice(pq_pk.as_ref());
+
+        let mut sk = Vec::with_capacity(self.secret_key_bytes());
+        sk.extend_from_slice(x_sk.as_bytes());
+        sk.extend_from_slice(x_pk.as_bytes());
+        sk.extend_from_slice(pq_sk.as_ref());
+        sk.extend_from_slice(pq_pk.as_ref());
+
+        (PublicKey::tagged(K::HYBRID_ALGORITHM, pk), SecretKey::from_vec(sk))
+    }
+
+    /// Runs X25519 with the ephemeral secret `eph` and the post-quantum
+    /// encapsulation `pq_encaps`, then combines the results.
+    fn encaps_with<F>(&self, pk: &PublicKey, eph: StaticSecret, pq_encaps: F) -> Result<(Ciphertext, SharedSecret), KemError>
+    where
+        F: FnOnce(&PublicKey) -> Result<(Ciphertext, SharedSecret), KemError>,
+    {
+        let (pk_x, pk_pq) = self.split_public_key(pk)?;
+        let peer_x = X25519PublicKey::from(x25519_bytes(pk_x));
+
+        let ct_x = X25519PublicKey::from(&eph);
+        let ss_x = eph.diffie_hellman(&peer_x);
+        if !ss_x.was_contributory() {
+            return Err(KemError::EncapsulationError);
+        }
+
+        let (ct_pq, ss_pq) = pq_encaps(&PublicKey::tagged(self.pq.algorithm(), pk_pq.to_vec()))?;
+
+        let ss = Self::combine(ss_pq.as_ref(), ss_x.as_bytes(), ct_pq.as_ref(), ct_x.as_bytes(), pk_pq, pk_x);
+
+        let mut ct = Vec::with_capacity(self.ciphertext_bytes());
+        ct.extend_from_slice(ct_x.as_bytes());
+        ct.extend_from_slice(ct_pq.as_ref());
+
+        Ok((Ciphertext::from_vec(ct), ss))
+    }
+
+    /// Derives the hybrid shared secret from both component secrets, binding
+    /// both ciphertexts and both public keys.
+    fn combine(
+        ss_pq: &[u8],
+        ss_x: &[u8],
+        ct_pq: &[u8],
+        ct_x: &[u8],
+        pk_pq: &[u8],
+        pk_x: &[u8],
+    ) -> SharedSecret {
+        let mut hasher = Sha3_256::new();
+        hasher.update(COMBINER_LABEL);
+        for part in [ss_pq, ss_x, ct_pq, ct_x, pk_pq, pk_x] {
+            hasher.update((part.len() as u32).to_be_bytes());
+            hasher.update(part);
+        }
+        SharedSecret::from_vec(hasher.finalize().to_vec())
+    }
+}
+
+fn x25519_bytes(bytes: &[u8]) -> [u8; X25519_BYTES] {
+    let mut out = [0u8; X25519_BYTES];
+    out.copy_from_slice(bytes);
+    out
+}
+
+impl<K: HybridComponent> Kem for HybridKem<K> {
+    fn algorithm(&self) -> KemAlgorithm {
+        K::HYBRID_ALGORITHM
+    }
+
+    fn keygen(&self) -> Result<(PublicKey, SecretKey), KemError> {
+        let (pq_pk, pq_sk) = self.pq.keygen()?;
+        Ok(self.assemble_keypair(StaticSecret::random_from_rng(OsRng), &pq_pk, &pq_sk))
+    }
+
+    fn encaps(&self, pk: &PublicKey) -> Result<(Ciphertext, SharedSecret), KemError> {
+        self.encaps_with(pk, StaticSecret::random_from_rng(OsRng), |pq_pk| self.pq.encaps(pq_pk))
+    }
+
+    fn decaps(&self, ct: &Ciphertext, sk: &SecretKey) -> Result<SharedSecret, KemError> {
+        if ct.as_ref().len() != self.ciphertext_bytes() {
+            return Err(KemError::InvalidCiphertextSize);
+        }
+        if sk.as_ref().len() != self.secret_key_bytes() {
+            return Err(KemError::InvalidKeySize);
+        }
+
+        let (ct_x, ct_pq) = ct.as_ref().split_at(X25519_BYTES);
+        let (sk_x, rest) = sk.as_ref().split_at(X25519_BYTES);
+        let (pk_x, rest) = rest.split_at(X25519_BYTES);
+        let (sk_pq, pk_pq) = rest.split_at(self.pq.secret_key_bytes());
+
+        let x_sk = StaticSecret::from(*Zeroizing::new(x25519_bytes(sk_x)));
+        let ss_x = x_sk.diffie_hellman(&X25519PublicKey::from(x25519_bytes(ct_x)));
+        if !ss_x.was_contributory() {
+            return Err(KemError::DecapsulationError);
+        }
+
+        let ss_pq = self.pq.decaps(
+            &Ciphertext::from_vec(ct_pq.to_vec()),
+            &SecretKey::from_vec(sk_pq.to_vec()),
+        )?;
+
+        Ok(Self::combine(ss_pq.as_ref(), ss_x.as_bytes(), ct_pq, ct_x, pk_pq, pk_x))
+    }
+
+    fn public_key_bytes(&self) -> usize {
+        X25519_BYTES + self.pq.public_key_bytes()
+    }
+
+    fn secret_key_bytes(&self) -> usize {
+        2 * X25519_BYTES + self.pq.secret_key_bytes() + self.pq.public_key_bytes()
+    }
+
+    fn ciphertext_bytes(&self) -> usize {
+        X25519_BYTES + self.pq.ciphertext_bytes()
+    }
+
+    fn shared_secret_bytes(&self) -> usize {
+        32
+    }
+
+    fn keygen_from_seed(&self, seed: &[u8]) -> Result<(PublicKey, SecretKey), KemError> {
+        let pq_seed_bytes = self.pq.keygen_seed_bytes().ok_or(KemError::DeterministicUnsupported(self.algorithm()))?;
+        if seed.len() != X25519_BYTES + pq_seed_bytes {
+            return Err(KemError::InvalidSeedSize);
+        }
+        let (x_seed, pq_seed) = seed.split_at(X25519_BYTES);
+        let (pq_pk, pq_sk) = self.pq.keygen_from_seed(pq_seed)?;
+        Ok(self.assemble_keypair(StaticSecret::from(x25519_bytes(x_seed)), &pq_pk, &pq_sk))
+    }
+
+    fn encaps_deterministic(&self, pk: &PublicKey, coins: &[u8]) -> Result<(Ciphertext, SharedSecret), KemError> {
+        let pq_coins_bytes = self.pq.encaps_coins_bytes().ok_or(KemError::DeterministicUnsupported(self.algorithm()))?;
+        if coins.len() != X25519_BYTES + pq_coins_bytes {
+            return Err(KemError::InvalidSeedSize);
+        }
+        let (x_coins, pq_coins) = coins.split_at(X25519_BYTES);
+        self.encaps_with(pk, StaticSecret::from(x25519_bytes(x_coins)), |pq_pk| {
+            self.pq.encaps_deterministic(pq_pk, pq_coins)
+        })
+    }
+
+    fn keygen_seed_bytes(&self) -> Option<usize> {
+        self.pq.keygen_seed_bytes().map(|n| X25519_BYTES + n)
+    }
+
+    fn encaps_coins_bytes(&self) -> Option<usize> {
+        self.pq.encaps_coins_bytes().map(|n| X25519_BYTES + n)
+    }
+}
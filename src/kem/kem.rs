@@ -1,17 +1,191 @@
 use rand::Error as RngError;
+use rayon::prelude::*;
+use subtle::ConstantTimeEq;
 use thiserror::Error;
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
+/// Identifies the KEM algorithm and parameter set a key belongs to.
+///
+/// Kyber512 and ML-KEM-512 (likewise 768/1024) have identical key sizes, so
+/// the length of a key alone cannot tell them apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KemAlgorithm {
+    /// Round-3 Kyber512 (`pqcrypto-kyber`)
+    Kyber512,
+    /// Round-3 Kyber768 (`pqcrypto-kyber`)
+    Kyber768,
+    /// Round-3 Kyber1024 (`pqcrypto-kyber`)
+    Kyber1024,
+    /// FIPS 203 ML-KEM-512
+    MlKem512,
+    /// FIPS 203 ML-KEM-768
+    MlKem768,
+    /// FIPS 203 ML-KEM-1024
+    MlKem1024,
+    /// X25519 + Kyber512 hybrid
+    X25519Kyber512,
+    /// X25519 + Kyber768 hybrid
+    X25519Kyber768,
+    /// X25519 + Kyber1024 hybrid
+    X25519Kyber1024,
+    /// X25519 + ML-KEM-512 hybrid
+    X25519MlKem512,
+    /// X25519 + ML-KEM-768 hybrid
+    X25519MlKem768,
+    /// X25519 + ML-KEM-1024 hybrid
+    X25519MlKem1024,
+}
+
+impl KemAlgorithm {
+    /// Every supported KEM, in identifier order.
+    pub const ALL: [KemAlgorithm; 12] = [
+        KemAlgorithm::Kyber512,
+        KemAlgorithm::Kyber768,
+        KemAlgorithm::Kyber1024,
+        KemAlgorithm::MlKem512,
+        KemAlgorithm::MlKem768,
+        KemAlgorithm::MlKem1024,
+        KemAlgorithm::X25519Kyber512,
+        KemAlgorithm::X25519Kyber768,
+        KemAlgorithm::X25519Kyber1024,
+        KemAlgorithm::X25519MlKem512,
+        KemAlgorithm::X25519MlKem768,
+        KemAlgorithm::X25519MlKem1024,
+    ];
+
+    /// Stable numeric identifier for config files and wire messages.
+    ///
+    /// The high byte names the family (`0x00` Kyber, `0x01` ML-KEM, `0x02`
+    /// X25519+Kyber, `0x03` X25519+ML-KEM), the low byte the parameter set.
+    /// Identifiers are never reused or renumbered.
+    pub const fn id(self) -> u16 {
+        match self {
+            KemAlgorithm::Kyber512 => 0x0001,
+            KemAlgorithm::Kyber768 => 0x0002,
+            KemAlgorithm::Kyber1024 => 0x0003,
+            KemAlgorithm::MlKem512 => 0x0101,
+            KemAlgorithm::MlKem768 => 0x0102,
+            KemAlgorithm::MlKem1024 => 0x0103,
+            KemAlgorithm::X25519Kyber512 => 0x0201,
+            KemAlgorithm::X25519Kyber768 => 0x0202,
+            KemAlgorithm::X25519Kyber1024 => 0x0203,
+            KemAlgorithm::X25519MlKem512 => 0x0301,
+            KemAlgorithm::X25519MlKem768 => 0x0302,
+            KemAlgorithm::X25519MlKem1024 => 0x0303,
+        }
+    }
+
+    /// Stable string identifier, also used by `Display`.
+    pub const fn name(self) -> &'static str {
+        match self {
+            KemAlgorithm::Kyber512 => "Kyber512",
+            KemAlgorithm::Kyber768 => "Kyber768",
+            KemAlgorithm::Kyber1024 => "Kyber1024",
+            KemAlgorithm::MlKem512 => "ML-KEM-512",
+            KemAlgorithm::MlKem768 => "ML-KEM-768",
+            KemAlgorithm::MlKem1024 => "ML-KEM-1024",
+            KemAlgorithm::X25519Kyber512 => "X25519+Kyber512",
+            KemAlgorithm::X25519Kyber768 => "X25519+Kyber768",
+            KemAlgorithm::X25519Kyber1024 => "X25519+Kyber1024",
+            KemAlgorithm::X25519MlKem512 => "X25519+ML-KEM-512",
+            KemAlgorithm::X25519MlKem768 => "X25519+ML-KEM-768",
+            KemAlgorithm::X25519MlKem1024 => "X25519+ML-KEM-1024",
+        }
+    }
+
+    /// Looks up an algorithm by its [`id`](Self::id).
+    pub fn from_id(id: u16) -> Option<Self> {
+        Self::ALL.into_iter().find(|alg| alg.id() == id)
+    }
+
+    /// Looks up an algorithm by its [`name`](Self::name), ignoring ASCII case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|alg| alg.name().eq_ignore_ascii_case(name))
+    }
+}
+
+impl std::fmt::Display for KemAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl std::str::FromStr for KemAlgorithm {
+    type Err = KemError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_name(s).ok_or_else(|| KemError::UnknownAlgorithm(s.to_string()))
+    }
+}
+
+impl TryFrom<u16> for KemAlgorithm {
+    type Error = KemError;
+
+    fn try_from(id: u16) -> Result<Self, Self::Error> {
+        Self::from_id(id).ok_or_else(|| KemError::UnknownAlgorithm(format!("{id:#06x}")))
+    }
+}
+
+impl From<KemAlgorithm> for u16 {
+    fn from(alg: KemAlgorithm) -> u16 {
+        alg.id()
+    }
+}
+
+/// A KEM public key.
+///
+/// Keys produced by [`Kem::keygen`] are tagged with the algorithm that
+/// generated them; keys built from raw bytes are untagged and accepted by any
+/// KEM with a matching key size.
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct PublicKey(Box<[u8]>);
-#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicKey {
+    bytes: Box<[u8]>,
+    algorithm: Option<KemAlgorithm>,
+}
+/// A KEM secret key. Wiped on drop, redacted in `Debug` output and compared
+/// in constant time.
+#[derive(Clone)]
 pub struct SecretKey(Box<[u8]>);
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Ciphertext(Box<[u8]>);
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// A KEM shared secret. Wiped on drop, redacted in `Debug` output and
+/// compared in constant time.
+#[derive(Clone)]
 pub struct SharedSecret(Box<[u8]>);
 
+/// Implements zeroize-on-drop, redacted `Debug` and constant-time equality
+/// for a byte-blob secret type.
+macro_rules! secret_bytes {
+    ($name:ident) => {
+        impl Drop for $name {
+            fn drop(&mut self) {
+                self.0.zeroize();
+            }
+        }
+
+        impl ZeroizeOnDrop for $name {}
+
+        impl std::fmt::Debug for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, concat!(stringify!($name), "(<{} bytes redacted>)"), self.0.len())
+            }
+        }
+
+        impl PartialEq for $name {
+            fn eq(&self, other: &Self) -> bool {
+                self.0.ct_eq(&other.0).into()
+            }
+        }
+
+        impl Eq for $name {}
+    };
+}
+
+secret_bytes!(SecretKey);
+secret_bytes!(SharedSecret);
+
 impl AsRef<[u8]> for PublicKey {
-    fn as_ref(&self) -> &[u8] { &self.0 }
+    fn as_ref(&self) -> &[u8] { &self.bytes }
 }
 impl AsRef<[u8]> for SecretKey {
     fn as_ref(&self) -> &[u8] { &self.0 }
@@ -24,8 +198,25 @@ impl AsRef<[u8]> for SharedSecret {
 }
 
 impl PublicKey {
-    pub fn from_vec(v: Vec<u8>) -> Self { Self(v.into_boxed_slice()) }
-    pub fn from_box(b: Box<[u8]>) -> Self { Self(b) }
+    pub fn from_vec(v: Vec<u8>) -> Self { Self::from_box(v.into_boxed_slice()) }
+    pub fn from_box(b: Box<[u8]>) -> Self { Self { bytes: b, algorithm: None } }
+
+    /// Builds a public key tagged with the algorithm it belongs to.
+    pub fn tagged(algorithm: KemAlgorithm, v: Vec<u8>) -> Self {
+        Self { bytes: v.into_boxed_slice(), algorithm: Some(algorithm) }
+    }
+
+    /// The algorithm this key belongs to, if known.
+    pub fn algorithm(&self) -> Option<KemAlgorithm> { self.algorithm }
+
+    /// Checks that this key may be used with `algorithm`. Untagged keys are
+    /// accepted so that keys exchanged as raw bytes keep working.
+    pub fn check_algorithm(&self, algorithm: KemAlgorithm) -> Result<(), KemError> {
+        match self.algorithm {
+            Some(tag) if tag != algorithm => Err(KemError::AlgorithmMismatch { expected: algorithm, found: tag }),
+            _ => Ok(()),
+        }
+    }
 }
 impl SecretKey {
     pub fn from_vec(v: Vec<u8>) -> Self { Self(v.into_boxed_slice()) }
@@ -50,11 +241,24 @@ pub enum KemError {
     EncapsulationError,
     #[error("Decapsulation operation failed")]
     DecapsulationError,
+    #[error("Key belongs to {found}, expected {expected}")]
+    AlgorithmMismatch { expected: KemAlgorithm, found: KemAlgorithm },
+    #[error("Encapsulation key failed the FIPS 203 modulus check")]
+    InvalidEncapsulationKey,
+    #[error("Decapsulation key failed the FIPS 203 hash check")]
+    InvalidDecapsulationKey,
+    #[error("{0} does not support deterministic key generation or encapsulation")]
+    DeterministicUnsupported(KemAlgorithm),
+    #[error("Invalid seed or coins size")]
+    InvalidSeedSize,
+    #[error("Unknown KEM algorithm: {0}")]
+    UnknownAlgorithm(String),
     #[error("Cryptographic RNG failure: {0}")]
     RandomError(#[from] RngError),
 }
 
 pub trait Kem: Send + Sync {
+    fn algorithm(&self) -> KemAlgorithm;
     fn keygen(&self) -> Result<(PublicKey, SecretKey), KemError>;
     fn encaps(&self, pk: &PublicKey) -> Result<(Ciphertext, SharedSecret), KemError>;
     fn decaps(&self, ct: &Ciphertext, sk: &SecretKey) -> Result<SharedSecret, KemError>;
@@ -62,4 +266,40 @@ pub trait Kem: Send + Sync {
     fn secret_key_bytes(&self) -> usize;
     fn ciphertext_bytes(&self) -> usize;
     fn shared_secret_bytes(&self) -> usize;
+
+    /// Deterministically derives a keypair from `keygen_seed_bytes()` bytes of
+    /// seed. Only for fixtures, KATs and key recovery: the seed must come from
+    /// a CSPRNG and be protected like the secret key itself.
+    fn keygen_from_seed(&self, _seed: &[u8]) -> Result<(PublicKey, SecretKey), KemError> {
+        Err(KemError::DeterministicUnsupported(self.algorithm()))
+    }
+
+    /// Encapsulates using `encaps_coins_bytes()` bytes of caller-supplied
+    /// randomness instead of the OS RNG. Reusing coins breaks security.
+    fn encaps_deterministic(&self, _pk: &PublicKey, _coins: &[u8]) -> Result<(Ciphertext, SharedSecret), KemError> {
+        Err(KemError::DeterministicUnsupported(self.algorithm()))
+    }
+
+    /// Seed size for [`Kem::keygen_from_seed`], or `None` if unsupported.
+    fn keygen_seed_bytes(&self) -> Option<usize> {
+        None
+    }
+
+    /// Coins size for [`Kem::encaps_deterministic`], or `None` if unsupported.
+    fn encaps_coins_bytes(&self) -> Option<usize> {
+        None
+    }
+
+    /// Encapsulates to every key in `pks`, spread over the rayon thread
+    /// pool. Results are returned in input order, and a bad key only fails
+    /// its own entry.
+    fn batch_encaps(&self, pks: &[PublicKey]) -> Vec<Result<(Ciphertext, SharedSecret), KemError>> {
+        pks.par_iter().map(|pk| self.encaps(pk)).collect()
+    }
+
+    /// Decapsulates every ciphertext in `cts` with `sk`, spread over the
+    /// rayon thread pool. Results are returned in input order.
+    fn batch_decaps(&self, cts: &[Ciphertext], sk: &SecretKey) -> Vec<Result<SharedSecret, KemError>> {
+        cts.par_iter().map(|ct| self.decaps(ct, sk)).collect()
+    }
 }
\ No newline at end of file
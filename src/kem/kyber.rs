@@ -1,77 +1,123 @@
-use pqcrypto_kyber::kyber512;
+use pqcrypto_kyber::{kyber512, kyber768, kyber1024};
 use pqcrypto_traits::kem::{PublicKey as PQPublicKey, SecretKey as PQSecretKey, Ciphertext as PQCiphertext, SharedSecret as PQSharedSecret};
-use super::kem::{Kem, KemError, PublicKey, SecretKey, Ciphertext, SharedSecret};
+use super::kem::{Kem, KemAlgorithm, KemError, PublicKey, SecretKey, Ciphertext, SharedSecret};
+use super::native_kyber;
 
-#[derive(Debug, Clone)]
-pub struct Kyber512 {
-    _phantom: std::marker::PhantomData<()>,
-}
+/// Generates a unit-like Kyber parameter set wrapping one of the
+/// `pqcrypto_kyber` modules. All parameter sets share the same size-checked
+/// `encaps`/`decaps` behaviour and only differ in the backing module.
+///
+/// The C backend draws its randomness internally, so `keygen_from_seed` and
+/// `encaps_deterministic` go through the pure-Rust implementation in
+/// [`super::native_kyber`] instead, which produces the same bytes.
+macro_rules! kyber_kem {
+    ($(#[$meta:meta])* $name:ident, $backend:ident, $params:expr, $algorithm:expr) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Default)]
+        pub struct $name {
+            _phantom: std::marker::PhantomData<()>,
+        }
 
-impl Kyber512 {
-    pub fn new() -> Self {
-        Self {
-            _phantom: std::marker::PhantomData,
+        impl $name {
+            pub fn new() -> Self {
+                Self {
+                    _phantom: std::marker::PhantomData,
+                }
+            }
         }
-    }
-}
 
+        impl Kem for $name {
+            fn algorithm(&self) -> KemAlgorithm {
+                $algorithm
+            }
 
-impl Kem for Kyber512 {
-    fn keygen(&self) -> Result<(PublicKey, SecretKey), KemError> {
-        let (pk_bytes, sk_bytes) = kyber512::keypair();
-        Ok((
-            PublicKey::from_vec(pk_bytes.as_bytes().to_vec()),
-            SecretKey::from_vec(sk_bytes.as_bytes().to_vec()),
-        ))
-    }
+            fn keygen(&self) -> Result<(PublicKey, SecretKey), KemError> {
+                let (pk_bytes, sk_bytes) = $backend::keypair();
+                Ok((
+                    PublicKey::tagged($algorithm, pk_bytes.as_bytes().to_vec()),
+                    SecretKey::from_vec(sk_bytes.as_bytes().to_vec()),
+                ))
+            }
 
-    fn encaps(&self, pk: &PublicKey) -> Result<(Ciphertext, SharedSecret), KemError> {
-        if pk.as_ref().len() != kyber512::public_key_bytes() {
-            return Err(KemError::InvalidKeySize);
-        }
-        
-        let pk_kyber = kyber512::PublicKey::from_bytes(pk.as_ref())
-            .map_err(|_| KemError::InvalidKeySize)?;
-        
-        let (ss_bytes, ct_bytes) = kyber512::encapsulate(&pk_kyber);
-        
-        Ok((
-            Ciphertext::from_vec(ct_bytes.as_bytes().to_vec()),
-            SharedSecret::from_vec(ss_bytes.as_bytes().to_vec()),
-        ))
-    }
-
-    fn decaps(&self, ct: &Ciphertext, sk: &SecretKey) -> Result<SharedSecret, KemError> {
-        if ct.as_ref().len() != kyber512::ciphertext_bytes() {
-            return Err(KemError::InvalidCiphertextSize);
-        }
-        if sk.as_ref().len() != kyber512::secret_key_bytes() {
-            return Err(KemError::InvalidKeySize);
-        }
+            fn encaps(&self, pk: &PublicKey) -> Result<(Ciphertext, SharedSecret), KemError> {
+                pk.check_algorithm($algorithm)?;
+                if pk.as_ref().len() != $backend::public_key_bytes() {
+                    return Err(KemError::InvalidKeySize);
+                }
+
+                let pk_kyber = $backend::PublicKey::from_bytes(pk.as_ref())
+                    .map_err(|_| KemError::InvalidKeySize)?;
+
+                let (ss_bytes, ct_bytes) = $backend::encapsulate(&pk_kyber);
+
+                Ok((
+                    Ciphertext::from_vec(ct_bytes.as_bytes().to_vec()),
+                    SharedSecret::from_vec(ss_bytes.as_bytes().to_vec()),
+                ))
+            }
 
-        let ct_kyber = kyber512::Ciphertext::from_bytes(ct.as_ref())
-            .map_err(|_| KemError::InvalidCiphertextSize)?;
-        let sk_kyber = kyber512::SecretKey::from_bytes(sk.as_ref())
-            .map_err(|_| KemError::InvalidKeySize)?;
+            fn decaps(&self, ct: &Ciphertext, sk: &SecretKey) -> Result<SharedSecret, KemError> {
+                if ct.as_ref().len() != $backend::ciphertext_bytes() {
+                    return Err(KemError::InvalidCiphertextSize);
+                }
+                if sk.as_ref().len() != $backend::secret_key_bytes() {
+                    return Err(KemError::InvalidKeySize);
+                }
 
-        let ss_bytes = kyber512::decapsulate(&ct_kyber, &sk_kyber);
-        
-        Ok(SharedSecret::from_vec(ss_bytes.as_bytes().to_vec()))
-    }
+                let ct_kyber = $backend::Ciphertext::from_bytes(ct.as_ref())
+                    .map_err(|_| KemError::InvalidCiphertextSize)?;
+                let sk_kyber = $backend::SecretKey::from_bytes(sk.as_ref())
+                    .map_err(|_| KemError::InvalidKeySize)?;
 
-    fn public_key_bytes(&self) -> usize {
-        kyber512::public_key_bytes()
-    }
+                let ss_bytes = $backend::decapsulate(&ct_kyber, &sk_kyber);
 
-    fn secret_key_bytes(&self) -> usize {
-        kyber512::secret_key_bytes()
-    }
+                Ok(SharedSecret::from_vec(ss_bytes.as_bytes().to_vec()))
+            }
 
-    fn ciphertext_bytes(&self) -> usize {
-        kyber512::ciphertext_bytes()
-    }
+            fn keygen_from_seed(&self, seed: &[u8]) -> Result<(PublicKey, SecretKey), KemError> {
+                native_kyber::keygen_from_seed(&$params, $algorithm, seed)
+            }
+
+            fn encaps_deterministic(&self, pk: &PublicKey, coins: &[u8]) -> Result<(Ciphertext, SharedSecret), KemError> {
+                native_kyber::encaps_deterministic(&$params, $algorithm, pk, coins)
+            }
+
+            fn keygen_seed_bytes(&self) -> Option<usize> {
+                Some(native_kyber::KEYGEN_SEED_BYTES)
+            }
+
+            fn encaps_coins_bytes(&self) -> Option<usize> {
+                Some(native_kyber::ENCAPS_COINS_BYTES)
+            }
+
+            fn public_key_bytes(&self) -> usize {
+                $backend::public_key_bytes()
+            }
+
+            fn secret_key_bytes(&self) -> usize {
+                $backend::secret_key_bytes()
+            }
+
+            fn ciphertext_bytes(&self) -> usize {
+                $backend::ciphertext_bytes()
+            }
+
+            fn shared_secret_bytes(&self) -> usize {
+                $backend::shared_secret_bytes()
+            }
+        }
+    };
+}
 
-    fn shared_secret_bytes(&self) -> usize {
-        kyber512::shared_secret_bytes()
-    }
-}
\ No newline at end of file
+kyber_kem!(
+    /// Kyber512 (NIST security level 1).
+    Kyber512, kyber512, native_kyber::KYBER512, KemAlgorithm::Kyber512
+);
+kyber_kem!(
+    /// Kyber768 (NIST security level 3).
+    Kyber768, kyber768, native_kyber::KYBER768, KemAlgorithm::Kyber768
+);
+kyber_kem!(
+    /// Kyber1024 (NIST security level 5).
+    Kyber1024, kyber1024, native_kyber::KYBER1024, KemAlgorithm::Kyber1024
+);
@@ -0,0 +1,201 @@
+//! FIPS 203 ML-KEM, backed by the pure-Rust `ml-kem` crate.
+//!
+//! Unlike the round-3 Kyber in [`super::kyber`], ML-KEM uses the final FIPS 203
+//! domain separation, so keys and ciphertexts are not interchangeable between
+//! the two even though their sizes match. Keys are tagged with their
+//! [`KemAlgorithm`] so peers can be migrated without confusing the two.
+
+use ml_kem::array::typenum::Unsigned;
+use ml_kem::kem::{Decapsulate, Encapsulate};
+use ml_kem::{B32, EncapsulateDeterministic, Encoded, EncodedSizeUser, KemCore};
+use rand::rngs::OsRng;
+use sha3::{Digest, Sha3_256};
+use super::kem::{Kem, KemAlgorithm, KemError, PublicKey, SecretKey, Ciphertext, SharedSecret};
+
+/// ML-KEM modulus q.
+const Q: u16 = 3329;
+
+/// Size of one ByteEncode12-encoded polynomial.
+const POLY_BYTES: usize = 384;
+
+/// FIPS 203 §7.2 modulus check: every 12-bit coefficient of the encoded
+/// vector `t̂` must already be reduced modulo q.
+fn check_encapsulation_key(ek: &[u8], k: usize) -> Result<(), KemError> {
+    let t_hat = &ek[..POLY_BYTES * k];
+    for chunk in t_hat.chunks_exact(3) {
+        let a = u16::from(chunk[0]) | (u16::from(chunk[1] & 0x0f) << 8);
+        let b = u16::from(chunk[1] >> 4) | (u16::from(chunk[2]) << 4);
+        if a >= Q || b >= Q {
+            return Err(KemError::InvalidEncapsulationKey);
+        }
+    }
+    Ok(())
+}
+
+/// FIPS 203 §7.3 hash check: the `H(ek)` stored in the decapsulation key must
+/// match the embedded encapsulation key.
+fn check_decapsulation_key(dk: &[u8], k: usize) -> Result<(), KemError> {
+    let ek_start = POLY_BYTES * k;
+    let h_start = ek_start + POLY_BYTES * k + 32;
+    let h = Sha3_256::digest(&dk[ek_start..h_start]);
+    if h.as_slice() != &dk[h_start..h_start + 32] {
+        return Err(KemError::InvalidDecapsulationKey);
+    }
+    Ok(())
+}
+
+fn keygen<K: KemCore>(algorithm: KemAlgorithm) -> (PublicKey, SecretKey) {
+    let (dk, ek) = K::generate(&mut OsRng);
+    (
+        PublicKey::tagged(algorithm, ek.as_bytes().to_vec()),
+        SecretKey::from_vec(dk.as_bytes().to_vec()),
+    )
+}
+
+/// Seed size for deterministic key generation: `d || z`.
+const KEYGEN_SEED_BYTES: usize = 64;
+
+/// Coins size for deterministic encapsulation: `m`.
+const ENCAPS_COINS_BYTES: usize = 32;
+
+fn keygen_from_seed<K: KemCore>(seed: &[u8], algorithm: KemAlgorithm) -> Result<(PublicKey, SecretKey), KemError> {
+    if seed.len() != KEYGEN_SEED_BYTES {
+        return Err(KemError::InvalidSeedSize);
+    }
+    let d = B32::try_from(&seed[..32]).map_err(|_| KemError::InvalidSeedSize)?;
+    let z = B32::try_from(&seed[32..]).map_err(|_| KemError::InvalidSeedSize)?;
+
+    let (dk, ek) = K::generate_deterministic(&d, &z);
+    Ok((
+        PublicKey::tagged(algorithm, ek.as_bytes().to_vec()),
+        SecretKey::from_vec(dk.as_bytes().to_vec()),
+    ))
+}
+
+fn parse_encapsulation_key<K: KemCore>(pk: &PublicKey, algorithm: KemAlgorithm, k: usize) -> Result<K::EncapsulationKey, KemError> {
+    pk.check_algorithm(algorithm)?;
+    let enc = Encoded::<K::EncapsulationKey>::try_from(pk.as_ref())
+        .map_err(|_| KemError::InvalidKeySize)?;
+    check_encapsulation_key(pk.as_ref(), k)?;
+    Ok(K::EncapsulationKey::from_bytes(&enc))
+}
+
+fn encaps_deterministic<K: KemCore>(pk: &PublicKey, coins: &[u8], algorithm: KemAlgorithm, k: usize) -> Result<(Ciphertext, SharedSecret), KemError> {
+    let m = B32::try_from(coins).map_err(|_| KemError::InvalidSeedSize)?;
+    let ek = parse_encapsulation_key::<K>(pk, algorithm, k)?;
+    let (ct, ss) = ek.encapsulate_deterministic(&m)
+        .map_err(|_| KemError::EncapsulationError)?;
+
+    Ok((
+        Ciphertext::from_vec(ct.to_vec()),
+        SharedSecret::from_vec(ss.to_vec()),
+    ))
+}
+
+fn encaps<K: KemCore>(pk: &PublicKey, algorithm: KemAlgorithm, k: usize) -> Result<(Ciphertext, SharedSecret), KemError> {
+    let ek = parse_encapsulation_key::<K>(pk, algorithm, k)?;
+    let (ct, ss) = ek.encapsulate(&mut OsRng)
+        .map_err(|_| KemError::EncapsulationError)?;
+
+    Ok((
+        Ciphertext::from_vec(ct.to_vec()),
+        SharedSecret::from_vec(ss.to_vec()),
+    ))
+}
+
+fn decaps<K: KemCore>(ct: &Ciphertext, sk: &SecretKey, k: usize) -> Result<SharedSecret, KemError> {
+    let ct_arr = ml_kem::Ciphertext::<K>::try_from(ct.as_ref())
+        .map_err(|_| KemError::InvalidCiphertextSize)?;
+    let enc = Encoded::<K::DecapsulationKey>::try_from(sk.as_ref())
+        .map_err(|_| KemError::InvalidKeySize)?;
+    check_decapsulation_key(sk.as_ref(), k)?;
+
+    let dk = K::DecapsulationKey::from_bytes(&enc);
+    let ss = dk.decapsulate(&ct_arr)
+        .map_err(|_| KemError::DecapsulationError)?;
+
+    Ok(SharedSecret::from_vec(ss.to_vec()))
+}
+
+/// Generates a unit-like ML-KEM parameter set wrapping one of the `ml_kem`
+/// parameter sets, with `$k` the module rank.
+macro_rules! ml_kem_kem {
+    ($(#[$meta:meta])* $name:ident, $backend:ty, $algorithm:expr, $k:expr) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Default)]
+        pub struct $name {
+            _phantom: std::marker::PhantomData<()>,
+        }
+
+        impl $name {
+            pub fn new() -> Self {
+                Self {
+                    _phantom: std::marker::PhantomData,
+                }
+            }
+        }
+
+        impl Kem for $name {
+            fn algorithm(&self) -> KemAlgorithm {
+                $algorithm
+            }
+
+            fn keygen(&self) -> Result<(PublicKey, SecretKey), KemError> {
+                Ok(keygen::<$backend>($algorithm))
+            }
+
+            fn encaps(&self, pk: &PublicKey) -> Result<(Ciphertext, SharedSecret), KemError> {
+                encaps::<$backend>(pk, $algorithm, $k)
+            }
+
+            fn decaps(&self, ct: &Ciphertext, sk: &SecretKey) -> Result<SharedSecret, KemError> {
+                decaps::<$backend>(ct, sk, $k)
+            }
+
+            fn public_key_bytes(&self) -> usize {
+                <<$backend as KemCore>::EncapsulationKey as EncodedSizeUser>::EncodedSize::USIZE
+            }
+
+            fn secret_key_bytes(&self) -> usize {
+                <<$backend as KemCore>::DecapsulationKey as EncodedSizeUser>::EncodedSize::USIZE
+            }
+
+            fn ciphertext_bytes(&self) -> usize {
+                <$backend as KemCore>::CiphertextSize::USIZE
+            }
+
+            fn shared_secret_bytes(&self) -> usize {
+                <$backend as KemCore>::SharedKeySize::USIZE
+            }
+
+            fn keygen_from_seed(&self, seed: &[u8]) -> Result<(PublicKey, SecretKey), KemError> {
+                keygen_from_seed::<$backend>(seed, $algorithm)
+            }
+
+            fn encaps_deterministic(&self, pk: &PublicKey, coins: &[u8]) -> Result<(Ciphertext, SharedSecret), KemError> {
+                encaps_deterministic::<$backend>(pk, coins, $algorithm, $k)
+            }
+
+            fn keygen_seed_bytes(&self) -> Option<usize> {
+                Some(KEYGEN_SEED_BYTES)
+            }
+
+            fn encaps_coins_bytes(&self) -> Option<usize> {
+                Some(ENCAPS_COINS_BYTES)
+            }
+        }
+    };
+}
+
+ml_kem_kem!(
+    /// ML-KEM-512 (NIST security category 1).
+    MlKem512, ml_kem::MlKem512, KemAlgorithm::MlKem512, 2
+);
+ml_kem_kem!(
+    /// ML-KEM-768 (NIST security category 3).
+    MlKem768, ml_kem::MlKem768, KemAlgorithm::MlKem768, 3
+);
+ml_kem_kem!(
+    /// ML-KEM-1024 (NIST security category 5).
+    MlKem1024, ml_kem::MlKem1024, KemAlgorithm::MlKem1024, 4
+);
@@ -1,5 +1,18 @@
 pub mod kyber;
+pub mod mlkem;
+pub mod hybrid;
+pub mod registry;
+pub mod pool;
+#[cfg(feature = "native-kyber")]
+pub mod native_kyber;
+#[cfg(not(feature = "native-kyber"))]
+mod native_kyber;
+#[allow(clippy::module_inception)]
 pub mod kem;
 
-pub use self::kem::Kem;
-pub use self::kyber::Kyber512;
\ No newline at end of file
+pub use self::kem::{Kem, KemAlgorithm};
+pub use self::kyber::{Kyber512, Kyber768, Kyber1024};
+pub use self::mlkem::{MlKem512, MlKem768, MlKem1024};
+#[cfg(feature = "native-kyber")]
+pub use self::native_kyber::{NativeKyber512, NativeKyber768, NativeKyber1024};
+pub use self::hybrid::{HybridKem, X25519Kyber512, X25519Kyber768, X25519Kyber1024, X25519MlKem512, X25519MlKem768, X25519MlKem1024};
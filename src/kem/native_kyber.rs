@@ -0,0 +1,476 @@
+//! Pure-Rust round-3 Kyber. The `NativeKyber*` types are enabled with the
+//! `native-kyber` feature; the seeded key generation and encapsulation
+//! behind them are always built, since the `pqcrypto-kyber` types in
+//! [`super::kyber`] use them for `keygen_from_seed`/`encaps_deterministic`.
+//!
+//! Built entirely on [`crate::math`]: the negacyclic NTT, the uniform and
+//! centered binomial samplers and the bit-packing encoders. Keys,
+//! ciphertexts and shared secrets are byte-for-byte identical to the
+//! `pqcrypto-kyber` backend in [`super::kyber`], and keys carry the same
+//! [`KemAlgorithm`] tag, so the two can be mixed freely.
+//!
+//! Seeds follow the order in which the reference implementation draws its
+//! randomness: key generation takes `d || z` (64 bytes), encapsulation takes
+//! the 32-byte message `m` before it is hashed.
+
+// Without the feature only key generation and encapsulation are reachable
+#![cfg_attr(not(feature = "native-kyber"), allow(dead_code))]
+
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+use sha3::{Digest, Sha3_256, Sha3_512, Shake128, Shake256};
+use std::sync::OnceLock;
+use subtle::{ConditionallySelectable, ConstantTimeEq};
+use zeroize::{Zeroize, Zeroizing};
+#[cfg(feature = "native-kyber")]
+use rand::RngCore;
+#[cfg(feature = "native-kyber")]
+use rand::rngs::OsRng;
+use crate::math::encode::{pack_bits, unpack_bits};
+use crate::math::ntt::{bit_reversed_powers, inv_ntt_negacyclic, ntt_negacyclic};
+use crate::math::sample::{centered_binomial, sample_uniform};
+#[cfg(feature = "native-kyber")]
+use super::kem::Kem;
+use super::kem::{KemAlgorithm, KemError, PublicKey, SecretKey, Ciphertext, SharedSecret};
+
+const N: usize = 256;
+const Q: i64 = 3329;
+const ROOT_OF_UNITY: i64 = 17;
+const SYM_BYTES: usize = 32;
+const POLY_BYTES: usize = 384;
+
+/// Seed size for deterministic key generation: `d || z`.
+pub(crate) const KEYGEN_SEED_BYTES: usize = 2 * SYM_BYTES;
+
+/// Coins size for deterministic encapsulation: `m`.
+pub(crate) const ENCAPS_COINS_BYTES: usize = SYM_BYTES;
+
+type Poly = Vec<i64>;
+
+/// Parameters for one Kyber security level.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct KyberParams {
+    k: usize,
+    eta1: u32,
+    eta2: u32,
+    du: u32,
+    dv: u32,
+}
+
+pub(crate) const KYBER512: KyberParams = KyberParams { k: 2, eta1: 3, eta2: 2, du: 10, dv: 4 };
+pub(crate) const KYBER768: KyberParams = KyberParams { k: 3, eta1: 2, eta2: 2, du: 10, dv: 4 };
+pub(crate) const KYBER1024: KyberParams = KyberParams { k: 4, eta1: 2, eta2: 2, du: 11, dv: 5 };
+
+impl KyberParams {
+    fn indcpa_public_key_bytes(&self) -> usize {
+        self.k * POLY_BYTES + SYM_BYTES
+    }
+
+    fn indcpa_secret_key_bytes(&self) -> usize {
+        self.k * POLY_BYTES
+    }
+
+    fn public_key_bytes(&self) -> usize {
+        self.indcpa_public_key_bytes()
+    }
+
+    fn secret_key_bytes(&self) -> usize {
+        self.indcpa_secret_key_bytes() + self.indcpa_public_key_bytes() + 2 * SYM_BYTES
+    }
+
+    fn ciphertext_bytes(&self) -> usize {
+        (self.k * self.du as usize + self.dv as usize) * N / 8
+    }
+}
+
+fn zetas() -> &'static [i64] {
+    static ZETAS: OnceLock<Vec<i64>> = OnceLock::new();
+    ZETAS.get_or_init(|| bit_reversed_powers(ROOT_OF_UNITY, Q, N / 2))
+}
+
+fn ntt(p: &mut Poly) {
+    ntt_negacyclic(p, zetas(), Q, 2);
+}
+
+fn inv_ntt(p: &mut Poly) {
+    inv_ntt_negacyclic(p, zetas(), Q, 2);
+}
+
+/// Multiplies two polynomials in the NTT domain and adds the product to
+/// `acc`. The NTT stops at degree-one residues, so coefficients are
+/// multiplied pairwise: pair `i` is reduced modulo `X^2 - gamma` with
+/// `gamma = ±zetas[64 + i/2]` (`+` for even `i`).
+fn basemul_acc(acc: &mut Poly, a: &Poly, b: &Poly) {
+    let zetas = zetas();
+    for i in 0..N / 2 {
+        let zeta = zetas[N / 4 + i / 2];
+        let gamma = if i % 2 == 0 { zeta } else { Q - zeta };
+        let (a0, a1, b0, b1) = (a[2 * i], a[2 * i + 1], b[2 * i], b[2 * i + 1]);
+        acc[2 * i] = (acc[2 * i] + a0 * b0 + (a1 * b1 % Q) * gamma) % Q;
+        acc[2 * i + 1] = (acc[2 * i + 1] + a0 * b1 + a1 * b0) % Q;
+    }
+}
+
+/// Inner product of two vectors in the NTT domain.
+fn inner_product(a: &[Poly], b: &[Poly]) -> Poly {
+    let mut acc = vec![0; N];
+    for (a_i, b_i) in a.iter().zip(b) {
+        basemul_acc(&mut acc, a_i, b_i);
+    }
+    acc
+}
+
+fn add_assign(a: &mut Poly, b: &Poly) {
+    for (x, y) in a.iter_mut().zip(b) {
+        *x = (*x + y) % Q;
+    }
+}
+
+/// Rounds `x` to `d` bits: `round(2^d / q * x) mod 2^d`. `Q` is a constant,
+/// so the division compiles to a multiplication rather than a data-dependent
+/// `div` instruction.
+fn compress(x: i64, d: u32) -> u32 {
+    ((((x << d) + Q / 2) / Q) & ((1 << d) - 1)) as u32
+}
+
+fn decompress(y: u32, d: u32) -> i64 {
+    (i64::from(y) * Q + (1 << (d - 1))) >> d
+}
+
+/// Serializes a polynomial with 12 bits per coefficient.
+fn poly_to_bytes(p: &Poly) -> Vec<u8> {
+    let values: Vec<u32> = p.iter().map(|&c| c as u32).collect();
+    pack_bits(&values, 12)
+}
+
+fn poly_from_bytes(bytes: &[u8]) -> Poly {
+    unpack_bits(bytes, 12, N).into_iter().map(i64::from).collect()
+}
+
+fn poly_compress(p: &Poly, d: u32) -> Vec<u8> {
+    let values: Vec<u32> = p.iter().map(|&c| compress(c, d)).collect();
+    pack_bits(&values, d)
+}
+
+fn poly_decompress(bytes: &[u8], d: u32) -> Poly {
+    unpack_bits(bytes, d, N).into_iter().map(|v| decompress(v, d)).collect()
+}
+
+fn polyvec_to_bytes(v: &[Poly]) -> Vec<u8> {
+    v.iter().flat_map(poly_to_bytes).collect()
+}
+
+fn polyvec_from_bytes(bytes: &[u8]) -> Vec<Poly> {
+    bytes.chunks_exact(POLY_BYTES).map(poly_from_bytes).collect()
+}
+
+fn polyvec_compress(v: &[Poly], d: u32) -> Vec<u8> {
+    v.iter().flat_map(|p| poly_compress(p, d)).collect()
+}
+
+fn polyvec_decompress(bytes: &[u8], d: u32) -> Vec<Poly> {
+    bytes.chunks_exact(N * d as usize / 8).map(|chunk| poly_decompress(chunk, d)).collect()
+}
+
+/// Expands the public matrix `A` (or its transpose) from `rho`.
+fn gen_matrix(rho: &[u8], k: usize, transposed: bool) -> Vec<Vec<Poly>> {
+    (0..k)
+        .map(|i| {
+            (0..k)
+                .map(|j| {
+                    let (x, y) = if transposed { (i as u8, j as u8) } else { (j as u8, i as u8) };
+                    let mut xof = Shake128::default();
+                    xof.update(rho);
+                    xof.update(&[x, y]);
+                    sample_uniform(&mut xof.finalize_xof(), Q, 12, N)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Samples a noise polynomial from `PRF(seed, nonce)`.
+fn sample_noise(seed: &[u8], nonce: u8, eta: u32) -> Poly {
+    let mut prf = Shake256::default();
+    prf.update(seed);
+    prf.update(&[nonce]);
+    let mut buf = Zeroizing::new(vec![0u8; eta as usize * N / 4]);
+    prf.finalize_xof().read(&mut buf);
+    centered_binomial(&buf, eta, Q)
+}
+
+/// `G = SHA3-512`, returned in a buffer that is wiped on drop.
+fn hash_g(input: &[u8]) -> Zeroizing<[u8; 2 * SYM_BYTES]> {
+    let mut out = Zeroizing::new([0u8; 2 * SYM_BYTES]);
+    out.copy_from_slice(&Sha3_512::digest(input));
+    out
+}
+
+fn wipe(polys: &mut [Poly]) {
+    polys.iter_mut().for_each(|p| p.zeroize());
+}
+
+fn indcpa_keypair(params: &KyberParams, d: &[u8]) -> (Vec<u8>, Zeroizing<Vec<u8>>) {
+    let k = params.k;
+    let g = hash_g(d);
+    let (rho, sigma) = g.split_at(SYM_BYTES);
+
+    let a = gen_matrix(rho, k, false);
+    let mut s: Vec<Poly> = (0..k).map(|i| sample_noise(sigma, i as u8, params.eta1)).collect();
+    let mut e: Vec<Poly> = (0..k).map(|i| sample_noise(sigma, (k + i) as u8, params.eta1)).collect();
+    s.iter_mut().chain(e.iter_mut()).for_each(ntt);
+
+    let t: Vec<Poly> = a
+        .iter()
+        .zip(&e)
+        .map(|(row, e_i)| {
+            let mut t_i = inner_product(row, &s);
+            add_assign(&mut t_i, e_i);
+            t_i
+        })
+        .collect();
+
+    let mut pk = polyvec_to_bytes(&t);
+    pk.extend_from_slice(rho);
+    let sk = Zeroizing::new(polyvec_to_bytes(&s));
+
+    wipe(&mut s);
+    wipe(&mut e);
+    (pk, sk)
+}
+
+fn indcpa_enc(params: &KyberParams, pk: &[u8], m: &[u8], coins: &[u8]) -> Vec<u8> {
+    let k = params.k;
+    let (t_bytes, rho) = pk.split_at(k * POLY_BYTES);
+    let t = polyvec_from_bytes(t_bytes);
+    let at = gen_matrix(rho, k, true);
+
+    let mut r: Vec<Poly> = (0..k).map(|i| sample_noise(coins, i as u8, params.eta1)).collect();
+    let mut e1: Vec<Poly> = (0..k).map(|i| sample_noise(coins, (k + i) as u8, params.eta2)).collect();
+    let mut e2 = sample_noise(coins, (2 * k) as u8, params.eta2);
+    r.iter_mut().for_each(ntt);
+
+    let u: Vec<Poly> = at
+        .iter()
+        .zip(&e1)
+        .map(|(row, e1_i)| {
+            let mut u_i = inner_product(row, &r);
+            inv_ntt(&mut u_i);
+            add_assign(&mut u_i, e1_i);
+            u_i
+        })
+        .collect();
+
+    let mut v = inner_product(&t, &r);
+    inv_ntt(&mut v);
+    add_assign(&mut v, &e2);
+    let mut msg = poly_decompress(m, 1);
+    add_assign(&mut v, &msg);
+
+    let mut ct = polyvec_compress(&u, params.du);
+    ct.extend_from_slice(&poly_compress(&v, params.dv));
+
+    wipe(&mut r);
+    wipe(&mut e1);
+    e2.zeroize();
+    msg.zeroize();
+    v.zeroize();
+    ct
+}
+
+fn indcpa_dec(params: &KyberParams, ct: &[u8], sk: &[u8]) -> Zeroizing<Vec<u8>> {
+    let k = params.k;
+    let (u_bytes, v_bytes) = ct.split_at(k * params.du as usize * N / 8);
+    let mut u = polyvec_decompress(u_bytes, params.du);
+    let v = poly_decompress(v_bytes, params.dv);
+    let mut s = polyvec_from_bytes(sk);
+    u.iter_mut().for_each(ntt);
+
+    let mut w = inner_product(&s, &u);
+    inv_ntt(&mut w);
+    for (w_i, v_i) in w.iter_mut().zip(&v) {
+        *w_i = (v_i - *w_i).rem_euclid(Q);
+    }
+    let m = Zeroizing::new(poly_compress(&w, 1));
+
+    wipe(&mut s);
+    w.zeroize();
+    m
+}
+
+fn keypair(params: &KyberParams, d: &[u8], z: &[u8]) -> (Vec<u8>, Vec<u8>) {
+    let (pk, sk_cpa) = indcpa_keypair(params, d);
+    let mut sk = Vec::with_capacity(params.secret_key_bytes());
+    sk.extend_from_slice(&sk_cpa);
+    sk.extend_from_slice(&pk);
+    sk.extend_from_slice(&Sha3_256::digest(&pk));
+    sk.extend_from_slice(z);
+    (pk, sk)
+}
+
+fn kdf(pre_key: &[u8], ct: &[u8]) -> SharedSecret {
+    let mut xof = Shake256::default();
+    xof.update(pre_key);
+    xof.update(&Sha3_256::digest(ct));
+    let mut ss = vec![0u8; SYM_BYTES];
+    xof.finalize_xof().read(&mut ss);
+    SharedSecret::from_vec(ss)
+}
+
+fn encapsulate(params: &KyberParams, pk: &[u8], coins: &[u8]) -> (Ciphertext, SharedSecret) {
+    // The raw coins are hashed so system RNG output is never exposed
+    let mut buf = Zeroizing::new([0u8; 2 * SYM_BYTES]);
+    buf[..SYM_BYTES].copy_from_slice(&Sha3_256::digest(coins));
+    buf[SYM_BYTES..].copy_from_slice(&Sha3_256::digest(pk));
+    let kr = hash_g(buf.as_ref());
+
+    let ct = indcpa_enc(params, pk, &buf[..SYM_BYTES], &kr[SYM_BYTES..]);
+    let ss = kdf(&kr[..SYM_BYTES], &ct);
+    (Ciphertext::from_vec(ct), ss)
+}
+
+fn decapsulate(params: &KyberParams, ct: &[u8], sk: &[u8]) -> SharedSecret {
+    let (sk_cpa, rest) = sk.split_at(params.indcpa_secret_key_bytes());
+    let (pk, rest) = rest.split_at(params.indcpa_public_key_bytes());
+    let (h, z) = rest.split_at(SYM_BYTES);
+
+    let m = indcpa_dec(params, ct, sk_cpa);
+    let mut buf = Zeroizing::new([0u8; 2 * SYM_BYTES]);
+    buf[..SYM_BYTES].copy_from_slice(&m);
+    buf[SYM_BYTES..].copy_from_slice(h);
+    let kr = hash_g(buf.as_ref());
+
+    // Implicit rejection: fall back to z if re-encryption does not match
+    let cmp = indcpa_enc(params, pk, &m, &kr[SYM_BYTES..]);
+    let matches = ct.ct_eq(&cmp);
+    let mut pre_key = Zeroizing::new([0u8; SYM_BYTES]);
+    for (out, (k_i, z_i)) in pre_key.iter_mut().zip(kr[..SYM_BYTES].iter().zip(z)) {
+        *out = u8::conditional_select(z_i, k_i, matches);
+    }
+    kdf(pre_key.as_ref(), ct)
+}
+
+/// Derives a keypair for `params` from a `d || z` seed.
+pub(crate) fn keygen_from_seed(
+    params: &KyberParams,
+    algorithm: KemAlgorithm,
+    seed: &[u8],
+) -> Result<(PublicKey, SecretKey), KemError> {
+    if seed.len() != KEYGEN_SEED_BYTES {
+        return Err(KemError::InvalidSeedSize);
+    }
+    let (d, z) = seed.split_at(SYM_BYTES);
+    let (pk, sk) = keypair(params, d, z);
+    Ok((PublicKey::tagged(algorithm, pk), SecretKey::from_vec(sk)))
+}
+
+/// Encapsulates to `pk` with the message `m` taken from `coins`.
+pub(crate) fn encaps_deterministic(
+    params: &KyberParams,
+    algorithm: KemAlgorithm,
+    pk: &PublicKey,
+    coins: &[u8],
+) -> Result<(Ciphertext, SharedSecret), KemError> {
+    pk.check_algorithm(algorithm)?;
+    if pk.as_ref().len() != params.public_key_bytes() {
+        return Err(KemError::InvalidKeySize);
+    }
+    if coins.len() != ENCAPS_COINS_BYTES {
+        return Err(KemError::InvalidSeedSize);
+    }
+    Ok(encapsulate(params, pk.as_ref(), coins))
+}
+
+#[cfg(feature = "native-kyber")]
+macro_rules! native_kyber_kem {
+    ($(#[$meta:meta])* $name:ident, $params:expr, $algorithm:expr) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Default)]
+        pub struct $name {
+            _phantom: std::marker::PhantomData<()>,
+        }
+
+        impl $name {
+            pub fn new() -> Self {
+                Self {
+                    _phantom: std::marker::PhantomData,
+                }
+            }
+        }
+
+        impl Kem for $name {
+            fn algorithm(&self) -> KemAlgorithm {
+                $algorithm
+            }
+
+            fn keygen(&self) -> Result<(PublicKey, SecretKey), KemError> {
+                let mut seed = Zeroizing::new([0u8; KEYGEN_SEED_BYTES]);
+                OsRng.try_fill_bytes(seed.as_mut())?;
+                self.keygen_from_seed(seed.as_ref())
+            }
+
+            fn encaps(&self, pk: &PublicKey) -> Result<(Ciphertext, SharedSecret), KemError> {
+                let mut coins = Zeroizing::new([0u8; ENCAPS_COINS_BYTES]);
+                OsRng.try_fill_bytes(coins.as_mut())?;
+                self.encaps_deterministic(pk, coins.as_ref())
+            }
+
+            fn decaps(&self, ct: &Ciphertext, sk: &SecretKey) -> Result<SharedSecret, KemError> {
+                if ct.as_ref().len() != $params.ciphertext_bytes() {
+                    return Err(KemError::InvalidCiphertextSize);
+                }
+                if sk.as_ref().len() != $params.secret_key_bytes() {
+                    return Err(KemError::InvalidKeySize);
+                }
+                Ok(decapsulate(&$params, ct.as_ref(), sk.as_ref()))
+            }
+
+            fn keygen_from_seed(&self, seed: &[u8]) -> Result<(PublicKey, SecretKey), KemError> {
+                keygen_from_seed(&$params, $algorithm, seed)
+            }
+
+            fn encaps_deterministic(&self, pk: &PublicKey, coins: &[u8]) -> Result<(Ciphertext, SharedSecret), KemError> {
+                encaps_deterministic(&$params, $algorithm, pk, coins)
+            }
+
+            fn keygen_seed_bytes(&self) -> Option<usize> {
+                Some(KEYGEN_SEED_BYTES)
+            }
+
+            fn encaps_coins_bytes(&self) -> Option<usize> {
+                Some(ENCAPS_COINS_BYTES)
+            }
+
+            fn public_key_bytes(&self) -> usize {
+                $params.public_key_bytes()
+            }
+
+            fn secret_key_bytes(&self) -> usize {
+                $params.secret_key_bytes()
+            }
+
+            fn ciphertext_bytes(&self) -> usize {
+                $params.ciphertext_bytes()
+            }
+
+            fn shared_secret_bytes(&self) -> usize {
+                SYM_BYTES
+            }
+        }
+    };
+}
+
+#[cfg(feature = "native-kyber")]
+native_kyber_kem!(
+    /// Pure-Rust Kyber512 (NIST security level 1).
+    NativeKyber512, KYBER512, KemAlgorithm::Kyber512
+);
+#[cfg(feature = "native-kyber")]
+native_kyber_kem!(
+    /// Pure-Rust Kyber768 (NIST security level 3).
+    NativeKyber768, KYBER768, KemAlgorithm::Kyber768
+);
+#[cfg(feature = "native-kyber")]
+native_kyber_kem!(
+    /// Pure-Rust Kyber1024 (NIST security level 5).
+    NativeKyber1024, KYBER1024, KemAlgorithm::Kyber1024
+);
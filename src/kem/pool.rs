@@ -0,0 +1,177 @@
+//! Background pool of pre-generated ephemeral KEM keypairs.
+//!
+//! Key generation is the slowest step of a handshake. A [`KeyPool`] keeps up
+//! to `size` keypairs ready and refills itself on a background thread once
+//! the number of ready keypairs drops to `refill_threshold`, so a burst of
+//! handshakes only pays for a queue pop. If the pool runs dry, keypairs are
+//! generated inline instead.
+//!
+//! Unused secret keys are wiped when the pool is shut down or dropped.
+//!
+//! ```
+//! use pq_core::kem::pool::{KeyPool, PoolConfig};
+//! use pq_core::kem::Kyber512;
+//!
+//! let pool = KeyPool::new(Box::new(Kyber512::new()), PoolConfig { size: 16, refill_threshold: 4 });
+//! let (pk, sk) = pool.take().unwrap();
+//! pool.shutdown();
+//! ```
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::JoinHandle;
+use super::kem::{Kem, KemAlgorithm, KemError, PublicKey, SecretKey};
+
+/// Sizing for a [`KeyPool`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolConfig {
+    /// Keypairs to keep ready.
+    pub size: usize,
+    /// Refilling starts once this many or fewer keypairs are left.
+    pub refill_threshold: usize,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self { size: 32, refill_threshold: 8 }
+    }
+}
+
+struct State {
+    keys: VecDeque<(PublicKey, SecretKey)>,
+    refilling: bool,
+    shutdown: bool,
+}
+
+struct Shared {
+    kem: Box<dyn Kem>,
+    config: PoolConfig,
+    state: Mutex<State>,
+    wake: Condvar,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        // Keypairs are pushed and popped whole, so a poisoned queue is
+        // still consistent
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// A pool of ephemeral keypairs, refilled by a background thread.
+pub struct KeyPool {
+    shared: Arc<Shared>,
+    worker: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl KeyPool {
+    /// Creates a pool generating keypairs with `kem` and starts filling it.
+    ///
+    /// # Panics
+    ///
+    /// If `config.size` is zero or `config.refill_threshold` is not below
+    /// `config.size`.
+    pub fn new(kem: Box<dyn Kem>, config: PoolConfig) -> Self {
+        assert!(config.size > 0, "pool size must be non-zero");
+        assert!(config.refill_threshold < config.size, "refill threshold must be below the pool size");
+
+        let shared = Arc::new(Shared {
+            kem,
+            config,
+            state: Mutex::new(State { keys: VecDeque::with_capacity(config.size), refilling: true, shutdown: false }),
+            wake: Condvar::new(),
+        });
+        let worker = {
+            let shared = Arc::clone(&shared);
+            std::thread::Builder::new()
+                .name("pq-core-keypool".into())
+                .spawn(move || refill(&shared))
+                .expect("failed to spawn key pool thread")
+        };
+        Self { shared, worker: Mutex::new(Some(worker)) }
+    }
+
+    /// The algorithm of the pooled keypairs.
+    pub fn algorithm(&self) -> KemAlgorithm {
+        self.shared.kem.algorithm()
+    }
+
+    /// The pool's sizing.
+    pub fn config(&self) -> PoolConfig {
+        self.shared.config
+    }
+
+    /// The number of keypairs ready to be taken.
+    pub fn available(&self) -> usize {
+        self.shared.lock().keys.len()
+    }
+
+    /// Takes a pre-generated keypair, or generates one inline if the pool
+    /// is empty or shut down. Each keypair is handed out at most once.
+    pub fn take(&self) -> Result<(PublicKey, SecretKey), KemError> {
+        let keypair = {
+            let mut state = self.shared.lock();
+            let keypair = state.keys.pop_front();
+            if !state.shutdown && !state.refilling && state.keys.len() <= self.shared.config.refill_threshold {
+                state.refilling = true;
+                self.shared.wake.notify_one();
+            }
+            keypair
+        };
+        match keypair {
+            Some(keypair) => Ok(keypair),
+            None => self.shared.kem.keygen(),
+        }
+    }
+
+    /// Stops the background thread and wipes every unused keypair. Later
+    /// calls to [`take`](Self::take) generate keypairs inline.
+    pub fn shutdown(&self) {
+        self.shared.lock().shutdown = true;
+        self.shared.wake.notify_all();
+        let worker = self.worker.lock().unwrap_or_else(|e| e.into_inner()).take();
+        if let Some(worker) = worker {
+            let _ = worker.join();
+        }
+        // Secret keys zeroize themselves on drop
+        self.shared.lock().keys.clear();
+    }
+}
+
+impl Drop for KeyPool {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// The background thread: sleeps until the pool drops to the refill
+/// threshold, then generates keypairs until it is full again.
+fn refill(shared: &Shared) {
+    loop {
+        {
+            let mut state = shared.lock();
+            if state.keys.len() >= shared.config.size {
+                state.refilling = false;
+            }
+            while !state.shutdown && !state.refilling {
+                state = shared.wake.wait(state).unwrap_or_else(|e| e.into_inner());
+            }
+            if state.shutdown {
+                return;
+            }
+        }
+
+        // Generate outside the lock so takers are never blocked on keygen
+        match shared.kem.keygen() {
+            Ok(keypair) => {
+                let mut state = shared.lock();
+                if state.shutdown {
+                    return;
+                }
+                state.keys.push_back(keypair);
+            }
+            // Leave the pool to inline generation until the next take
+            Err(_) => shared.lock().refilling = false,
+        }
+    }
+}
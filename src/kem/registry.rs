@@ -0,0 +1,48 @@
+//! Runtime selection of KEM implementations.
+//!
+//! Config files and wire messages name a KEM by its [`KemAlgorithm`] id or
+//! name; the registry turns that into a ready-to-use `Box<dyn Kem>`, so new
+//! algorithms can be picked without touching calling code.
+//!
+//! ```
+//! use pq_core::kem::registry;
+//!
+//! let kem = registry::kem_from_name("ML-KEM-768").unwrap();
+//! let (pk, sk) = kem.keygen().unwrap();
+//! let (ct, ss) = kem.encaps(&pk).unwrap();
+//! assert_eq!(kem.decaps(&ct, &sk).unwrap(), ss);
+//! ```
+
+use super::kem::{Kem, KemAlgorithm, KemError};
+use super::{
+    Kyber512, Kyber768, Kyber1024, MlKem512, MlKem768, MlKem1024, X25519Kyber512, X25519Kyber768,
+    X25519Kyber1024, X25519MlKem512, X25519MlKem768, X25519MlKem1024,
+};
+
+/// Returns the default implementation of `algorithm`.
+pub fn new_kem(algorithm: KemAlgorithm) -> Box<dyn Kem> {
+    match algorithm {
+        KemAlgorithm::Kyber512 => Box::new(Kyber512::new()),
+        KemAlgorithm::Kyber768 => Box::new(Kyber768::new()),
+        KemAlgorithm::Kyber1024 => Box::new(Kyber1024::new()),
+        KemAlgorithm::MlKem512 => Box::new(MlKem512::new()),
+        KemAlgorithm::MlKem768 => Box::new(MlKem768::new()),
+        KemAlgorithm::MlKem1024 => Box::new(MlKem1024::new()),
+        KemAlgorithm::X25519Kyber512 => Box::new(X25519Kyber512::new()),
+        KemAlgorithm::X25519Kyber768 => Box::new(X25519Kyber768::new()),
+        KemAlgorithm::X25519Kyber1024 => Box::new(X25519Kyber1024::new()),
+        KemAlgorithm::X25519MlKem512 => Box::new(X25519MlKem512::new()),
+        KemAlgorithm::X25519MlKem768 => Box::new(X25519MlKem768::new()),
+        KemAlgorithm::X25519MlKem1024 => Box::new(X25519MlKem1024::new()),
+    }
+}
+
+/// Instantiates the KEM with numeric identifier `id`.
+pub fn kem_from_id(id: u16) -> Result<Box<dyn Kem>, KemError> {
+    KemAlgorithm::try_from(id).map(new_kem)
+}
+
+/// Instantiates the KEM named `name` (case-insensitive).
+pub fn kem_from_name(name: &str) -> Result<Box<dyn Kem>, KemError> {
+    name.parse().map(new_kem)
+}
@@ -6,6 +6,14 @@ pub mod math;
 pub mod sig;
 pub mod protocol;
 pub mod bidirectional;
+pub mod encoding;
+pub mod sealed;
+pub mod hpke;
+pub mod multi_recipient;
+pub mod cert;
+pub mod x509;
+pub mod trust;
+pub mod identity;
 
 pub use protocol::PQSession;
 pub use bidirectional::{BidirectionalSession, MessageEnvelope, MessageType};
\ No newline at end of file
@@ -0,0 +1,56 @@
+//! Fixed-width little-endian bit packing, as used by the Kyber and Dilithium
+//! key and ciphertext encodings.
+
+/// Packs each value into `bits` bits, least significant bit first.
+///
+/// # Panics
+/// Panics if `bits` is 0 or greater than 32, or if `values.len() * bits` is not
+/// a multiple of 8.
+///
+/// # Example
+/// ```
+/// use pq_core::math::encode::pack_bits;
+/// assert_eq!(pack_bits(&[1, 2], 4), vec![0x21]);
+/// ```
+pub fn pack_bits(values: &[u32], bits: u32) -> Vec<u8> {
+    assert!(bits > 0 && bits <= 32, "Bit width must be in 1..=32");
+    assert!((values.len() * bits as usize).is_multiple_of(8), "Packed output must be whole bytes");
+    let mut out = Vec::with_capacity(values.len() * bits as usize / 8);
+    let mut acc: u64 = 0;
+    let mut acc_bits = 0;
+    let mask = if bits == 32 { u32::MAX } else { (1u32 << bits) - 1 };
+    for &v in values {
+        acc |= u64::from(v & mask) << acc_bits;
+        acc_bits += bits;
+        while acc_bits >= 8 {
+            out.push(acc as u8);
+            acc >>= 8;
+            acc_bits -= 8;
+        }
+    }
+    out
+}
+
+/// Unpacks `count` values of `bits` bits each, least significant bit first.
+///
+/// # Panics
+/// Panics if `bits` is 0 or greater than 32, or if `bytes` is too short.
+pub fn unpack_bits(bytes: &[u8], bits: u32, count: usize) -> Vec<u32> {
+    assert!(bits > 0 && bits <= 32, "Bit width must be in 1..=32");
+    assert!(bytes.len() * 8 >= count * bits as usize, "Input too short");
+    let mut out = Vec::with_capacity(count);
+    let mut acc: u64 = 0;
+    let mut acc_bits = 0;
+    let mask = if bits == 32 { u64::from(u32::MAX) } else { (1u64 << bits) - 1 };
+    let mut iter = bytes.iter();
+    while out.len() < count {
+        while acc_bits < bits {
+            acc |= u64::from(*iter.next().expect("length checked above")) << acc_bits;
+            acc_bits += 8;
+        }
+        out.push((acc & mask) as u32);
+        acc >>= bits;
+        acc_bits -= bits;
+    }
+    out
+}
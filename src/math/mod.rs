@@ -1,3 +1,5 @@
 //! Math module for PQ-Core: provides number-theoretic and polynomial arithmetic utilities.
 
-pub mod ntt;
\ No newline at end of file
+pub mod encode;
+pub mod ntt;
+pub mod sample;
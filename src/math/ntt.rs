@@ -55,7 +55,104 @@ pub fn ntt(poly: &mut [i32], root: i32, modulus: i32) {
 pub fn modpow(mut base: i32, mut exp: u32, modulus: i32) -> i32 {
     assert!(modulus > 1, "Modulus must be > 1");
     let mut result = 1;
-    base = base % modulus;
+    base %= modulus;
+    while exp > 0 {
+        if exp % 2 == 1 {
+            result = (result * base) % modulus;
+        }
+        exp /= 2;
+        base = (base * base) % modulus;
+    }
+    result
+}
+
+/// Returns `count` powers of `root` modulo `modulus`, indexed in bit-reversed
+/// order: entry `k` is `root^bitrev(k)` where `bitrev` reverses the low
+/// `log2(count)` bits. This is the twiddle-factor table used by
+/// [`ntt_negacyclic`] and [`inv_ntt_negacyclic`].
+///
+/// # Panics
+/// Panics if `count` is not a power of two or if `modulus <= 1`.
+pub fn bit_reversed_powers(root: i64, modulus: i64, count: usize) -> Vec<i64> {
+    assert!(count.is_power_of_two(), "Table size must be a power of two");
+    assert!(modulus > 1, "Modulus must be > 1");
+    let bits = count.trailing_zeros();
+    (0..count)
+        .map(|k| {
+            let rev = if bits == 0 { 0 } else { (k as u64).reverse_bits() >> (64 - bits) };
+            pow_mod(root, rev, modulus)
+        })
+        .collect()
+}
+
+/// Computes the in-place negacyclic NTT (over `Z_q[X]/(X^n + 1)`) used by
+/// Kyber and Dilithium, with outputs in bit-reversed order.
+///
+/// The butterflies stop once the block length reaches `min_len`: `1` gives a
+/// complete NTT (Dilithium), `2` leaves degree-one residues (Kyber).
+///
+/// # Arguments
+/// * `poly` - Coefficients in `[0, modulus)` (transformed in-place).
+/// * `zetas` - Table from [`bit_reversed_powers`] with `poly.len() / min_len` entries.
+/// * `modulus` - The prime modulus.
+/// * `min_len` - Smallest butterfly block length.
+///
+/// # Panics
+/// Panics if `poly.len()` is not a power of two or `zetas` is too short.
+pub fn ntt_negacyclic(poly: &mut [i64], zetas: &[i64], modulus: i64, min_len: usize) {
+    let n = poly.len();
+    assert!(n.is_power_of_two(), "NTT input length must be a power of two");
+    assert!(zetas.len() >= n / min_len, "Twiddle table too short");
+    let mut k = 1;
+    let mut len = n / 2;
+    while len >= min_len {
+        for start in (0..n).step_by(2 * len) {
+            let zeta = zetas[k];
+            k += 1;
+            for j in start..start + len {
+                let t = (zeta * poly[j + len]) % modulus;
+                poly[j + len] = (poly[j] - t).rem_euclid(modulus);
+                poly[j] = (poly[j] + t) % modulus;
+            }
+        }
+        len /= 2;
+    }
+}
+
+/// Inverts [`ntt_negacyclic`] in place, including the final scaling, so that
+/// `inv_ntt_negacyclic(ntt_negacyclic(a)) == a`.
+///
+/// # Panics
+/// Panics if `poly.len()` is not a power of two or `zetas` is too short.
+pub fn inv_ntt_negacyclic(poly: &mut [i64], zetas: &[i64], modulus: i64, min_len: usize) {
+    let n = poly.len();
+    assert!(n.is_power_of_two(), "NTT input length must be a power of two");
+    let count = n / min_len;
+    assert!(zetas.len() >= count, "Twiddle table too short");
+    let mut k = count - 1;
+    let mut len = min_len;
+    while len < n {
+        for start in (0..n).step_by(2 * len) {
+            let zeta = zetas[k];
+            k -= 1;
+            for j in start..start + len {
+                let t = poly[j];
+                poly[j] = (t + poly[j + len]) % modulus;
+                poly[j + len] = (zeta * (poly[j + len] - t).rem_euclid(modulus)) % modulus;
+            }
+        }
+        len *= 2;
+    }
+    let scale = pow_mod(count as i64, (modulus - 2) as u64, modulus);
+    for c in poly.iter_mut() {
+        *c = (*c * scale) % modulus;
+    }
+}
+
+/// 64-bit modular exponentiation for moduli too large for [`modpow`].
+fn pow_mod(mut base: i64, mut exp: u64, modulus: i64) -> i64 {
+    let mut result = 1;
+    base = base.rem_euclid(modulus);
     while exp > 0 {
         if exp % 2 == 1 {
             result = (result * base) % modulus;
@@ -92,4 +189,40 @@ mod tests {
     fn test_modpow_invalid_modulus() {
         modpow(2, 3, 1);
     }
+    #[test]
+    fn test_bit_reversed_powers_kyber() {
+        let zetas = bit_reversed_powers(17, 3329, 128);
+        assert_eq!(zetas[0], 1);
+        assert_eq!(zetas[1], 1729);
+        assert_eq!(zetas[2], 2580);
+        assert_eq!(zetas[127], 2154);
+    }
+    #[test]
+    fn test_ntt_negacyclic_roundtrip() {
+        for (root, modulus, min_len) in [(17, 3329, 2), (1753, 8380417, 1)] {
+            let zetas = bit_reversed_powers(root, modulus, 256 / min_len);
+            let original: Vec<i64> = (0..256).map(|i| (i * 7919) % modulus).collect();
+            let mut poly = original.clone();
+            ntt_negacyclic(&mut poly, &zetas, modulus, min_len);
+            assert_ne!(poly, original);
+            inv_ntt_negacyclic(&mut poly, &zetas, modulus, min_len);
+            assert_eq!(poly, original);
+        }
+    }
+    #[test]
+    fn test_ntt_negacyclic_multiplication() {
+        // X * X^255 = X^256 = -1 in Z_q[X]/(X^256 + 1)
+        let modulus = 8380417;
+        let zetas = bit_reversed_powers(1753, modulus, 256);
+        let mut a = vec![0i64; 256];
+        let mut b = vec![0i64; 256];
+        a[1] = 1;
+        b[255] = 1;
+        ntt_negacyclic(&mut a, &zetas, modulus, 1);
+        ntt_negacyclic(&mut b, &zetas, modulus, 1);
+        let mut c: Vec<i64> = a.iter().zip(&b).map(|(x, y)| (x * y) % modulus).collect();
+        inv_ntt_negacyclic(&mut c, &zetas, modulus, 1);
+        assert_eq!(c[0], modulus - 1);
+        assert!(c[1..].iter().all(|&x| x == 0));
+    }
 }
\ No newline at end of file
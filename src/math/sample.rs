@@ -0,0 +1,106 @@
+//! Polynomial sampling shared by the lattice schemes: uniform rejection
+//! sampling from an XOF stream and centered binomial noise.
+
+use sha3::digest::XofReader;
+
+/// Samples `n` coefficients uniformly in `[0, modulus)` by rejection.
+///
+/// The stream is consumed three bytes at a time. Each group is read as a
+/// 24-bit little-endian integer and split into `24 / bits` candidates of
+/// `bits` bits each (low bits first); candidates `>= modulus` are rejected.
+/// With `bits = 12` this is Kyber's `Parse`, with `bits = 23` Dilithium's
+/// `ExpandA` sampler.
+///
+/// # Panics
+/// Panics if `bits` is 0 or greater than 24.
+pub fn sample_uniform(reader: &mut impl XofReader, modulus: i64, bits: u32, n: usize) -> Vec<i64> {
+    assert!(bits > 0 && bits <= 24, "Candidate width must be in 1..=24");
+    let mask = (1u32 << bits) - 1;
+    let mut poly = Vec::with_capacity(n);
+    let mut buf = [0u8; 3];
+    while poly.len() < n {
+        reader.read(&mut buf);
+        let group = u32::from(buf[0]) | u32::from(buf[1]) << 8 | u32::from(buf[2]) << 16;
+        for i in 0..24 / bits {
+            let candidate = i64::from((group >> (i * bits)) & mask);
+            if candidate < modulus && poly.len() < n {
+                poly.push(candidate);
+            }
+        }
+    }
+    poly
+}
+
+/// Samples coefficients from the centered binomial distribution with
+/// parameter `eta`, one per `2 * eta` bits of `bytes`.
+///
+/// Each coefficient is the number of set bits in the first `eta` bits minus
+/// the number in the next `eta` bits, so it lies in `[-eta, eta]`. Output is
+/// reduced into `[0, modulus)`.
+///
+/// # Panics
+/// Panics if `eta` is 0 or greater than 8.
+///
+/// # Example
+/// ```
+/// use pq_core::math::sample::centered_binomial;
+/// // 0b0000_0011: first coefficient is 2 - 0, second is 0 - 0
+/// assert_eq!(centered_binomial(&[0x03], 2, 3329), vec![2, 0]);
+/// ```
+pub fn centered_binomial(bytes: &[u8], eta: u32, modulus: i64) -> Vec<i64> {
+    assert!(eta > 0 && eta <= 8, "eta must be in 1..=8");
+    let width = 2 * eta;
+    let count = bytes.len() * 8 / width as usize;
+    let mask = (1u32 << eta) - 1;
+    let mut out = Vec::with_capacity(count);
+    let mut acc: u32 = 0;
+    let mut acc_bits = 0;
+    let mut iter = bytes.iter();
+    while out.len() < count {
+        while acc_bits < width {
+            acc |= u32::from(*iter.next().expect("count bounds the input")) << acc_bits;
+            acc_bits += 8;
+        }
+        let a = (acc & mask).count_ones() as i64;
+        let b = ((acc >> eta) & mask).count_ones() as i64;
+        out.push((a - b).rem_euclid(modulus));
+        acc >>= width;
+        acc_bits -= width;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sha3::Shake128;
+    use sha3::digest::{ExtendableOutput, Update};
+
+    #[test]
+    fn test_sample_uniform_in_range() {
+        for (modulus, bits) in [(3329, 12), (8380417, 23)] {
+            let mut xof = Shake128::default();
+            xof.update(b"uniform");
+            let poly = sample_uniform(&mut xof.finalize_xof(), modulus, bits, 256);
+            assert_eq!(poly.len(), 256);
+            assert!(poly.iter().all(|&c| (0..modulus).contains(&c)));
+        }
+    }
+
+    #[test]
+    fn test_centered_binomial_bounds() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        for eta in [2, 3] {
+            let poly = centered_binomial(&bytes, eta, 3329);
+            assert_eq!(poly.len(), 256 * 8 / (2 * eta as usize));
+            let eta = i64::from(eta);
+            assert!(poly.iter().all(|&c| c <= eta || c >= 3329 - eta));
+        }
+    }
+
+    #[test]
+    fn test_centered_binomial_all_ones() {
+        // Both halves fully set cancel out
+        assert!(centered_binomial(&[0xff; 3], 3, 3329).iter().all(|&c| c == 0));
+    }
+}
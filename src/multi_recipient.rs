@@ -0,0 +1,251 @@
+//! Multi-recipient encryption: one payload, many KEM public keys.
+//!
+//! The payload is encrypted once with AES-256-GCM under a random 32-byte
+//! data key. The data key is then wrapped for every recipient in a
+//! *stanza*: a fresh KEM encapsulation to that recipient, from which
+//! HKDF-SHA256 derives a key ID and the wrapping key.
+//!
+//! Format (version 1), all integers big-endian:
+//!
+//! ```text
+//! magic "PQMR" (4) | version 0x01 (1) | KEM id (2) | stanza count (2)
+//! stanza * count:  key ID (16) | KEM ciphertext | wrapped data key (32 + 16)
+//! AES-GCM payload ciphertext + tag (16)
+//! ```
+//!
+//! Key IDs are derived from each stanza's shared secret, so only the
+//! stanza's recipient can recognise it: a recipient decapsulates stanzas
+//! until the key ID matches, and learns nothing about the other stanzas. The
+//! stanzas are shuffled and all have the same size, so the message reveals
+//! the number of recipients but not who they are or the order they were
+//! given in. The payload is authenticated together with the header and
+//! every stanza, so anyone who does not hold the data key cannot remove,
+//! reorder or swap stanzas without detection.
+//!
+//! That does not hold against the recipients themselves: every recipient
+//! learns the data key, so any of them can build a new message with a
+//! different payload or a different set of stanzas that the others will
+//! accept. Messages are not authenticated to the sender; sign them when
+//! recipients must be able to tell who wrote a message.
+//!
+//! ```
+//! use pq_core::kem::{Kem, Kyber512};
+//! use pq_core::multi_recipient;
+//!
+//! let kem = Kyber512::new();
+//! let (alice_pk, alice_sk) = kem.keygen().unwrap();
+//! let (bob_pk, bob_sk) = kem.keygen().unwrap();
+//!
+//! let message = multi_recipient::encrypt(&[alice_pk, bob_pk], b"doc-1", b"minutes").unwrap();
+//! assert_eq!(multi_recipient::decrypt(&alice_sk, b"doc-1", &message).unwrap(), b"minutes");
+//! assert_eq!(multi_recipient::decrypt(&bob_sk, b"doc-1", &message).unwrap(), b"minutes");
+//! ```
+
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use hkdf::Hkdf;
+use rand::rngs::OsRng;
+use rand::seq::SliceRandom;
+use rand::RngCore;
+use sha2::Sha256;
+use subtle::ConstantTimeEq;
+use thiserror::Error;
+use zeroize::Zeroizing;
+use crate::kem::kem::{Ciphertext, KemError, PublicKey, SecretKey, SharedSecret};
+use crate::kem::{registry, Kem, KemAlgorithm};
+
+/// Identifies a multi-recipient message.
+pub const MAGIC: [u8; 4] = *b"PQMR";
+/// The format version written by [`encrypt`].
+pub const VERSION: u8 = 1;
+/// The most recipients a single message can have.
+pub const MAX_RECIPIENTS: usize = u16::MAX as usize;
+
+const HEADER_LEN: usize = 4 + 1 + 2 + 2;
+const KEY_ID_LEN: usize = 16;
+const DATA_KEY_LEN: usize = 32;
+const TAG_LEN: usize = 16;
+const WRAPPED_KEY_LEN: usize = DATA_KEY_LEN + TAG_LEN;
+const INFO_LABEL: &[u8] = b"PQ-Core multi-recipient v1";
+
+#[derive(Debug, Error)]
+pub enum MultiRecipientError {
+    #[error("At least one recipient is required")]
+    NoRecipients,
+    #[error("Too many recipients (at most {MAX_RECIPIENTS})")]
+    TooManyRecipients,
+    #[error("Public key is not tagged with an algorithm")]
+    MissingAlgorithm,
+    #[error("All recipients must use the same KEM")]
+    MixedAlgorithms,
+    #[error("Not a multi-recipient message")]
+    BadMagic,
+    #[error("Unsupported multi-recipient message version: {0}")]
+    UnsupportedVersion(u8),
+    #[error("Message is truncated")]
+    Truncated,
+    #[error("No stanza for this key")]
+    NotARecipient,
+    #[error("Encryption failed")]
+    EncryptionFailed,
+    #[error("Decryption failed")]
+    DecryptionFailed,
+    #[error("KEM operation failed: {0}")]
+    Kem(#[from] KemError),
+}
+
+/// Encrypts `plaintext` to every key in `recipients`. The keys must be
+/// tagged with the same algorithm.
+pub fn encrypt(recipients: &[PublicKey], aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, MultiRecipientError> {
+    let first = recipients.first().ok_or(MultiRecipientError::NoRecipients)?;
+    let algorithm = first.algorithm().ok_or(MultiRecipientError::MissingAlgorithm)?;
+    if recipients.iter().any(|pk| pk.algorithm() != Some(algorithm)) {
+        return Err(MultiRecipientError::MixedAlgorithms);
+    }
+    encrypt_with(registry::new_kem(algorithm).as_ref(), recipients, aad, plaintext)
+}
+
+/// Encrypts `plaintext` to every key in `recipients` using `kem`, for keys
+/// held as raw bytes.
+pub fn encrypt_with(
+    kem: &dyn Kem,
+    recipients: &[PublicKey],
+    aad: &[u8],
+    plaintext: &[u8],
+) -> Result<Vec<u8>, MultiRecipientError> {
+    if recipients.is_empty() {
+        return Err(MultiRecipientError::NoRecipients);
+    }
+    let count = u16::try_from(recipients.len()).map_err(|_| MultiRecipientError::TooManyRecipients)?;
+
+    let mut data_key = Zeroizing::new([0u8; DATA_KEY_LEN]);
+    OsRng.fill_bytes(data_key.as_mut());
+
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    header.extend_from_slice(&MAGIC);
+    header.push(VERSION);
+    header.extend_from_slice(&kem.algorithm().id().to_be_bytes());
+    header.extend_from_slice(&count.to_be_bytes());
+
+    let mut stanzas = recipients
+        .iter()
+        .map(|pk| wrap_data_key(kem, pk, &header, data_key.as_ref()))
+        .collect::<Result<Vec<_>, _>>()?;
+    stanzas.shuffle(&mut OsRng);
+
+    let mut out = header;
+    for stanza in &stanzas {
+        out.extend_from_slice(stanza);
+    }
+    let ciphertext = payload_cipher(data_key.as_ref())
+        .encrypt(&Nonce::default(), Payload { msg: plaintext, aad: &payload_aad(&out, aad) })
+        .map_err(|_| MultiRecipientError::EncryptionFailed)?;
+    out.extend_from_slice(&ciphertext);
+    Ok(out)
+}
+
+/// Decrypts a message produced by [`encrypt`] with one recipient's secret
+/// key. The KEM is taken from the message header.
+pub fn decrypt(sk: &SecretKey, aad: &[u8], message: &[u8]) -> Result<Vec<u8>, MultiRecipientError> {
+    if message.len() < HEADER_LEN {
+        return Err(MultiRecipientError::Truncated);
+    }
+    let (header, rest) = message.split_at(HEADER_LEN);
+    if header[..4] != MAGIC {
+        return Err(MultiRecipientError::BadMagic);
+    }
+    if header[4] != VERSION {
+        return Err(MultiRecipientError::UnsupportedVersion(header[4]));
+    }
+    let kem = registry::new_kem(KemAlgorithm::try_from(u16::from_be_bytes([header[5], header[6]]))?);
+    let count = u16::from_be_bytes([header[7], header[8]]) as usize;
+
+    let stanza_len = KEY_ID_LEN + kem.ciphertext_bytes() + WRAPPED_KEY_LEN;
+    if rest.len() < count * stanza_len + TAG_LEN {
+        return Err(MultiRecipientError::Truncated);
+    }
+    let (stanzas, ciphertext) = rest.split_at(count * stanza_len);
+
+    let mut data_key = None;
+    for stanza in stanzas.chunks_exact(stanza_len) {
+        if let Some(key) = unwrap_data_key(kem.as_ref(), sk, header, stanza)? {
+            data_key = Some(key);
+            break;
+        }
+    }
+    let data_key = data_key.ok_or(MultiRecipientError::NotARecipient)?;
+
+    let prefix = &message[..HEADER_LEN + stanzas.len()];
+    payload_cipher(data_key.as_ref())
+        .decrypt(&Nonce::default(), Payload { msg: ciphertext, aad: &payload_aad(prefix, aad) })
+        .map_err(|_| MultiRecipientError::DecryptionFailed)
+}
+
+/// Builds the stanza wrapping `data_key` for `pk`.
+fn wrap_data_key(kem: &dyn Kem, pk: &PublicKey, header: &[u8], data_key: &[u8]) -> Result<Vec<u8>, MultiRecipientError> {
+    let (kem_ct, ss) = kem.encaps(pk)?;
+    let keys = derive_stanza_keys(&ss, header, kem_ct.as_ref())?;
+    let wrapped = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(keys.wrap_key.as_ref()))
+        .encrypt(Nonce::from_slice(&keys.nonce), data_key)
+        .map_err(|_| MultiRecipientError::EncryptionFailed)?;
+
+    let mut stanza = Vec::with_capacity(KEY_ID_LEN + kem_ct.as_ref().len() + WRAPPED_KEY_LEN);
+    stanza.extend_from_slice(&keys.key_id);
+    stanza.extend_from_slice(kem_ct.as_ref());
+    stanza.extend_from_slice(&wrapped);
+    Ok(stanza)
+}
+
+/// Returns the data key if `stanza` is addressed to `sk`, or `None` if its
+/// key ID does not match.
+fn unwrap_data_key(
+    kem: &dyn Kem,
+    sk: &SecretKey,
+    header: &[u8],
+    stanza: &[u8],
+) -> Result<Option<Zeroizing<Vec<u8>>>, MultiRecipientError> {
+    let (key_id, rest) = stanza.split_at(KEY_ID_LEN);
+    let (kem_ct, wrapped) = rest.split_at(kem.ciphertext_bytes());
+    let ss = kem.decaps(&Ciphertext::from_vec(kem_ct.to_vec()), sk)?;
+    let keys = derive_stanza_keys(&ss, header, kem_ct)?;
+    if !bool::from(keys.key_id.ct_eq(key_id)) {
+        return Ok(None);
+    }
+    let data_key = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(keys.wrap_key.as_ref()))
+        .decrypt(Nonce::from_slice(&keys.nonce), wrapped)
+        .map_err(|_| MultiRecipientError::DecryptionFailed)?;
+    Ok(Some(Zeroizing::new(data_key)))
+}
+
+/// Per-stanza values derived from the stanza's shared secret.
+struct StanzaKeys {
+    key_id: [u8; KEY_ID_LEN],
+    wrap_key: Zeroizing<[u8; 32]>,
+    nonce: [u8; 12],
+}
+
+/// Derives a stanza's key ID, wrapping key and nonce, bound to the message
+/// header and the stanza's KEM ciphertext.
+fn derive_stanza_keys(ss: &SharedSecret, header: &[u8], kem_ct: &[u8]) -> Result<StanzaKeys, MultiRecipientError> {
+    let hk = Hkdf::<Sha256>::new(None, ss.as_ref());
+    let mut okm = Zeroizing::new([0u8; KEY_ID_LEN + 32 + 12]);
+    hk.expand_multi_info(&[INFO_LABEL, header, kem_ct], okm.as_mut())
+        .map_err(|_| MultiRecipientError::EncryptionFailed)?;
+
+    let mut keys = StanzaKeys { key_id: [0u8; KEY_ID_LEN], wrap_key: Zeroizing::new([0u8; 32]), nonce: [0u8; 12] };
+    keys.key_id.copy_from_slice(&okm[..KEY_ID_LEN]);
+    keys.wrap_key.copy_from_slice(&okm[KEY_ID_LEN..KEY_ID_LEN + 32]);
+    keys.nonce.copy_from_slice(&okm[KEY_ID_LEN + 32..]);
+    Ok(keys)
+}
+
+/// The data key encrypts exactly one payload, so a fixed all-zero nonce is
+/// never reused.
+fn payload_cipher(data_key: &[u8]) -> Aes256Gcm {
+    Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(data_key))
+}
+
+/// Authenticates the header and every stanza along with the caller's data.
+fn payload_aad(prefix: &[u8], aad: &[u8]) -> Vec<u8> {
+    [prefix, aad].concat()
+}
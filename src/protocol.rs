@@ -1,12 +1,19 @@
-use crate::kem::{Kyber512, Kem};
+use crate::cert::{Certificate, CertificateError, TrustAnchors};
+use crate::identity::{DeviceCertificate, IdentityError, IdentityId};
+use crate::trust::{PeerIdentity, TrustError, TrustStore, TrustedPeer};
+use crate::kem::{registry, Kyber512, Kem, KemAlgorithm};
+use crate::kem::pool::KeyPool;
 use crate::kem::kem::{PublicKey, SecretKey, Ciphertext};
-use crate::sig::dilithium::{Dilithium, DilithiumPublicKey, DilithiumSecretKey, DilithiumSignature};
+use crate::sig::dilithium::Dilithium;
+use crate::sig::{Signer, Verifier};
 use rand::rngs::OsRng;
 use rand::RngCore;
 use aes_gcm::{Aes256Gcm, Key, Nonce};
 use aes_gcm::aead::{Aead, KeyInit};
 use hkdf::Hkdf;
-use sha2::Sha256;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use zeroize::Zeroizing;
 
 pub enum PQState {
     Init,
@@ -16,91 +23,413 @@ pub enum PQState {
     Error,
 }
 
-pub struct HandshakeMessage {
+/// A hello or a reply. The sender signs every field but the signature; a
+/// reply's signature also covers the hello it answers, so a reply cannot
+/// be replayed to another session or have its ciphertext replaced.
+pub struct HandshakeMessage<S: Verifier = Dilithium> {
     pub kem_pk: PublicKey,
-    pub sig_pk: DilithiumPublicKey,
-    pub signature: DilithiumSignature,
+    pub sig_pk: S::PublicKey,
+    pub signature: S::Signature,
+    /// The sender's certificate chain, leaf first, certifying `sig_pk`.
+    /// Empty when the sender has no certificate. Covered by the signature,
+    /// so the chain vouches for this handshake and not just for `sig_pk`.
+    pub certificates: Vec<Certificate<S>>,
+    /// The sender's device certificate, certifying `sig_pk` for its
+    /// identity. `None` when the sender is not a multi-device identity.
+    pub device: Option<DeviceCertificate>,
     pub nonce: u64,
     pub ciphertext: Ciphertext,
+    /// In a reply to a device, a second encapsulation to the KEM key in
+    /// the hello's device certificate. `None` otherwise.
+    pub device_ciphertext: Option<Ciphertext>,
+}
+
+/// Domain separation for the signature over a hello.
+const HELLO_CONTEXT: &[u8] = b"pq-core handshake v1 hello";
+/// Domain separation for the signature over a reply, followed by the hash
+/// of the hello it answers.
+const REPLY_CONTEXT: &[u8] = b"pq-core handshake v1 reply";
+
+/// The fields of a handshake message covered by its signature.
+struct SignedFields<'a, S: Verifier> {
+    kem_pk: &'a PublicKey,
+    sig_pk: &'a S::PublicKey,
+    certificates: &'a [Certificate<S>],
+    device: Option<&'a DeviceCertificate>,
+    nonce: u64,
+    ciphertext: &'a Ciphertext,
+    device_ciphertext: Option<&'a Ciphertext>,
+}
+
+impl<S: Verifier> SignedFields<'_, S> {
+    /// `context`, then every field with a 4-byte length prefix.
+    fn encode(&self, context: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut put = |field: &[u8]| {
+            out.extend_from_slice(&(field.len() as u32).to_be_bytes());
+            out.extend_from_slice(field);
+        };
+        put(context);
+        put(self.kem_pk.as_ref());
+        put(self.sig_pk.as_ref());
+        put(&(self.certificates.len() as u32).to_be_bytes());
+        for certificate in self.certificates {
+            put(&certificate.to_bytes());
+        }
+        put(&self.device.map(DeviceCertificate::to_bytes).unwrap_or_default());
+        put(&self.nonce.to_be_bytes());
+        put(self.ciphertext.as_ref());
+        put(self.device_ciphertext.map(AsRef::as_ref).unwrap_or_default());
+        out
+    }
+}
+
+impl<S: Verifier> HandshakeMessage<S> {
+    fn signed_fields(&self) -> SignedFields<'_, S> {
+        SignedFields {
+            kem_pk: &self.kem_pk,
+            sig_pk: &self.sig_pk,
+            certificates: &self.certificates,
+            device: self.device.as_ref(),
+            nonce: self.nonce,
+            ciphertext: &self.ciphertext,
+            device_ciphertext: self.device_ciphertext.as_ref(),
+        }
+    }
+
+    /// SHA-256 over the signed bytes and the signature.
+    fn hash(&self, context: &[u8]) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(self.signed_fields().encode(context));
+        hasher.update(self.signature.as_ref());
+        hasher.finalize().into()
+    }
+}
+
+/// The context a reply to the hello with hash `hello_hash` is signed under.
+fn reply_context(hello_hash: &[u8; 32]) -> Vec<u8> {
+    [REPLY_CONTEXT, hello_hash].concat()
+}
+
+/// The initiator's (tx, rx) chain keys; swapped for the responder.
+type ChainKeys = (Zeroizing<[u8; 32]>, Zeroizing<[u8; 32]>);
+
+/// Derives the chain keys from the shared secret, salted with the hash of
+/// the whole transcript.
+fn derive_chain_keys(shared_secret: &[u8], transcript: &[u8; 32]) -> Result<ChainKeys, PQError> {
+    let hk = Hkdf::<Sha256>::new(Some(transcript), shared_secret);
+    let mut tx_key = Zeroizing::new([0u8; 32]);
+    let mut rx_key = Zeroizing::new([0u8; 32]);
+    hk.expand(b"tx", tx_key.as_mut()).map_err(|_| PQError::Other)?;
+    hk.expand(b"rx", rx_key.as_mut()).map_err(|_| PQError::Other)?;
+    Ok((tx_key, rx_key))
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PQError {
     InvalidSignature,
     InvalidCiphertext,
+    /// The peer's certificate chain is missing, invalid or untrusted
+    Certificate(CertificateError),
+    /// The peer's device certificate is invalid, expired or revoked
+    Device(IdentityError),
+    /// The trust store has no record of the peer's identity key
+    UnknownIdentity,
+    /// The peer's identity key differs from the one on record
+    IdentityChanged,
+    /// The trust store could not reach a decision
+    Trust(TrustError),
     Other,
 }
 
-pub struct PQSession {
+impl From<TrustError> for PQError {
+    fn from(err: TrustError) -> Self {
+        match err {
+            TrustError::UnknownIdentity => PQError::UnknownIdentity,
+            TrustError::IdentityChanged => PQError::IdentityChanged,
+            TrustError::Certificate(err) => PQError::Certificate(err),
+            TrustError::Device(err) => PQError::Device(err),
+            err => PQError::Trust(err),
+        }
+    }
+}
+
+/// A handshake-authenticated session, generic over the signature scheme of
+/// its identity key (Dilithium2 by default).
+pub struct PQSession<S: Signer = Dilithium> {
     state: PQState,
-    kem: Kyber512,
-    sig: Dilithium,
-    sk: SecretKey,
-    sig_sk: DilithiumSecretKey,
-    sig_pk: DilithiumPublicKey,
-    tx_chain_key: [u8; 32],
-    rx_chain_key: [u8; 32],
+    kem: Box<dyn Kem>,
+    pool: Option<Arc<KeyPool>>,
+    sig: S,
+    /// Our ephemeral KEM secret key, set once the handshake starts
+    sk: Option<SecretKey>,
+    sig_sk: S::SecretKey,
+    sig_pk: S::PublicKey,
+    certificates: Vec<Certificate<S>>,
+    device: Option<DeviceCertificate>,
+    /// Secret half of the KEM key in `device`
+    device_kem_sk: Option<SecretKey>,
+    trust_store: Option<Box<dyn TrustStore<S>>>,
+    expected_peer: Option<String>,
+    peer: Option<TrustedPeer<S>>,
+    /// Hash of the hello we sent, which the peer's reply must be signed over
+    hello_hash: [u8; 32],
+    tx_chain_key: Zeroizing<[u8; 32]>,
+    rx_chain_key: Zeroizing<[u8; 32]>,
     nonce: u64,
 }
 
+impl Default for PQSession {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl PQSession {
+    /// Creates a session using Kyber512 for key exchange.
     pub fn new() -> Self {
-        let kem = Kyber512::new();
-        let sig = Dilithium::new();
-        
-        // Generate KEM keys
-        let (_, sk) = kem.keygen().expect("KEM keygen failed");
-        
-        // Generate signature keys
+        Self::with_kem(Box::new(Kyber512::new()))
+    }
+
+    /// Creates a session using the given KEM for key exchange, e.g.
+    /// `Kyber768` or `Kyber1024` for long-lived secrets.
+    ///
+    /// Both peers must use the same KEM parameter set.
+    pub fn with_kem(kem: Box<dyn Kem>) -> Self {
+        Self::with_signer(kem, Dilithium::new())
+    }
+
+    /// Creates a session that draws its ephemeral KEM keypairs from `pool`
+    /// instead of generating them during the handshake. The pool is
+    /// usually shared by every session of a server.
+    pub fn with_key_pool(pool: Arc<KeyPool>) -> Self {
+        Self::with_signer_and_key_pool(pool, Dilithium::new())
+    }
+
+    /// Creates a session using the registry's implementation of `algorithm`,
+    /// e.g. as read from a config file.
+    pub fn with_kem_algorithm(algorithm: KemAlgorithm) -> Self {
+        Self::with_kem(registry::new_kem(algorithm))
+    }
+}
+
+impl<S: Signer> PQSession<S> {
+    /// Creates a session whose identity key belongs to `signer`. Both peers
+    /// must use the same KEM and signature scheme.
+    pub fn with_signer(kem: Box<dyn Kem>, signer: S) -> Self {
+        Self::build(kem, None, signer)
+    }
+
+    /// Like [`with_key_pool`](PQSession::with_key_pool), with the identity
+    /// key belonging to `signer`.
+    pub fn with_signer_and_key_pool(pool: Arc<KeyPool>, signer: S) -> Self {
+        Self::build(registry::new_kem(pool.algorithm()), Some(pool), signer)
+    }
+
+    fn build(kem: Box<dyn Kem>, pool: Option<Arc<KeyPool>>, sig: S) -> Self {
+        // Generate signature keys; the ephemeral KEM keypair is only drawn
+        // once the handshake starts
         let (sig_pk, sig_sk) = sig.keygen().expect("Signature keygen failed");
         
         PQSession {
             state: PQState::Init,
             kem,
+            pool,
             sig,
-            sk,
+            sk: None,
             sig_sk,
             sig_pk,
-            tx_chain_key: [0u8; 32],
-            rx_chain_key: [0u8; 32],
+            certificates: Vec::new(),
+            device: None,
+            device_kem_sk: None,
+            trust_store: None,
+            expected_peer: None,
+            peer: None,
+            hello_hash: [0u8; 32],
+            tx_chain_key: Zeroizing::new([0u8; 32]),
+            rx_chain_key: Zeroizing::new([0u8; 32]),
             nonce: 0,
         }
     }
 
-    pub fn initiate_handshake(&mut self) -> Result<HandshakeMessage, PQError> {
-        let (pk, sk) = self.kem.keygen().map_err(|_| PQError::Other)?;
-        self.sk = sk.clone();
+    /// Uses a long-term identity keypair instead of the one generated for
+    /// this session, so peers can pin `pk`.
+    pub fn with_identity(mut self, pk: S::PublicKey, sk: S::SecretKey) -> Self {
+        self.sig_pk = pk;
+        self.sig_sk = sk;
+        self
+    }
+
+    /// The identity key this session signs its handshakes with.
+    pub fn identity_public_key(&self) -> &S::PublicKey {
+        &self.sig_pk
+    }
+
+    /// Uses the key certified by `chain`, leaf first, as the identity key
+    /// and sends the chain in every handshake. `sk` is the secret key
+    /// matching the leaf certificate.
+    pub fn with_certificate_chain(mut self, chain: Vec<Certificate<S>>, sk: S::SecretKey) -> Result<Self, PQError> {
+        let leaf = chain.first().ok_or(PQError::Certificate(CertificateError::EmptyChain))?;
+        self.sig_pk = leaf.public_key().clone();
+        self.sig_sk = sk;
+        self.certificates = chain;
+        Ok(self)
+    }
+
+    /// Uses the device key certified by `certificate` as the identity key
+    /// and sends the certificate in every handshake, so that peers trusting
+    /// the device's master identity accept it. `sk` is the device's secret
+    /// signing key and `kem_sk` the secret half of its certified KEM key.
+    ///
+    /// A peer answering this device's hello also encapsulates to the
+    /// certified KEM key, so only the device can derive the session keys.
+    pub fn with_device(mut self, certificate: DeviceCertificate, sk: S::SecretKey, kem_sk: SecretKey) -> Result<Self, PQError> {
+        self.sig_pk = certificate.signing_public_key(&self.sig).map_err(PQError::Device)?;
+        self.sig_sk = sk;
+        self.device = Some(certificate);
+        self.device_kem_sk = Some(kem_sk);
+        Ok(self)
+    }
+
+    /// Requires the peer's handshake to carry a certificate chain that
+    /// validates up to one of `anchors` and certifies the handshake key,
+    /// issued to the name set with
+    /// [`with_expected_peer`](PQSession::with_expected_peer).
+    pub fn with_trust_anchors(self, anchors: TrustAnchors<S>) -> Self
+    where
+        S: 'static,
+    {
+        self.with_trust_store(anchors)
+    }
+
+    /// Consults `store` on every handshake: a peer whose identity key it
+    /// does not trust is refused with [`PQError::UnknownIdentity`],
+    /// [`PQError::IdentityChanged`], [`PQError::Certificate`] or
+    /// [`PQError::Device`].
+    pub fn with_trust_store(mut self, store: impl TrustStore<S> + 'static) -> Self {
+        self.trust_store = Some(Box::new(store));
+        self
+    }
+
+    /// The name of the peer this session is meant to reach, e.g. its host
+    /// name. Trust stores look the peer's identity up by it, and
+    /// certificate anchors require it as the leaf's subject.
+    pub fn with_expected_peer(mut self, name: impl Into<String>) -> Self {
+        self.expected_peer = Some(name.into());
+        self
+    }
+
+    /// The peer's validated leaf certificate, once a handshake whose trust
+    /// decision rested on a certificate chain has succeeded.
+    pub fn peer_certificate(&self) -> Option<&Certificate<S>> {
+        match &self.peer {
+            Some(TrustedPeer::Certificate(leaf)) => Some(leaf),
+            _ => None,
+        }
+    }
+
+    /// The peer's verified device certificate, once a handshake whose trust
+    /// decision rested on one has succeeded.
+    pub fn peer_device(&self) -> Option<&DeviceCertificate> {
+        match &self.peer {
+            Some(TrustedPeer::Device(device)) => Some(device),
+            _ => None,
+        }
+    }
+
+    /// The master identity behind the peer's device key; see
+    /// [`peer_device`](PQSession::peer_device).
+    pub fn peer_identity(&self) -> Option<IdentityId> {
+        self.peer_device().map(DeviceCertificate::identity)
+    }
+
+    /// Checks the handshake signature over the message and `context` and,
+    /// if a trust store is configured, that the store trusts the signing key.
+    fn authenticate_peer(&mut self, msg: &HandshakeMessage<S>, context: &[u8]) -> Result<(), PQError> {
+        let signed = msg.signed_fields().encode(context);
+        if !self.sig.verify(&signed, &msg.signature, &msg.sig_pk).map_err(|_| PQError::InvalidSignature)? {
+            return Err(PQError::InvalidSignature);
+        }
+
+        if let Some(store) = &mut self.trust_store {
+            let peer = PeerIdentity {
+                name: self.expected_peer.as_deref(),
+                algorithm: self.sig.algorithm(),
+                public_key: &msg.sig_pk,
+                certificates: &msg.certificates,
+                device: msg.device.as_ref(),
+            };
+            self.peer = Some(store.verify_peer(&peer)?);
+        }
+        Ok(())
+    }
+
+    /// Takes an ephemeral keypair from the pool, if any, or generates one.
+    fn ephemeral_keypair(&self) -> Result<(PublicKey, SecretKey), PQError> {
+        match &self.pool {
+            Some(pool) => pool.take(),
+            None => self.kem.keygen(),
+        }
+        .map_err(|_| PQError::Other)
+    }
+
+    pub fn initiate_handshake(&mut self) -> Result<HandshakeMessage<S>, PQError> {
+        let (pk, sk) = self.ephemeral_keypair()?;
+        self.sk = Some(sk);
         self.state = PQState::HandshakeSent;
         
-        // Sign the public key with our signature key
-        let signature = self.sig.sign(pk.as_ref(), &self.sig_sk).map_err(|_| PQError::InvalidSignature)?;
         let nonce = random_u64();
         let (ciphertext, _) = self.kem.encaps(&pk).map_err(|_| PQError::Other)?;
 
-        Ok(HandshakeMessage {
+        // Sign the whole hello with our signature key
+        let signed = SignedFields::<S> {
+            kem_pk: &pk,
+            sig_pk: &self.sig_pk,
+            certificates: &self.certificates,
+            device: self.device.as_ref(),
+            nonce,
+            ciphertext: &ciphertext,
+            device_ciphertext: None,
+        }
+        .encode(HELLO_CONTEXT);
+        let signature = self.sig.sign(&signed, &self.sig_sk).map_err(|_| PQError::InvalidSignature)?;
+
+        let hello = HandshakeMessage {
             kem_pk: pk,
             sig_pk: self.sig_pk.clone(),
             signature,
+            certificates: self.certificates.clone(),
+            device: self.device.clone(),
             nonce,
             ciphertext,
-        })
+            device_ciphertext: None,
+        };
+        self.hello_hash = hello.hash(HELLO_CONTEXT);
+        Ok(hello)
     }
 
-    pub fn complete_handshake(&mut self, msg: HandshakeMessage) -> Result<(), PQError> {
-        // Verify signature if present
-        if !self.sig.verify(msg.kem_pk.as_ref(), &msg.signature, &msg.sig_pk).map_err(|_| PQError::InvalidSignature)? {
-            return Err(PQError::InvalidSignature);
-        }
+    pub fn complete_handshake(&mut self, msg: HandshakeMessage<S>) -> Result<(), PQError> {
+        // Verify the signature over the reply and our hello, and the
+        // certificate chain behind it
+        let context = reply_context(&self.hello_hash);
+        self.authenticate_peer(&msg, &context)?;
 
-        // KEM decapsulation
-        let shared_secret = self.kem.decaps(&msg.ciphertext, &self.sk).map_err(|_| PQError::Other)?;
+        // KEM decapsulation, plus our certified device key if we have one
+        let sk = self.sk.as_ref().ok_or(PQError::Other)?;
+        let shared_secret = self.kem.decaps(&msg.ciphertext, sk).map_err(|_| PQError::Other)?;
+        let mut secret = Zeroizing::new(shared_secret.as_ref().to_vec());
+        match (&self.device, &self.device_kem_sk, &msg.device_ciphertext) {
+            (Some(device), Some(kem_sk), Some(device_ciphertext)) => {
+                let kem = device_kem(device)?;
+                let device_secret = kem.decaps(device_ciphertext, kem_sk).map_err(|_| PQError::InvalidCiphertext)?;
+                secret.extend_from_slice(device_secret.as_ref());
+            }
+            (None, _, None) => {}
+            _ => return Err(PQError::InvalidCiphertext),
+        }
 
-        // Derive chain keys using HKDF
-        let hk = Hkdf::<Sha256>::new(None, shared_secret.as_ref());
-        let mut tx_key = [0u8; 32];
-        let mut rx_key = [0u8; 32];
-        hk.expand(b"tx", &mut tx_key).map_err(|_| PQError::Other)?;
-        hk.expand(b"rx", &mut rx_key).map_err(|_| PQError::Other)?;
+        // Derive chain keys using HKDF, salted with the transcript
+        let (tx_key, rx_key) = derive_chain_keys(&secret, &msg.hash(&context))?;
         self.tx_chain_key = tx_key;
         self.rx_chain_key = rx_key;
 
@@ -108,44 +437,65 @@ impl PQSession {
         Ok(())
     }
 
-    pub fn process_handshake(&mut self, msg: HandshakeMessage) -> Result<HandshakeMessage, PQError> {
-        // Verify the incoming signature
-        if !self.sig.verify(msg.kem_pk.as_ref(), &msg.signature, &msg.sig_pk).map_err(|_| PQError::InvalidSignature)? {
-            return Err(PQError::InvalidSignature);
-        }
+    pub fn process_handshake(&mut self, msg: HandshakeMessage<S>) -> Result<HandshakeMessage<S>, PQError> {
+        // Verify the incoming signature and the certificate chain behind it
+        self.authenticate_peer(&msg, HELLO_CONTEXT)?;
 
-        // Encapsulate to the incoming public key to get shared secret
+        // Encapsulate to the incoming public key to get shared secret, and
+        // to the peer's certified device key if it sent one
         let (ciphertext, shared_secret) = self.kem.encaps(&msg.kem_pk).map_err(|_| PQError::Other)?;
-
-        // Derive chain keys using HKDF
-        let hk = Hkdf::<Sha256>::new(None, shared_secret.as_ref());
-        let mut tx_key = [0u8; 32];
-        let mut rx_key = [0u8; 32];
-        hk.expand(b"rx", &mut tx_key).map_err(|_| PQError::Other)?;  // Bob's tx is Alice's rx
-        hk.expand(b"tx", &mut rx_key).map_err(|_| PQError::Other)?;  // Bob's rx is Alice's tx
-        self.tx_chain_key = tx_key;
-        self.rx_chain_key = rx_key;
+        let mut secret = Zeroizing::new(shared_secret.as_ref().to_vec());
+        let device_ciphertext = match &msg.device {
+            Some(device) => {
+                let (device_ciphertext, device_secret) =
+                    device_kem(device)?.encaps(device.kem_public_key()).map_err(|_| PQError::Other)?;
+                secret.extend_from_slice(device_secret.as_ref());
+                Some(device_ciphertext)
+            }
+            None => None,
+        };
 
         // Generate our own key pair for the response
-        let (our_pk, our_sk) = self.kem.keygen().map_err(|_| PQError::Other)?;
-        self.sk = our_sk;
-
-        // Sign our public key
-        let our_signature = self.sig.sign(our_pk.as_ref(), &self.sig_sk).map_err(|_| PQError::InvalidSignature)?;
+        let (our_pk, our_sk) = self.ephemeral_keypair()?;
+        self.sk = Some(our_sk);
 
-        self.state = PQState::Established;
+        // Sign the reply together with the hello it answers
+        let context = reply_context(&msg.hash(HELLO_CONTEXT));
+        let nonce = msg.nonce.wrapping_add(1);  // Increment the nonce
+        let signed = SignedFields::<S> {
+            kem_pk: &our_pk,
+            sig_pk: &self.sig_pk,
+            certificates: &self.certificates,
+            device: self.device.as_ref(),
+            nonce,
+            ciphertext: &ciphertext,
+            device_ciphertext: device_ciphertext.as_ref(),
+        }
+        .encode(&context);
+        let our_signature = self.sig.sign(&signed, &self.sig_sk).map_err(|_| PQError::InvalidSignature)?;
 
-        Ok(HandshakeMessage {
+        let reply = HandshakeMessage {
             kem_pk: our_pk,
             sig_pk: self.sig_pk.clone(),
             signature: our_signature,
-            nonce: msg.nonce + 1,  // Increment the nonce
+            certificates: self.certificates.clone(),
+            device: self.device.clone(),
+            nonce,
             ciphertext,
-        })
+            device_ciphertext,
+        };
+
+        // Derive chain keys using HKDF, salted with the transcript
+        let (rx_key, tx_key) = derive_chain_keys(&secret, &reply.hash(&context))?;  // Bob's tx is Alice's rx
+        self.tx_chain_key = tx_key;
+        self.rx_chain_key = rx_key;
+
+        self.state = PQState::Established;
+        Ok(reply)
     }
 
     pub fn encrypt(&mut self, plaintext: &[u8]) -> Vec<u8> {
-        let key = Key::<Aes256Gcm>::from_slice(&self.tx_chain_key);
+        let key = Key::<Aes256Gcm>::from_slice(self.tx_chain_key.as_ref());
         let cipher = Aes256Gcm::new(key);
         let mut nonce_bytes = [0u8; 12];
         nonce_bytes[..8].copy_from_slice(&self.nonce.to_le_bytes());
@@ -163,14 +513,14 @@ impl PQSession {
             return Err(PQError::InvalidCiphertext);
         }
         let nonce_bytes = &ciphertext[..8];
-        let msg_nonce = match nonce_bytes.try_into().ok().and_then(|b: [u8;8]| Some(u64::from_le_bytes(b))) {
+        let msg_nonce = match nonce_bytes.try_into().ok().map(|b: [u8;8]| u64::from_le_bytes(b)) {
             Some(n) => n,
             None => return Err(PQError::InvalidCiphertext),
         };
         if msg_nonce < self.nonce {
             return Err(PQError::Other); // replay detected
         }
-        let key = Key::<Aes256Gcm>::from_slice(&self.rx_chain_key);
+        let key = Key::<Aes256Gcm>::from_slice(self.rx_chain_key.as_ref());
         let cipher = Aes256Gcm::new(key);
         let mut nonce_full = [0u8; 12];
         nonce_full[..8].copy_from_slice(nonce_bytes);
@@ -182,6 +532,12 @@ impl PQSession {
     }
 }
 
+/// The KEM of a device certificate's KEM key.
+fn device_kem(device: &DeviceCertificate) -> Result<Box<dyn Kem>, PQError> {
+    let algorithm = device.kem_public_key().algorithm().ok_or(PQError::Device(IdentityError::UntaggedKemKey))?;
+    Ok(registry::new_kem(algorithm))
+}
+
 fn random_u64() -> u64 {
     let mut bytes = [0u8; 8];
     OsRng.fill_bytes(&mut bytes);
@@ -0,0 +1,139 @@
+//! Sealed boxes: one-shot public-key encryption to a KEM public key.
+//!
+//! A sealed box lets a sender encrypt a message to a recipient's public key
+//! without a [`PQSession`](crate::PQSession) handshake, e.g. for blobs
+//! consumed offline. Each box encapsulates a fresh shared secret, derives an
+//! AES-256-GCM key and nonce from it with HKDF-SHA256 and encrypts the
+//! plaintext under the caller's associated data.
+//!
+//! Format (version 1), all integers big-endian:
+//!
+//! ```text
+//! magic "PQSB" (4) | version 0x01 (1) | KEM id (2) | KEM ciphertext | AES-GCM ciphertext + tag (16)
+//! ```
+//!
+//! The KEM id is [`KemAlgorithm::id`]. The HKDF info string covers the
+//! header and the KEM ciphertext, so neither can be altered without
+//! breaking decryption.
+//!
+//! ```
+//! use pq_core::kem::{Kem, MlKem768};
+//! use pq_core::sealed;
+//!
+//! let (pk, sk) = MlKem768::new().keygen().unwrap();
+//! let sealed = sealed::seal(&pk, b"job-42", b"hello").unwrap();
+//! assert_eq!(sealed::open(&sk, b"job-42", &sealed).unwrap(), b"hello");
+//! ```
+
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use hkdf::Hkdf;
+use sha2::Sha256;
+use thiserror::Error;
+use zeroize::Zeroizing;
+use crate::kem::kem::{Ciphertext, KemError, PublicKey, SecretKey};
+use crate::kem::{registry, Kem, KemAlgorithm};
+
+/// Identifies a sealed box.
+pub const MAGIC: [u8; 4] = *b"PQSB";
+/// The format version written by [`seal`].
+pub const VERSION: u8 = 1;
+
+const HEADER_LEN: usize = 4 + 1 + 2;
+const TAG_LEN: usize = 16;
+const INFO_LABEL: &[u8] = b"PQ-Core sealed box v1";
+
+#[derive(Debug, Error)]
+pub enum SealError {
+    #[error("Public key is not tagged with an algorithm")]
+    MissingAlgorithm,
+    #[error("Not a sealed box")]
+    BadMagic,
+    #[error("Unsupported sealed box version: {0}")]
+    UnsupportedVersion(u8),
+    #[error("Sealed box is truncated")]
+    Truncated,
+    #[error("Encryption failed")]
+    EncryptionFailed,
+    #[error("Decryption failed")]
+    DecryptionFailed,
+    #[error("KEM operation failed: {0}")]
+    Kem(#[from] KemError),
+}
+
+/// The number of bytes a sealed box adds to the plaintext.
+pub fn overhead(algorithm: KemAlgorithm) -> usize {
+    HEADER_LEN + registry::new_kem(algorithm).ciphertext_bytes() + TAG_LEN
+}
+
+/// Seals `plaintext` to `pk`, which must be tagged with its algorithm (as
+/// keys from [`Kem::keygen`] and the decoders in
+/// [`encoding`](crate::encoding) are).
+pub fn seal(pk: &PublicKey, aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, SealError> {
+    let algorithm = pk.algorithm().ok_or(SealError::MissingAlgorithm)?;
+    seal_with(registry::new_kem(algorithm).as_ref(), pk, aad, plaintext)
+}
+
+/// Seals `plaintext` to `pk` using `kem`, for keys held as raw bytes.
+pub fn seal_with(kem: &dyn Kem, pk: &PublicKey, aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, SealError> {
+    let (kem_ct, ss) = kem.encaps(pk)?;
+
+    let mut out = Vec::with_capacity(HEADER_LEN + kem_ct.as_ref().len() + plaintext.len() + TAG_LEN);
+    out.extend_from_slice(&MAGIC);
+    out.push(VERSION);
+    out.extend_from_slice(&kem.algorithm().id().to_be_bytes());
+    out.extend_from_slice(kem_ct.as_ref());
+
+    let (key, nonce) = derive_key(ss.as_ref(), &out)?;
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key.as_ref()));
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), Payload { msg: plaintext, aad })
+        .map_err(|_| SealError::EncryptionFailed)?;
+    out.extend_from_slice(&ciphertext);
+    Ok(out)
+}
+
+/// Opens a box produced by [`seal`] with the recipient's secret key. The
+/// KEM is taken from the box header.
+pub fn open(sk: &SecretKey, aad: &[u8], sealed: &[u8]) -> Result<Vec<u8>, SealError> {
+    if sealed.len() < HEADER_LEN {
+        return Err(SealError::Truncated);
+    }
+    if sealed[..4] != MAGIC {
+        return Err(SealError::BadMagic);
+    }
+    if sealed[4] != VERSION {
+        return Err(SealError::UnsupportedVersion(sealed[4]));
+    }
+    let algorithm = KemAlgorithm::try_from(u16::from_be_bytes([sealed[5], sealed[6]]))?;
+    let kem = registry::new_kem(algorithm);
+
+    let prefix_len = HEADER_LEN + kem.ciphertext_bytes();
+    if sealed.len() < prefix_len + TAG_LEN {
+        return Err(SealError::Truncated);
+    }
+    let (prefix, ciphertext) = sealed.split_at(prefix_len);
+    let kem_ct = Ciphertext::from_vec(prefix[HEADER_LEN..].to_vec());
+    let ss = kem.decaps(&kem_ct, sk)?;
+
+    let (key, nonce) = derive_key(ss.as_ref(), prefix)?;
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key.as_ref()));
+    cipher
+        .decrypt(Nonce::from_slice(&nonce), Payload { msg: ciphertext, aad })
+        .map_err(|_| SealError::DecryptionFailed)
+}
+
+/// Derives the AES key and nonce from the shared secret, bound to the box
+/// header and KEM ciphertext. Every box has a fresh shared secret, so the
+/// derived nonce is never reused under the same key.
+fn derive_key(ss: &[u8], prefix: &[u8]) -> Result<(Zeroizing<[u8; 32]>, [u8; 12]), SealError> {
+    let hk = Hkdf::<Sha256>::new(None, ss);
+    let mut okm = Zeroizing::new([0u8; 44]);
+    hk.expand_multi_info(&[INFO_LABEL, prefix], okm.as_mut())
+        .map_err(|_| SealError::EncryptionFailed)?;
+    let mut key = Zeroizing::new([0u8; 32]);
+    key.copy_from_slice(&okm[..32]);
+    let mut nonce = [0u8; 12];
+    nonce.copy_from_slice(&okm[32..]);
+    Ok((key, nonce))
+}
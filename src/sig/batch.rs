@@ -0,0 +1,61 @@
+//! Batched signature verification.
+//!
+//! [`Verifier::verify_batch`] checks many `(message, signature, public key)`
+//! triples at once, spread over the rayon thread pool, and returns a
+//! [`BatchVerification`] report saying which of them failed. Signature
+//! checks are independent of one another, so a batch of `n` envelopes costs
+//! roughly `n / cores` single verifications.
+
+use super::SignatureError;
+
+/// The outcome of [`Verifier::verify_batch`](super::Verifier::verify_batch):
+/// one result per input triple, in input order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchVerification {
+    results: Vec<Result<bool, SignatureError>>,
+}
+
+impl BatchVerification {
+    pub(crate) fn new(results: Vec<Result<bool, SignatureError>>) -> Self {
+        Self { results }
+    }
+
+    pub fn len(&self) -> usize {
+        self.results.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.results.is_empty()
+    }
+
+    /// True when every item verified. An empty batch is trivially valid.
+    pub fn all_valid(&self) -> bool {
+        self.results.iter().all(|result| matches!(result, Ok(true)))
+    }
+
+    /// Whether item `index` verified.
+    pub fn is_valid(&self, index: usize) -> bool {
+        matches!(self.results.get(index), Some(Ok(true)))
+    }
+
+    /// Indices of the items that failed, either because the signature did
+    /// not verify or because verification returned an error.
+    pub fn failed(&self) -> Vec<usize> {
+        self.results
+            .iter()
+            .enumerate()
+            .filter(|(_, result)| !matches!(result, Ok(true)))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// The per-item results, exactly as [`Verifier::verify`](super::Verifier::verify)
+    /// reported them.
+    pub fn results(&self) -> &[Result<bool, SignatureError>] {
+        &self.results
+    }
+
+    pub fn into_results(self) -> Vec<Result<bool, SignatureError>> {
+        self.results
+    }
+}
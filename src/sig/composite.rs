@@ -0,0 +1,312 @@
+//! Composite signatures: Ed25519 and Dilithium side by side.
+//!
+//! The signature counterpart of [`HybridKem`](crate::kem::hybrid::HybridKem):
+//! every message is signed by both components, and a composite signature is
+//! valid only if both component signatures verify. It stays unforgeable as
+//! long as either component is unbroken, and it still carries a classical
+//! Ed25519 signature for deployments that must keep one.
+//!
+//! The layout follows the IETF composite signature draft
+//! (`draft-ietf-lamps-pq-composite-sigs`), post-quantum component first:
+//! - public key: `dilithium_pk || ed25519_pk (32)`
+//! - secret key: `dilithium_sk || ed25519_seed (32)`
+//! - signature: `dilithium_sig || ed25519_sig (64)`
+//!
+//! Both components sign the same message representative
+//! `M' = Prefix || Label || len(ctx) || ctx || SHA-512(M)`, where `Prefix`
+//! is the draft's fixed prefix and `Label` names the combination, so a
+//! component signature cannot be lifted out and passed off as a standalone
+//! Ed25519 or Dilithium signature. The draft only registers ML-DSA
+//! combinations; the labels here are the crate's own, so composite keys and
+//! signatures do not interoperate with other implementations.
+//!
+//! ```
+//! use pq_core::sig::composite::Dilithium2Ed25519;
+//!
+//! let scheme = Dilithium2Ed25519::new();
+//! let (pk, sk) = scheme.keygen().unwrap();
+//! let signature = scheme.sign(b"handshake", &sk).unwrap();
+//! assert!(scheme.verify(b"handshake", &signature, &pk).unwrap());
+//! ```
+
+use std::marker::PhantomData;
+use ed25519_dalek::{Signature as Ed25519Signature, SigningKey as Ed25519SigningKey, VerifyingKey as Ed25519VerifyingKey};
+use ed25519_dalek::Signer as _;
+use rand::rngs::OsRng;
+use sha2::{Digest, Sha512};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+use super::dilithium::{Dilithium, Dilithium3};
+use super::{SignatureAlgorithm, SignatureError, Signer, Verifier};
+
+/// The draft's fixed message prefix, the ASCII string
+/// `CompositeAlgorithmSignatures2025`.
+const PREFIX: &[u8] = b"CompositeAlgorithmSignatures2025";
+
+/// The longest context string a composite signature can be bound to.
+pub const MAX_CONTEXT_BYTES: usize = 255;
+
+const ED25519_PUBLIC_KEY_BYTES: usize = 32;
+const ED25519_SECRET_KEY_BYTES: usize = 32;
+const ED25519_SIGNATURE_BYTES: usize = 64;
+
+/// Post-quantum signature schemes that can be paired with Ed25519 in a
+/// [`CompositeScheme`]. Components are stateless, so key and signature
+/// sizes can be read off a default instance.
+pub trait CompositeComponent: Signer + Clone + Default {
+    /// Identifier of the composite built on this scheme.
+    const COMPOSITE_ALGORITHM: SignatureAlgorithm;
+    /// Domain separation label signed along with every message.
+    const LABEL: &'static [u8];
+}
+
+impl CompositeComponent for Dilithium {
+    const COMPOSITE_ALGORITHM: SignatureAlgorithm = SignatureAlgorithm::Dilithium2Ed25519;
+    const LABEL: &'static [u8] = b"PQ-Core COMPSIG-Dilithium2-Ed25519-SHA512";
+}
+impl CompositeComponent for Dilithium3 {
+    const COMPOSITE_ALGORITHM: SignatureAlgorithm = SignatureAlgorithm::Dilithium3Ed25519;
+    const LABEL: &'static [u8] = b"PQ-Core COMPSIG-Dilithium3-Ed25519-SHA512";
+}
+
+/// A composite public key: `pq_pk || ed25519_pk`.
+#[derive(Debug, Clone)]
+pub struct CompositePublicKey<S> {
+    inner: Vec<u8>,
+    _scheme: PhantomData<S>,
+}
+
+/// A composite secret key: `pq_sk || ed25519_seed`. Wiped on drop, and
+/// redacted from `Debug` output.
+#[derive(Clone)]
+pub struct CompositeSecretKey<S> {
+    inner: Vec<u8>,
+    _scheme: PhantomData<S>,
+}
+
+/// A composite signature: `pq_sig || ed25519_sig`.
+#[derive(Debug, Clone)]
+pub struct CompositeSignature<S> {
+    inner: Vec<u8>,
+    _scheme: PhantomData<S>,
+}
+
+impl<S: CompositeComponent> CompositePublicKey<S> {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SignatureError> {
+        if bytes.len() != CompositeScheme::<S>::public_key_bytes() {
+            return Err(SignatureError::InvalidPublicKey);
+        }
+        Ok(Self { inner: bytes.to_vec(), _scheme: PhantomData })
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.inner
+    }
+}
+
+impl<S: CompositeComponent> CompositeSecretKey<S> {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SignatureError> {
+        if bytes.len() != CompositeScheme::<S>::secret_key_bytes() {
+            return Err(SignatureError::InvalidSecretKey);
+        }
+        Ok(Self { inner: bytes.to_vec(), _scheme: PhantomData })
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.inner
+    }
+}
+
+impl<S> Drop for CompositeSecretKey<S> {
+    fn drop(&mut self) {
+        self.inner.zeroize();
+    }
+}
+
+impl<S> ZeroizeOnDrop for CompositeSecretKey<S> {}
+
+impl<S> std::fmt::Debug for CompositeSecretKey<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "CompositeSecretKey(<{} bytes redacted>)", self.inner.len())
+    }
+}
+
+impl<S: CompositeComponent> CompositeSignature<S> {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SignatureError> {
+        if bytes.len() != CompositeScheme::<S>::signature_bytes() {
+            return Err(SignatureError::InvalidSignature);
+        }
+        Ok(Self { inner: bytes.to_vec(), _scheme: PhantomData })
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.inner
+    }
+}
+
+impl<S> AsRef<[u8]> for CompositePublicKey<S> {
+    fn as_ref(&self) -> &[u8] { &self.inner }
+}
+impl<S> AsRef<[u8]> for CompositeSecretKey<S> {
+    fn as_ref(&self) -> &[u8] { &self.inner }
+}
+impl<S> AsRef<[u8]> for CompositeSignature<S> {
+    fn as_ref(&self) -> &[u8] { &self.inner }
+}
+
+/// Ed25519 combined with the post-quantum signature scheme `S`.
+#[derive(Debug, Clone, Default)]
+pub struct CompositeScheme<S: CompositeComponent> {
+    pq: S,
+}
+
+/// Dilithium2 + Ed25519 composite.
+pub type Dilithium2Ed25519 = CompositeScheme<Dilithium>;
+/// Dilithium3 + Ed25519 composite.
+pub type Dilithium3Ed25519 = CompositeScheme<Dilithium3>;
+
+impl<S: CompositeComponent> CompositeScheme<S> {
+    pub fn new() -> Self {
+        Self { pq: S::default() }
+    }
+
+    pub fn keygen(&self) -> Result<(CompositePublicKey<S>, CompositeSecretKey<S>), SignatureError> {
+        let (pq_pk, pq_sk) = self.pq.keygen()?;
+        let ed_sk = Ed25519SigningKey::generate(&mut OsRng);
+
+        let pk = [pq_pk.as_ref(), ed_sk.verifying_key().as_bytes()].concat();
+        let sk = [pq_sk.as_ref(), ed_sk.as_bytes()].concat();
+        Ok((
+            CompositePublicKey { inner: pk, _scheme: PhantomData },
+            CompositeSecretKey { inner: sk, _scheme: PhantomData },
+        ))
+    }
+
+    /// Signs `message` with an empty context string.
+    pub fn sign(&self, message: &[u8], sk: &CompositeSecretKey<S>) -> Result<CompositeSignature<S>, SignatureError> {
+        self.sign_with_context(message, &[], sk)
+    }
+
+    pub fn verify(
+        &self,
+        message: &[u8],
+        signature: &CompositeSignature<S>,
+        pk: &CompositePublicKey<S>,
+    ) -> Result<bool, SignatureError> {
+        self.verify_with_context(message, &[], signature, pk)
+    }
+
+    /// Signs `message` with both components, bound to `ctx`, which must be
+    /// at most [`MAX_CONTEXT_BYTES`] long.
+    pub fn sign_with_context(
+        &self,
+        message: &[u8],
+        ctx: &[u8],
+        sk: &CompositeSecretKey<S>,
+    ) -> Result<CompositeSignature<S>, SignatureError> {
+        let mp = Self::message_representative(message, ctx)?;
+        let (pq_sk, ed_seed) = sk.inner.split_at(sk.inner.len() - ED25519_SECRET_KEY_BYTES);
+
+        let pq_sk = self.pq.secret_key_from_bytes(pq_sk)?;
+        let pq_sig = self.pq.sign(&mp, &pq_sk)?;
+        let ed_sk = Ed25519SigningKey::from_bytes(ed_seed.try_into().map_err(|_| SignatureError::InvalidSecretKey)?);
+        let ed_sig = ed_sk.sign(&mp);
+
+        Ok(CompositeSignature { inner: [pq_sig.as_ref(), &ed_sig.to_bytes()].concat(), _scheme: PhantomData })
+    }
+
+    /// Verifies both component signatures; the composite is valid only if
+    /// both are.
+    pub fn verify_with_context(
+        &self,
+        message: &[u8],
+        ctx: &[u8],
+        signature: &CompositeSignature<S>,
+        pk: &CompositePublicKey<S>,
+    ) -> Result<bool, SignatureError> {
+        let mp = Self::message_representative(message, ctx)?;
+        let (pq_pk, ed_pk) = pk.inner.split_at(pk.inner.len() - ED25519_PUBLIC_KEY_BYTES);
+        let (pq_sig, ed_sig) = signature.inner.split_at(signature.inner.len() - ED25519_SIGNATURE_BYTES);
+
+        let pq_pk = self.pq.public_key_from_bytes(pq_pk)?;
+        let ed_pk = Ed25519VerifyingKey::from_bytes(ed_pk.try_into().map_err(|_| SignatureError::InvalidPublicKey)?)
+            .map_err(|_| SignatureError::InvalidPublicKey)?;
+        let pq_sig = self.pq.signature_from_bytes(pq_sig)?;
+        let ed_sig = Ed25519Signature::from_slice(ed_sig).map_err(|_| SignatureError::InvalidSignature)?;
+
+        let pq_ok = self.pq.verify(&mp, &pq_sig, &pq_pk)?;
+        let ed_ok = ed_pk.verify_strict(&mp, &ed_sig).is_ok();
+        Ok(pq_ok && ed_ok)
+    }
+
+    /// `M' = Prefix || Label || len(ctx) || ctx || SHA-512(M)`
+    fn message_representative(message: &[u8], ctx: &[u8]) -> Result<Vec<u8>, SignatureError> {
+        let ctx_len = u8::try_from(ctx.len()).map_err(|_| SignatureError::ContextTooLong)?;
+        Ok([PREFIX, S::LABEL, &[ctx_len], ctx, &Sha512::digest(message)].concat())
+    }
+
+    pub fn public_key_bytes() -> usize {
+        S::default().public_key_bytes() + ED25519_PUBLIC_KEY_BYTES
+    }
+
+    pub fn secret_key_bytes() -> usize {
+        S::default().secret_key_bytes() + ED25519_SECRET_KEY_BYTES
+    }
+
+    pub fn signature_bytes() -> usize {
+        S::default().signature_bytes() + ED25519_SIGNATURE_BYTES
+    }
+}
+
+impl<S: CompositeComponent> Verifier for CompositeScheme<S> {
+    type PublicKey = CompositePublicKey<S>;
+    type Signature = CompositeSignature<S>;
+
+    fn algorithm(&self) -> SignatureAlgorithm {
+        S::COMPOSITE_ALGORITHM
+    }
+
+    fn verify(
+        &self,
+        message: &[u8],
+        signature: &CompositeSignature<S>,
+        pk: &CompositePublicKey<S>,
+    ) -> Result<bool, SignatureError> {
+        CompositeScheme::verify(self, message, signature, pk)
+    }
+
+    fn public_key_from_bytes(&self, bytes: &[u8]) -> Result<CompositePublicKey<S>, SignatureError> {
+        CompositePublicKey::from_bytes(bytes)
+    }
+
+    fn signature_from_bytes(&self, bytes: &[u8]) -> Result<CompositeSignature<S>, SignatureError> {
+        CompositeSignature::from_bytes(bytes)
+    }
+
+    fn public_key_bytes(&self) -> usize {
+        Self::public_key_bytes()
+    }
+
+    fn signature_bytes(&self) -> usize {
+        Self::signature_bytes()
+    }
+}
+
+impl<S: CompositeComponent> Signer for CompositeScheme<S> {
+    type SecretKey = CompositeSecretKey<S>;
+
+    fn keygen(&self) -> Result<(CompositePublicKey<S>, CompositeSecretKey<S>), SignatureError> {
+        CompositeScheme::keygen(self)
+    }
+
+    fn sign(&self, message: &[u8], sk: &CompositeSecretKey<S>) -> Result<CompositeSignature<S>, SignatureError> {
+        CompositeScheme::sign(self, message, sk)
+    }
+
+    fn secret_key_from_bytes(&self, bytes: &[u8]) -> Result<CompositeSecretKey<S>, SignatureError> {
+        CompositeSecretKey::from_bytes(bytes)
+    }
+
+    fn secret_key_bytes(&self) -> usize {
+        Self::secret_key_bytes()
+    }
+}
@@ -1,152 +1,283 @@
-use pqcrypto_dilithium::dilithium2;
-use pqcrypto_traits::sign::{PublicKey as PQPublicKey, SecretKey as PQSecretKey, SignedMessage as PQSignedMessage};
-use zeroize::Zeroize;
-
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub enum DilithiumError {
-    InvalidPublicKey,
-    InvalidSecretKey,
-    InvalidSignature,
-    SigningError,
-    VerificationError,
-}
+use pqcrypto_dilithium::{dilithium2, dilithium3, dilithium5};
+use pqcrypto_traits::sign::{
+    DetachedSignature as PQDetachedSignature, PublicKey as PQPublicKey, SecretKey as PQSecretKey,
+    SignedMessage as PQSignedMessage,
+};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+use super::dilithium_keygen::{keypair_from_seed, DILITHIUM2, DILITHIUM3, DILITHIUM5, SEED_BYTES};
+use super::{SignatureAlgorithm, SignatureError, Signer, Verifier};
+
+/// Errors from the Dilithium implementation; shared by every signature
+/// scheme.
+pub type DilithiumError = SignatureError;
 
-impl std::fmt::Display for DilithiumError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            DilithiumError::InvalidPublicKey => write!(f, "Invalid public key"),
-            DilithiumError::InvalidSecretKey => write!(f, "Invalid secret key"),
-            DilithiumError::InvalidSignature => write!(f, "Invalid signature"),
-            DilithiumError::SigningError => write!(f, "Signing operation failed"),
-            DilithiumError::VerificationError => write!(f, "Verification failed"),
+/// Generates one Dilithium parameter set: the unit-like scheme type and its
+/// public key, secret key and signature types, wrapping one of the
+/// `pqcrypto_dilithium` modules. Every parameter set has its own key types,
+/// so `from_bytes` rejects keys and signatures of another level.
+macro_rules! dilithium_scheme {
+    (
+        $(#[$meta:meta])*
+        $name:ident, $pk:ident, $sk:ident, $sig:ident, $backend:ident, $params:expr, $algorithm:expr
+    ) => {
+        #[derive(Debug, Clone)]
+        pub struct $pk {
+            inner: Vec<u8>,
         }
-    }
-}
 
-impl std::error::Error for DilithiumError {}
+        impl $pk {
+            pub fn from_bytes(bytes: &[u8]) -> Result<Self, DilithiumError> {
+                if bytes.len() != $backend::public_key_bytes() {
+                    return Err(DilithiumError::InvalidPublicKey);
+                }
+                Ok(Self {
+                    inner: bytes.to_vec(),
+                })
+            }
 
-#[derive(Debug, Clone)]
-pub struct DilithiumPublicKey {
-    inner: Vec<u8>,
-}
+            pub fn as_bytes(&self) -> &[u8] {
+                &self.inner
+            }
+        }
 
-impl DilithiumPublicKey {
-    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DilithiumError> {
-        if bytes.len() != dilithium2::public_key_bytes() {
-            return Err(DilithiumError::InvalidPublicKey);
+        #[derive(Clone)]
+        pub struct $sk {
+            inner: Vec<u8>,
         }
-        Ok(Self {
-            inner: bytes.to_vec(),
-        })
-    }
-
-    pub fn as_bytes(&self) -> &[u8] {
-        &self.inner
-    }
-}
 
-#[derive(Debug, Clone)]
-pub struct DilithiumSecretKey {
-    inner: Vec<u8>,
-}
+        impl $sk {
+            pub fn from_bytes(bytes: &[u8]) -> Result<Self, DilithiumError> {
+                if bytes.len() != $backend::secret_key_bytes() {
+                    return Err(DilithiumError::InvalidSecretKey);
+                }
+                Ok(Self {
+                    inner: bytes.to_vec(),
+                })
+            }
 
-impl DilithiumSecretKey {
-    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DilithiumError> {
-        if bytes.len() != dilithium2::secret_key_bytes() {
-            return Err(DilithiumError::InvalidSecretKey);
+            pub fn as_bytes(&self) -> &[u8] {
+                &self.inner
+            }
         }
-        Ok(Self {
-            inner: bytes.to_vec(),
-        })
-    }
-
-    pub fn as_bytes(&self) -> &[u8] {
-        &self.inner
-    }
-}
 
-impl Drop for DilithiumSecretKey {
-    fn drop(&mut self) {
-        self.inner.zeroize();
-    }
-}
+        impl Drop for $sk {
+            fn drop(&mut self) {
+                self.inner.zeroize();
+            }
+        }
 
-#[derive(Debug, Clone)]
-pub struct DilithiumSignature {
-    inner: Vec<u8>,
-}
+        impl ZeroizeOnDrop for $sk {}
 
-impl DilithiumSignature {
-    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DilithiumError> {
-        if bytes.len() != dilithium2::signature_bytes() {
-            return Err(DilithiumError::InvalidSignature);
+        impl std::fmt::Debug for $sk {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, concat!(stringify!($sk), "(<{} bytes redacted>)"), self.inner.len())
+            }
         }
-        Ok(Self {
-            inner: bytes.to_vec(),
-        })
-    }
-
-    pub fn as_bytes(&self) -> &[u8] {
-        &self.inner
-    }
-}
 
-#[derive(Debug, Clone)]
-pub struct Dilithium;
-
-impl Dilithium {
-    pub fn new() -> Self {
-        Self
-    }
-
-    pub fn keygen(&self) -> Result<(DilithiumPublicKey, DilithiumSecretKey), DilithiumError> {
-        let (pk_bytes, sk_bytes) = dilithium2::keypair();
-        
-        Ok((
-            DilithiumPublicKey {
-                inner: pk_bytes.as_bytes().to_vec(),
-            },
-            DilithiumSecretKey {
-                inner: sk_bytes.as_bytes().to_vec(),
-            },
-        ))
-    }
-
-    pub fn sign(&self, message: &[u8], sk: &DilithiumSecretKey) -> Result<DilithiumSignature, DilithiumError> {
-        let sk_dilithium = dilithium2::SecretKey::from_bytes(&sk.inner)
-            .map_err(|_| DilithiumError::InvalidSecretKey)?;
-        
-        let sig_bytes = dilithium2::sign(message, &sk_dilithium);
-        
-        Ok(DilithiumSignature {
-            inner: sig_bytes.as_bytes().to_vec(),
-        })
-    }
-
-    pub fn verify(&self, message: &[u8], signature: &DilithiumSignature, pk: &DilithiumPublicKey) -> Result<bool, DilithiumError> {
-        let pk_dilithium = dilithium2::PublicKey::from_bytes(&pk.inner)
-            .map_err(|_| DilithiumError::InvalidPublicKey)?;
-        
-        // Create a SignedMessage from the signature bytes
-        let signed_msg = dilithium2::SignedMessage::from_bytes(&signature.inner)
-            .map_err(|_| DilithiumError::InvalidSignature)?;
-        
-        // Use open to verify and recover the message
-        match dilithium2::open(&signed_msg, &pk_dilithium) {
-            Ok(recovered_msg) => Ok(recovered_msg == message),
-            Err(_) => Ok(false),
+        #[derive(Debug, Clone)]
+        pub struct $sig {
+            inner: Vec<u8>,
+        }
+
+        impl $sig {
+            pub fn from_bytes(bytes: &[u8]) -> Result<Self, DilithiumError> {
+                if bytes.len() != $backend::signature_bytes() {
+                    return Err(DilithiumError::InvalidSignature);
+                }
+                Ok(Self {
+                    inner: bytes.to_vec(),
+                })
+            }
+
+            pub fn as_bytes(&self) -> &[u8] {
+                &self.inner
+            }
+        }
+
+        impl AsRef<[u8]> for $pk {
+            fn as_ref(&self) -> &[u8] { &self.inner }
+        }
+        impl AsRef<[u8]> for $sk {
+            fn as_ref(&self) -> &[u8] { &self.inner }
+        }
+        impl AsRef<[u8]> for $sig {
+            fn as_ref(&self) -> &[u8] { &self.inner }
+        }
+
+        $(#[$meta])*
+        #[derive(Debug, Clone, Default)]
+        pub struct $name;
+
+        impl $name {
+            pub fn new() -> Self {
+                Self
+            }
+
+            pub fn keygen(&self) -> Result<($pk, $sk), DilithiumError> {
+                let (pk_bytes, sk_bytes) = $backend::keypair();
+
+                Ok((
+                    $pk {
+                        inner: pk_bytes.as_bytes().to_vec(),
+                    },
+                    $sk {
+                        inner: sk_bytes.as_bytes().to_vec(),
+                    },
+                ))
+            }
+
+            /// Deterministically derives a keypair from a `seed_bytes()`-byte seed.
+            ///
+            /// The same seed always yields the same keypair, so keys can be recovered
+            /// from a stored seed. Signing is deterministic as well, so signatures made
+            /// with a recovered key are reproducible too.
+            pub fn keygen_from_seed(&self, seed: &[u8]) -> Result<($pk, $sk), DilithiumError> {
+                let seed: &[u8; SEED_BYTES] = seed.try_into().map_err(|_| DilithiumError::InvalidSeed)?;
+                let (pk, sk) = keypair_from_seed(&$params, seed);
+
+                Ok((
+                    $pk { inner: pk },
+                    $sk { inner: sk },
+                ))
+            }
+
+            /// Signs `message`, returning a detached signature of exactly
+            /// `signature_bytes()` bytes.
+            pub fn sign(&self, message: &[u8], sk: &$sk) -> Result<$sig, DilithiumError> {
+                let sk_dilithium = $backend::SecretKey::from_bytes(&sk.inner)
+                    .map_err(|_| DilithiumError::InvalidSecretKey)?;
+
+                let sig_bytes = $backend::detached_sign(message, &sk_dilithium);
+
+                Ok($sig {
+                    inner: sig_bytes.as_bytes().to_vec(),
+                })
+            }
+
+            pub fn verify(&self, message: &[u8], signature: &$sig, pk: &$pk) -> Result<bool, DilithiumError> {
+                let pk_dilithium = $backend::PublicKey::from_bytes(&pk.inner)
+                    .map_err(|_| DilithiumError::InvalidPublicKey)?;
+                let sig_dilithium = $backend::DetachedSignature::from_bytes(&signature.inner)
+                    .map_err(|_| DilithiumError::InvalidSignature)?;
+
+                Ok($backend::verify_detached_signature(&sig_dilithium, message, &pk_dilithium).is_ok())
+            }
+
+            /// Signs `message` in the attached form: the signature followed by
+            /// the message itself, as produced by the reference `crypto_sign`.
+            pub fn sign_attached(&self, message: &[u8], sk: &$sk) -> Result<Vec<u8>, DilithiumError> {
+                let sk_dilithium = $backend::SecretKey::from_bytes(&sk.inner)
+                    .map_err(|_| DilithiumError::InvalidSecretKey)?;
+
+                Ok($backend::sign(message, &sk_dilithium).as_bytes().to_vec())
+            }
+
+            /// Verifies an attached signed message from
+            /// [`sign_attached`](Self::sign_attached) and returns the message.
+            pub fn open_attached(&self, signed_message: &[u8], pk: &$pk) -> Result<Vec<u8>, DilithiumError> {
+                let pk_dilithium = $backend::PublicKey::from_bytes(&pk.inner)
+                    .map_err(|_| DilithiumError::InvalidPublicKey)?;
+                if signed_message.len() < $backend::signature_bytes() {
+                    return Err(DilithiumError::InvalidSignature);
+                }
+                let signed_msg = $backend::SignedMessage::from_bytes(signed_message)
+                    .map_err(|_| DilithiumError::InvalidSignature)?;
+
+                $backend::open(&signed_msg, &pk_dilithium).map_err(|_| DilithiumError::VerificationError)
+            }
+
+            /// Verifies `(message, signature, public key)` triples, spread over the
+            /// rayon thread pool. Results are returned in input order, one per
+            /// triple, exactly as [`verify`](Self::verify) would report them;
+            /// [`Verifier::verify_batch`] returns the same as a report.
+            pub fn batch_verify(
+                &self,
+                items: &[(&[u8], &$sig, &$pk)],
+            ) -> Vec<Result<bool, DilithiumError>> {
+                Verifier::verify_batch(self, items).into_results()
+            }
+
+            pub fn public_key_bytes() -> usize {
+                $backend::public_key_bytes()
+            }
+
+            pub fn secret_key_bytes() -> usize {
+                $backend::secret_key_bytes()
+            }
+
+            pub fn signature_bytes() -> usize {
+                $backend::signature_bytes()
+            }
+
+            pub fn seed_bytes() -> usize {
+                SEED_BYTES
+            }
         }
-    }
 
-    pub fn public_key_bytes() -> usize {
-        dilithium2::public_key_bytes()
-    }
+        impl Verifier for $name {
+            type PublicKey = $pk;
+            type Signature = $sig;
+
+            fn algorithm(&self) -> SignatureAlgorithm {
+                $algorithm
+            }
 
-    pub fn secret_key_bytes() -> usize {
-        dilithium2::secret_key_bytes()
-    }
+            fn verify(&self, message: &[u8], signature: &$sig, pk: &$pk) -> Result<bool, SignatureError> {
+                $name::verify(self, message, signature, pk)
+            }
+
+            fn public_key_from_bytes(&self, bytes: &[u8]) -> Result<$pk, SignatureError> {
+                $pk::from_bytes(bytes)
+            }
+
+            fn signature_from_bytes(&self, bytes: &[u8]) -> Result<$sig, SignatureError> {
+                $sig::from_bytes(bytes)
+            }
+
+            fn public_key_bytes(&self) -> usize {
+                $backend::public_key_bytes()
+            }
+
+            fn signature_bytes(&self) -> usize {
+                $backend::signature_bytes()
+            }
+        }
+
+        impl Signer for $name {
+            type SecretKey = $sk;
+
+            fn keygen(&self) -> Result<($pk, $sk), SignatureError> {
+                $name::keygen(self)
+            }
+
+            fn sign(&self, message: &[u8], sk: &$sk) -> Result<$sig, SignatureError> {
+                $name::sign(self, message, sk)
+            }
+
+            fn secret_key_from_bytes(&self, bytes: &[u8]) -> Result<$sk, SignatureError> {
+                $sk::from_bytes(bytes)
+            }
+
+            fn secret_key_bytes(&self) -> usize {
+                $backend::secret_key_bytes()
+            }
+        }
+
+    };
+}
 
-    pub fn signature_bytes() -> usize {
-        dilithium2::signature_bytes()
-    }
-}
\ No newline at end of file
+dilithium_scheme!(
+    /// Dilithium2 (NIST security level 2).
+    Dilithium, DilithiumPublicKey, DilithiumSecretKey, DilithiumSignature, dilithium2, DILITHIUM2,
+    SignatureAlgorithm::Dilithium2
+);
+dilithium_scheme!(
+    /// Dilithium3 (NIST security level 3).
+    Dilithium3, Dilithium3PublicKey, Dilithium3SecretKey, Dilithium3Signature, dilithium3, DILITHIUM3,
+    SignatureAlgorithm::Dilithium3
+);
+dilithium_scheme!(
+    /// Dilithium5 (NIST security level 5).
+    Dilithium5, Dilithium5PublicKey, Dilithium5SecretKey, Dilithium5Signature, dilithium5, DILITHIUM5,
+    SignatureAlgorithm::Dilithium5
+);
@@ -0,0 +1,148 @@
+//! Native Dilithium key expansion.
+//!
+//! The `pqcrypto-dilithium` backend always draws its key seed from the OS RNG.
+//! This module re-implements the (deterministic) expansion from a 32-byte seed
+//! to a keypair, producing the exact encodings used by the PQClean code so the
+//! keys work with the backend's `sign`/`open`.
+
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+use sha3::{Shake128, Shake256};
+use zeroize::{Zeroize, Zeroizing};
+use crate::math::encode::pack_bits;
+use crate::math::sample;
+use crate::math::ntt::{bit_reversed_powers, inv_ntt_negacyclic, ntt_negacyclic};
+
+const N: usize = 256;
+const Q: i64 = 8_380_417;
+const D: u32 = 13;
+const ROOT_OF_UNITY: i64 = 1753;
+
+pub(crate) const SEED_BYTES: usize = 32;
+const CRH_BYTES: usize = 64;
+const TR_BYTES: usize = 64;
+
+/// Parameters that affect key generation for one Dilithium security level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct DilithiumParams {
+    /// Rows of the matrix A (length of s2 and t).
+    pub k: usize,
+    /// Columns of the matrix A (length of s1).
+    pub l: usize,
+    /// Bound on the coefficients of s1 and s2.
+    pub eta: u32,
+}
+
+pub(crate) const DILITHIUM2: DilithiumParams = DilithiumParams { k: 4, l: 4, eta: 2 };
+pub(crate) const DILITHIUM3: DilithiumParams = DilithiumParams { k: 6, l: 5, eta: 4 };
+pub(crate) const DILITHIUM5: DilithiumParams = DilithiumParams { k: 8, l: 7, eta: 2 };
+
+fn xof_reader<X: Update + ExtendableOutput + Default>(parts: &[&[u8]]) -> X::Reader {
+    let mut xof = X::default();
+    for part in parts {
+        xof.update(part);
+    }
+    xof.finalize_xof()
+}
+
+/// Samples a polynomial with uniform coefficients mod q, directly in the NTT
+/// domain (ExpandA).
+fn sample_uniform(rho: &[u8], nonce: u16) -> Vec<i64> {
+    let mut reader = xof_reader::<Shake128>(&[rho, &nonce.to_le_bytes()]);
+    sample::sample_uniform(&mut reader, Q, 23, N)
+}
+
+/// Samples a polynomial with coefficients in `[-eta, eta]` (ExpandS).
+fn sample_eta(rhoprime: &[u8], nonce: u16, eta: u32) -> Vec<i64> {
+    let mut reader = xof_reader::<Shake256>(&[rhoprime, &nonce.to_le_bytes()]);
+    let mut poly = Vec::with_capacity(N);
+    let mut buf = [0u8; 1];
+    while poly.len() < N {
+        reader.read(&mut buf);
+        for t in [buf[0] & 0x0F, buf[0] >> 4] {
+            if poly.len() == N {
+                break;
+            }
+            let t = i64::from(t);
+            match eta {
+                2 if t < 15 => poly.push(2 - t % 5),
+                4 if t < 9 => poly.push(4 - t),
+                _ => {}
+            }
+        }
+    }
+    poly
+}
+
+/// Expands `seed` into a `(public_key, secret_key)` pair in the PQClean
+/// encoding for the given parameter set.
+pub(crate) fn keypair_from_seed(params: &DilithiumParams, seed: &[u8; SEED_BYTES]) -> (Vec<u8>, Vec<u8>) {
+    let DilithiumParams { k, l, eta } = *params;
+    let zetas = bit_reversed_powers(ROOT_OF_UNITY, Q, N);
+
+    let mut seedbuf = Zeroizing::new([0u8; 2 * SEED_BYTES + CRH_BYTES]);
+    xof_reader::<Shake256>(&[seed]).read(seedbuf.as_mut());
+    let rho = &seedbuf[..SEED_BYTES];
+    let rhoprime = &seedbuf[SEED_BYTES..SEED_BYTES + CRH_BYTES];
+    let key = &seedbuf[SEED_BYTES + CRH_BYTES..];
+
+    let mut s1: Vec<Vec<i64>> = (0..l).map(|j| sample_eta(rhoprime, j as u16, eta)).collect();
+    let mut s2: Vec<Vec<i64>> = (0..k).map(|i| sample_eta(rhoprime, (l + i) as u16, eta)).collect();
+
+    let mut s1_hat: Vec<Vec<i64>> = s1
+        .iter()
+        .map(|p| {
+            let mut p: Vec<i64> = p.iter().map(|c| c.rem_euclid(Q)).collect();
+            ntt_negacyclic(&mut p, &zetas, Q, 1);
+            p
+        })
+        .collect();
+
+    // t = A * s1 + s2, split into high bits t1 and low bits t0
+    let mut t1 = Vec::with_capacity(k * N);
+    let mut t0 = Vec::with_capacity(k * N);
+    for (i, s2_i) in s2.iter().enumerate() {
+        let mut t = vec![0i64; N];
+        for (j, s1_hat_j) in s1_hat.iter().enumerate() {
+            let a = sample_uniform(rho, ((i << 8) + j) as u16);
+            for c in 0..N {
+                t[c] = (t[c] + a[c] * s1_hat_j[c]) % Q;
+            }
+        }
+        inv_ntt_negacyclic(&mut t, &zetas, Q, 1);
+        for c in 0..N {
+            let v = (t[c] + s2_i[c]).rem_euclid(Q);
+            let hi = (v + (1 << (D - 1)) - 1) >> D;
+            t1.push(hi as u32);
+            t0.push(((1 << (D - 1)) - (v - (hi << D))) as u32);
+        }
+        t.zeroize();
+    }
+
+    let mut pk = Vec::with_capacity(SEED_BYTES + k * N * 10 / 8);
+    pk.extend_from_slice(rho);
+    pk.extend_from_slice(&pack_bits(&t1, 10));
+
+    let mut tr = [0u8; TR_BYTES];
+    xof_reader::<Shake256>(&[&pk]).read(&mut tr);
+
+    let eta_bits = if eta == 2 { 3 } else { 4 };
+    let mut eta_coeffs: Vec<u32> = s1
+        .iter()
+        .chain(s2.iter())
+        .flatten()
+        .map(|&c| (i64::from(eta) - c) as u32)
+        .collect();
+
+    let mut sk = Vec::new();
+    sk.extend_from_slice(rho);
+    sk.extend_from_slice(key);
+    sk.extend_from_slice(&tr);
+    sk.extend_from_slice(&pack_bits(&eta_coeffs, eta_bits));
+    sk.extend_from_slice(&pack_bits(&t0, D));
+
+    eta_coeffs.zeroize();
+    t0.zeroize();
+    s1.iter_mut().chain(s2.iter_mut()).chain(s1_hat.iter_mut()).for_each(|p| p.zeroize());
+
+    (pk, sk)
+}
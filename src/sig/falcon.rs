@@ -0,0 +1,235 @@
+//! Falcon signatures, backed by `pqcrypto-falcon` (PQClean).
+//!
+//! Falcon has the smallest public key plus signature of the lattice
+//! schemes, which makes it the choice for bandwidth-constrained links:
+//! Falcon-512 sends 897 + 666 bytes where ML-DSA-44 sends 1312 + 2420.
+//!
+//! Falcon signatures are compressed and so vary in length from one
+//! signature to the next. [`signature_bytes`](Falcon512::signature_bytes)
+//! reports the maximum, which is what a message layout should budget for;
+//! the signature types hold exactly the bytes produced. A signature is the
+//! header byte `0x30 + log2(n)`, the 40-byte nonce and the compressed
+//! signature proper, the detached form of the reference implementation.
+//!
+//! ```
+//! use pq_core::sig::falcon::Falcon512;
+//!
+//! let scheme = Falcon512::new();
+//! let (pk, sk) = scheme.keygen().unwrap();
+//! let signature = scheme.sign(b"telemetry", &sk).unwrap();
+//! assert!(signature.as_bytes().len() <= Falcon512::signature_bytes());
+//! assert!(scheme.verify(b"telemetry", &signature, &pk).unwrap());
+//! ```
+
+use pqcrypto_falcon::{falcon512, falcon1024};
+use pqcrypto_traits::sign::{DetachedSignature as PQDetachedSignature, PublicKey as PQPublicKey, SecretKey as PQSecretKey};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+use super::{SignatureAlgorithm, SignatureError, Signer, Verifier};
+
+/// The header byte and nonce that start every signature.
+const SIGNATURE_OVERHEAD: usize = 1 + 40;
+
+/// Generates one Falcon parameter set: the unit-like scheme type and its
+/// public key, secret key and signature types, wrapping one of the
+/// `pqcrypto_falcon` modules. As with [`super::dilithium`], every parameter
+/// set has its own key types, so `from_bytes` rejects keys and signatures of
+/// another set.
+macro_rules! falcon_scheme {
+    (
+        $(#[$meta:meta])*
+        $name:ident, $pk:ident, $sk:ident, $sig:ident, $backend:ident, $logn:expr, $algorithm:expr
+    ) => {
+        #[derive(Debug, Clone)]
+        pub struct $pk {
+            inner: Vec<u8>,
+        }
+
+        impl $pk {
+            pub fn from_bytes(bytes: &[u8]) -> Result<Self, SignatureError> {
+                if bytes.len() != $backend::public_key_bytes() {
+                    return Err(SignatureError::InvalidPublicKey);
+                }
+                Ok(Self { inner: bytes.to_vec() })
+            }
+
+            pub fn as_bytes(&self) -> &[u8] {
+                &self.inner
+            }
+        }
+
+        #[derive(Clone)]
+        pub struct $sk {
+            inner: Vec<u8>,
+        }
+
+        impl $sk {
+            pub fn from_bytes(bytes: &[u8]) -> Result<Self, SignatureError> {
+                if bytes.len() != $backend::secret_key_bytes() {
+                    return Err(SignatureError::InvalidSecretKey);
+                }
+                Ok(Self { inner: bytes.to_vec() })
+            }
+
+            pub fn as_bytes(&self) -> &[u8] {
+                &self.inner
+            }
+        }
+
+        impl Drop for $sk {
+            fn drop(&mut self) {
+                self.inner.zeroize();
+            }
+        }
+
+        impl ZeroizeOnDrop for $sk {}
+
+        impl std::fmt::Debug for $sk {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, concat!(stringify!($sk), "(<{} bytes redacted>)"), self.inner.len())
+            }
+        }
+
+        #[derive(Debug, Clone)]
+        pub struct $sig {
+            inner: Vec<u8>,
+        }
+
+        impl $sig {
+            /// Accepts any length up to `signature_bytes()`, as long as the
+            /// header byte names this parameter set.
+            pub fn from_bytes(bytes: &[u8]) -> Result<Self, SignatureError> {
+                if bytes.len() <= SIGNATURE_OVERHEAD
+                    || bytes.len() > $backend::signature_bytes()
+                    || bytes[0] != 0x30 + $logn
+                {
+                    return Err(SignatureError::InvalidSignature);
+                }
+                Ok(Self { inner: bytes.to_vec() })
+            }
+
+            pub fn as_bytes(&self) -> &[u8] {
+                &self.inner
+            }
+        }
+
+        impl AsRef<[u8]> for $pk {
+            fn as_ref(&self) -> &[u8] { &self.inner }
+        }
+        impl AsRef<[u8]> for $sk {
+            fn as_ref(&self) -> &[u8] { &self.inner }
+        }
+        impl AsRef<[u8]> for $sig {
+            fn as_ref(&self) -> &[u8] { &self.inner }
+        }
+
+        $(#[$meta])*
+        #[derive(Debug, Clone, Default)]
+        pub struct $name;
+
+        impl $name {
+            pub fn new() -> Self {
+                Self
+            }
+
+            pub fn keygen(&self) -> Result<($pk, $sk), SignatureError> {
+                let (pk, sk) = $backend::keypair();
+                Ok((
+                    $pk { inner: pk.as_bytes().to_vec() },
+                    $sk { inner: sk.as_bytes().to_vec() },
+                ))
+            }
+
+            /// Signs `message`, returning a detached signature of at most
+            /// `signature_bytes()` bytes.
+            pub fn sign(&self, message: &[u8], sk: &$sk) -> Result<$sig, SignatureError> {
+                let sk_falcon = $backend::SecretKey::from_bytes(&sk.inner)
+                    .map_err(|_| SignatureError::InvalidSecretKey)?;
+
+                let signature = $backend::detached_sign(message, &sk_falcon);
+                Ok($sig { inner: signature.as_bytes().to_vec() })
+            }
+
+            pub fn verify(&self, message: &[u8], signature: &$sig, pk: &$pk) -> Result<bool, SignatureError> {
+                let pk_falcon = $backend::PublicKey::from_bytes(&pk.inner)
+                    .map_err(|_| SignatureError::InvalidPublicKey)?;
+                let sig_falcon = $backend::DetachedSignature::from_bytes(&signature.inner)
+                    .map_err(|_| SignatureError::InvalidSignature)?;
+
+                Ok($backend::verify_detached_signature(&sig_falcon, message, &pk_falcon).is_ok())
+            }
+
+            pub fn public_key_bytes() -> usize {
+                $backend::public_key_bytes()
+            }
+
+            pub fn secret_key_bytes() -> usize {
+                $backend::secret_key_bytes()
+            }
+
+            /// The longest signature this parameter set produces.
+            pub fn signature_bytes() -> usize {
+                $backend::signature_bytes()
+            }
+        }
+
+        impl Verifier for $name {
+            type PublicKey = $pk;
+            type Signature = $sig;
+
+            fn algorithm(&self) -> SignatureAlgorithm {
+                $algorithm
+            }
+
+            fn verify(&self, message: &[u8], signature: &$sig, pk: &$pk) -> Result<bool, SignatureError> {
+                $name::verify(self, message, signature, pk)
+            }
+
+            fn public_key_from_bytes(&self, bytes: &[u8]) -> Result<$pk, SignatureError> {
+                $pk::from_bytes(bytes)
+            }
+
+            fn signature_from_bytes(&self, bytes: &[u8]) -> Result<$sig, SignatureError> {
+                $sig::from_bytes(bytes)
+            }
+
+            fn public_key_bytes(&self) -> usize {
+                $backend::public_key_bytes()
+            }
+
+            fn signature_bytes(&self) -> usize {
+                $backend::signature_bytes()
+            }
+        }
+
+        impl Signer for $name {
+            type SecretKey = $sk;
+
+            fn keygen(&self) -> Result<($pk, $sk), SignatureError> {
+                $name::keygen(self)
+            }
+
+            fn sign(&self, message: &[u8], sk: &$sk) -> Result<$sig, SignatureError> {
+                $name::sign(self, message, sk)
+            }
+
+            fn secret_key_from_bytes(&self, bytes: &[u8]) -> Result<$sk, SignatureError> {
+                $sk::from_bytes(bytes)
+            }
+
+            fn secret_key_bytes(&self) -> usize {
+                $backend::secret_key_bytes()
+            }
+        }
+    };
+}
+
+falcon_scheme!(
+    /// Falcon-512 (NIST security category 1).
+    Falcon512, Falcon512PublicKey, Falcon512SecretKey, Falcon512Signature, falcon512, 9,
+    SignatureAlgorithm::Falcon512
+);
+falcon_scheme!(
+    /// Falcon-1024 (NIST security category 5).
+    Falcon1024, Falcon1024PublicKey, Falcon1024SecretKey, Falcon1024Signature, falcon1024, 10,
+    SignatureAlgorithm::Falcon1024
+);
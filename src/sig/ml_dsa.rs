@@ -0,0 +1,474 @@
+//! FIPS 204 ML-DSA, backed by the pure-Rust `ml-dsa` crate.
+//!
+//! Unlike the pre-standard Dilithium in [`super::dilithium`], ML-DSA binds
+//! every signature to a context string of up to 255 bytes (empty by
+//! default), and its keys and signatures are not interchangeable with
+//! Dilithium's even where the sizes match.
+//!
+//! Besides signing a message directly, ML-DSA supports the HashML-DSA
+//! pre-hash mode: the message is streamed through a [`PreHasher`] and only
+//! its digest is signed, so arbitrarily large inputs never have to be held
+//! in memory. Pre-hash signatures are domain-separated from direct ones and
+//! only verify with [`verify_prehashed`](MlDsa65::verify_prehashed).
+//!
+//! ```
+//! use pq_core::sig::ml_dsa::{MlDsa65, PreHash};
+//!
+//! let scheme = MlDsa65::new();
+//! let (pk, sk) = scheme.keygen().unwrap();
+//!
+//! let mut hasher = PreHash::Sha512.hasher();
+//! for chunk in [&b"artifact "[..], b"in ", b"chunks"] {
+//!     hasher.update(chunk);
+//! }
+//! let signature = scheme.sign_prehashed(hasher, b"release", &sk).unwrap();
+//!
+//! let mut hasher = PreHash::Sha512.hasher();
+//! hasher.update(b"artifact in chunks");
+//! assert!(scheme.verify_prehashed(hasher, b"release", &signature, &pk).unwrap());
+//! ```
+
+use std::io;
+use ml_dsa::{EncodedSignature, EncodedSigningKey, EncodedVerifyingKey, KeyGen, B32};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sha2::{Digest, Sha256, Sha512};
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+use sha3::{Shake128, Shake256};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+use super::{SignatureAlgorithm, SignatureError, Signer, Verifier};
+
+/// Seed size for deterministic key generation: `ξ`.
+const SEED_BYTES: usize = 32;
+
+/// The longest context string FIPS 204 allows.
+pub const MAX_CONTEXT_BYTES: usize = 255;
+
+/// The hash functions HashML-DSA can pre-hash a message with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PreHash {
+    Sha256,
+    Sha512,
+    /// SHAKE128 with a 256-bit output
+    Shake128,
+    /// SHAKE256 with a 512-bit output
+    Shake256,
+}
+
+impl PreHash {
+    /// Starts hashing a message for [`sign_prehashed`](MlDsa65::sign_prehashed)
+    /// or [`verify_prehashed`](MlDsa65::verify_prehashed).
+    pub fn hasher(self) -> PreHasher {
+        PreHasher::new(self)
+    }
+
+    /// The DER encoding of the hash function's OID, which HashML-DSA signs
+    /// along with the digest.
+    fn oid_der(self) -> [u8; 11] {
+        let last = match self {
+            PreHash::Sha256 => 0x01,
+            PreHash::Sha512 => 0x03,
+            PreHash::Shake128 => 0x0b,
+            PreHash::Shake256 => 0x0c,
+        };
+        // 2.16.840.1.101.3.4.2.<last>
+        [0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, last]
+    }
+}
+
+#[derive(Clone)]
+enum PreHashState {
+    Sha256(Sha256),
+    Sha512(Sha512),
+    Shake128(Shake128),
+    Shake256(Shake256),
+}
+
+/// Incrementally hashes a message for HashML-DSA.
+///
+/// Feed the message with [`update`](Self::update), or copy a reader into it
+/// with [`std::io::copy`], then hand the hasher to the signing or verifying
+/// call.
+#[derive(Clone)]
+pub struct PreHasher {
+    algorithm: PreHash,
+    state: PreHashState,
+}
+
+impl PreHasher {
+    pub fn new(algorithm: PreHash) -> Self {
+        let state = match algorithm {
+            PreHash::Sha256 => PreHashState::Sha256(Sha256::new()),
+            PreHash::Sha512 => PreHashState::Sha512(Sha512::new()),
+            PreHash::Shake128 => PreHashState::Shake128(Shake128::default()),
+            PreHash::Shake256 => PreHashState::Shake256(Shake256::default()),
+        };
+        Self { algorithm, state }
+    }
+
+    /// The hash function in use.
+    pub fn algorithm(&self) -> PreHash {
+        self.algorithm
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        match &mut self.state {
+            PreHashState::Sha256(h) => Digest::update(h, data),
+            PreHashState::Sha512(h) => Digest::update(h, data),
+            PreHashState::Shake128(h) => Update::update(h, data),
+            PreHashState::Shake256(h) => Update::update(h, data),
+        }
+    }
+
+    /// The message digest `PH(M)`.
+    fn finalize(self) -> Vec<u8> {
+        match self.state {
+            PreHashState::Sha256(h) => h.finalize().to_vec(),
+            PreHashState::Sha512(h) => h.finalize().to_vec(),
+            PreHashState::Shake128(h) => xof_output(h.finalize_xof(), 32),
+            PreHashState::Shake256(h) => xof_output(h.finalize_xof(), 64),
+        }
+    }
+}
+
+impl std::fmt::Debug for PreHasher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PreHasher").field("algorithm", &self.algorithm).finish_non_exhaustive()
+    }
+}
+
+impl io::Write for PreHasher {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn xof_output(mut reader: impl XofReader, len: usize) -> Vec<u8> {
+    let mut out = vec![0u8; len];
+    reader.read(&mut out);
+    out
+}
+
+fn check_context(ctx: &[u8]) -> Result<u8, SignatureError> {
+    u8::try_from(ctx.len()).map_err(|_| SignatureError::ContextTooLong)
+}
+
+/// FIPS 204 HintBitUnpack requires the hint positions of each polynomial to
+/// be strictly increasing. The backend also accepts repeated positions,
+/// which would make signatures malleable, so they are rejected here.
+fn hint_is_canonical(sig: &[u8], omega: usize, k: usize) -> bool {
+    let hint = &sig[sig.len() - omega - k..];
+    let (indices, cuts) = hint.split_at(omega);
+    let mut start = 0;
+    for &end in cuts {
+        let end = usize::from(end);
+        if end < start || end > omega {
+            return false;
+        }
+        if indices[start..end].windows(2).any(|w| w[0] >= w[1]) {
+            return false;
+        }
+        start = end;
+    }
+    true
+}
+
+/// Generates one ML-DSA parameter set: the scheme type and its public key,
+/// secret key and signature types, wrapping one of the `ml_dsa` parameter
+/// sets. As with [`super::dilithium`], every parameter set has its own key
+/// types, so `from_bytes` rejects keys and signatures of another level.
+macro_rules! ml_dsa_scheme {
+    (
+        $(#[$meta:meta])*
+        $name:ident, $pk:ident, $sk:ident, $sig:ident, $params:ty, $algorithm:expr,
+        sizes: ($pk_len:expr, $sk_len:expr, $sig_len:expr), hint: ($omega:expr, $k:expr)
+    ) => {
+        #[derive(Debug, Clone)]
+        pub struct $pk {
+            inner: Vec<u8>,
+        }
+
+        impl $pk {
+            pub fn from_bytes(bytes: &[u8]) -> Result<Self, SignatureError> {
+                if bytes.len() != $pk_len {
+                    return Err(SignatureError::InvalidPublicKey);
+                }
+                Ok(Self { inner: bytes.to_vec() })
+            }
+
+            pub fn as_bytes(&self) -> &[u8] {
+                &self.inner
+            }
+        }
+
+        #[derive(Clone)]
+        pub struct $sk {
+            inner: Vec<u8>,
+        }
+
+        impl $sk {
+            pub fn from_bytes(bytes: &[u8]) -> Result<Self, SignatureError> {
+                if bytes.len() != $sk_len {
+                    return Err(SignatureError::InvalidSecretKey);
+                }
+                Ok(Self { inner: bytes.to_vec() })
+            }
+
+            pub fn as_bytes(&self) -> &[u8] {
+                &self.inner
+            }
+        }
+
+        impl Drop for $sk {
+            fn drop(&mut self) {
+                self.inner.zeroize();
+            }
+        }
+
+        impl ZeroizeOnDrop for $sk {}
+
+        impl std::fmt::Debug for $sk {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, concat!(stringify!($sk), "(<{} bytes redacted>)"), self.inner.len())
+            }
+        }
+
+        #[derive(Debug, Clone)]
+        pub struct $sig {
+            inner: Vec<u8>,
+        }
+
+        impl $sig {
+            pub fn from_bytes(bytes: &[u8]) -> Result<Self, SignatureError> {
+                if bytes.len() != $sig_len {
+                    return Err(SignatureError::InvalidSignature);
+                }
+                Ok(Self { inner: bytes.to_vec() })
+            }
+
+            pub fn as_bytes(&self) -> &[u8] {
+                &self.inner
+            }
+        }
+
+        impl AsRef<[u8]> for $pk {
+            fn as_ref(&self) -> &[u8] { &self.inner }
+        }
+        impl AsRef<[u8]> for $sk {
+            fn as_ref(&self) -> &[u8] { &self.inner }
+        }
+        impl AsRef<[u8]> for $sig {
+            fn as_ref(&self) -> &[u8] { &self.inner }
+        }
+
+        $(#[$meta])*
+        ///
+        /// Signing is hedged by default, mixing fresh randomness into every
+        /// signature; [`new_deterministic`](Self::new_deterministic) selects
+        /// the deterministic variant instead.
+        #[derive(Debug, Clone, Default)]
+        pub struct $name {
+            deterministic: bool,
+        }
+
+        impl $name {
+            pub fn new() -> Self {
+                Self { deterministic: false }
+            }
+
+            /// Signs with the FIPS 204 deterministic variant, so the same key,
+            /// message and context always produce the same signature.
+            pub fn new_deterministic() -> Self {
+                Self { deterministic: true }
+            }
+
+            pub fn keygen(&self) -> Result<($pk, $sk), SignatureError> {
+                let mut seed = Zeroizing::new([0u8; SEED_BYTES]);
+                OsRng.fill_bytes(seed.as_mut());
+                self.keygen_from_seed(seed.as_ref())
+            }
+
+            /// Deterministically derives a keypair from the `seed_bytes()`-byte
+            /// FIPS 204 seed `ξ`.
+            pub fn keygen_from_seed(&self, seed: &[u8]) -> Result<($pk, $sk), SignatureError> {
+                let xi = B32::try_from(seed).map_err(|_| SignatureError::InvalidSeed)?;
+                let kp = <$params>::key_gen_internal(&xi);
+                Ok((
+                    $pk { inner: kp.verifying_key().encode().to_vec() },
+                    $sk { inner: kp.signing_key().encode().to_vec() },
+                ))
+            }
+
+            /// Signs `message` with an empty context string.
+            pub fn sign(&self, message: &[u8], sk: &$sk) -> Result<$sig, SignatureError> {
+                self.sign_with_context(message, &[], sk)
+            }
+
+            pub fn verify(&self, message: &[u8], signature: &$sig, pk: &$pk) -> Result<bool, SignatureError> {
+                self.verify_with_context(message, &[], signature, pk)
+            }
+
+            /// Signs `message` bound to `ctx`, which must be at most
+            /// [`MAX_CONTEXT_BYTES`] long. The signature only verifies under the
+            /// same context.
+            pub fn sign_with_context(&self, message: &[u8], ctx: &[u8], sk: &$sk) -> Result<$sig, SignatureError> {
+                let ctx_len = check_context(ctx)?;
+                self.sign_internal(&[&[0], &[ctx_len], ctx, message], sk)
+            }
+
+            pub fn verify_with_context(
+                &self,
+                message: &[u8],
+                ctx: &[u8],
+                signature: &$sig,
+                pk: &$pk,
+            ) -> Result<bool, SignatureError> {
+                let ctx_len = check_context(ctx)?;
+                Self::verify_internal(&[&[0], &[ctx_len], ctx, message], signature, pk)
+            }
+
+            /// Signs the message hashed by `hasher` with HashML-DSA, bound to
+            /// `ctx`.
+            pub fn sign_prehashed(&self, hasher: PreHasher, ctx: &[u8], sk: &$sk) -> Result<$sig, SignatureError> {
+                let ctx_len = check_context(ctx)?;
+                let oid = hasher.algorithm().oid_der();
+                let digest = hasher.finalize();
+                self.sign_internal(&[&[1], &[ctx_len], ctx, &oid, &digest], sk)
+            }
+
+            /// Verifies a [`sign_prehashed`](Self::sign_prehashed) signature
+            /// over the message hashed by `hasher`.
+            pub fn verify_prehashed(
+                &self,
+                hasher: PreHasher,
+                ctx: &[u8],
+                signature: &$sig,
+                pk: &$pk,
+            ) -> Result<bool, SignatureError> {
+                let ctx_len = check_context(ctx)?;
+                let oid = hasher.algorithm().oid_der();
+                let digest = hasher.finalize();
+                Self::verify_internal(&[&[1], &[ctx_len], ctx, &oid, &digest], signature, pk)
+            }
+
+            /// ML-DSA.Sign_internal over the message representative `M'`,
+            /// given as the concatenation of `mp`.
+            fn sign_internal(&self, mp: &[&[u8]], sk: &$sk) -> Result<$sig, SignatureError> {
+                let enc = EncodedSigningKey::<$params>::try_from(sk.inner.as_slice())
+                    .map_err(|_| SignatureError::InvalidSecretKey)?;
+                let signing_key = ml_dsa::SigningKey::<$params>::decode(&enc);
+
+                let mut rnd = B32::default();
+                if !self.deterministic {
+                    OsRng.fill_bytes(&mut rnd);
+                }
+                let signature = signing_key.sign_internal(mp, &rnd);
+                rnd.zeroize();
+
+                Ok($sig { inner: signature.encode().to_vec() })
+            }
+
+            fn verify_internal(mp: &[&[u8]], signature: &$sig, pk: &$pk) -> Result<bool, SignatureError> {
+                let enc_pk = EncodedVerifyingKey::<$params>::try_from(pk.inner.as_slice())
+                    .map_err(|_| SignatureError::InvalidPublicKey)?;
+                let enc_sig = EncodedSignature::<$params>::try_from(signature.inner.as_slice())
+                    .map_err(|_| SignatureError::InvalidSignature)?;
+
+                // A malformed encoding is a signature that does not verify
+                if !hint_is_canonical(&signature.inner, $omega, $k) {
+                    return Ok(false);
+                }
+                let Some(sigma) = ml_dsa::Signature::<$params>::decode(&enc_sig) else {
+                    return Ok(false);
+                };
+                Ok(ml_dsa::VerifyingKey::<$params>::decode(&enc_pk).verify_internal(mp, &sigma))
+            }
+
+            pub fn public_key_bytes() -> usize {
+                $pk_len
+            }
+
+            pub fn secret_key_bytes() -> usize {
+                $sk_len
+            }
+
+            pub fn signature_bytes() -> usize {
+                $sig_len
+            }
+
+            pub fn seed_bytes() -> usize {
+                SEED_BYTES
+            }
+        }
+
+        impl Verifier for $name {
+            type PublicKey = $pk;
+            type Signature = $sig;
+
+            fn algorithm(&self) -> SignatureAlgorithm {
+                $algorithm
+            }
+
+            fn verify(&self, message: &[u8], signature: &$sig, pk: &$pk) -> Result<bool, SignatureError> {
+                $name::verify(self, message, signature, pk)
+            }
+
+            fn public_key_from_bytes(&self, bytes: &[u8]) -> Result<$pk, SignatureError> {
+                $pk::from_bytes(bytes)
+            }
+
+            fn signature_from_bytes(&self, bytes: &[u8]) -> Result<$sig, SignatureError> {
+                $sig::from_bytes(bytes)
+            }
+
+            fn public_key_bytes(&self) -> usize {
+                $pk_len
+            }
+
+            fn signature_bytes(&self) -> usize {
+                $sig_len
+            }
+        }
+
+        impl Signer for $name {
+            type SecretKey = $sk;
+
+            fn keygen(&self) -> Result<($pk, $sk), SignatureError> {
+                $name::keygen(self)
+            }
+
+            fn sign(&self, message: &[u8], sk: &$sk) -> Result<$sig, SignatureError> {
+                $name::sign(self, message, sk)
+            }
+
+            fn secret_key_from_bytes(&self, bytes: &[u8]) -> Result<$sk, SignatureError> {
+                $sk::from_bytes(bytes)
+            }
+
+            fn secret_key_bytes(&self) -> usize {
+                $sk_len
+            }
+        }
+    };
+}
+
+ml_dsa_scheme!(
+    /// ML-DSA-44 (NIST security category 2).
+    MlDsa44, MlDsa44PublicKey, MlDsa44SecretKey, MlDsa44Signature, ml_dsa::MlDsa44, SignatureAlgorithm::MlDsa44,
+    sizes: (1312, 2560, 2420), hint: (80, 4)
+);
+ml_dsa_scheme!(
+    /// ML-DSA-65 (NIST security category 3).
+    MlDsa65, MlDsa65PublicKey, MlDsa65SecretKey, MlDsa65Signature, ml_dsa::MlDsa65, SignatureAlgorithm::MlDsa65,
+    sizes: (1952, 4032, 3309), hint: (55, 6)
+);
+ml_dsa_scheme!(
+    /// ML-DSA-87 (NIST security category 5).
+    MlDsa87, MlDsa87PublicKey, MlDsa87SecretKey, MlDsa87Signature, ml_dsa::MlDsa87, SignatureAlgorithm::MlDsa87,
+    sizes: (2592, 4896, 4627), hint: (75, 8)
+);
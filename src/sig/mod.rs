@@ -1 +1,264 @@
-pub mod dilithium;
\ No newline at end of file
+//! Digital signature schemes.
+//!
+//! [`Signer`] and [`Verifier`] are the signature counterpart of
+//! [`Kem`](crate::kem::Kem): protocol code written against them works with
+//! any scheme, and each scheme keeps its own key and signature types.
+
+mod batch;
+pub mod composite;
+pub mod dilithium;
+mod dilithium_keygen;
+pub mod falcon;
+pub mod ml_dsa;
+pub mod slh_dsa;
+
+use rayon::prelude::*;
+use thiserror::Error;
+
+pub use batch::BatchVerification;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum SignatureError {
+    #[error("Invalid public key")]
+    InvalidPublicKey,
+    #[error("Invalid secret key")]
+    InvalidSecretKey,
+    #[error("Invalid signature")]
+    InvalidSignature,
+    #[error("Signing operation failed")]
+    SigningError,
+    #[error("Verification failed")]
+    VerificationError,
+    #[error("Invalid key generation seed")]
+    InvalidSeed,
+    #[error("Context string longer than 255 bytes")]
+    ContextTooLong,
+    #[error("Unknown signature algorithm: {0}")]
+    UnknownAlgorithm(String),
+}
+
+/// The verifying half of a signature scheme.
+pub trait Verifier: Send + Sync {
+    type PublicKey: Clone + AsRef<[u8]> + Send + Sync;
+    type Signature: Clone + AsRef<[u8]> + Send + Sync;
+
+    fn algorithm(&self) -> SignatureAlgorithm;
+
+    /// Checks `signature` over `message`. Returns `Ok(false)` for a
+    /// well-formed signature that does not verify.
+    fn verify(&self, message: &[u8], signature: &Self::Signature, pk: &Self::PublicKey) -> Result<bool, SignatureError>;
+
+    /// Decodes a public key, checking its length.
+    fn public_key_from_bytes(&self, bytes: &[u8]) -> Result<Self::PublicKey, SignatureError>;
+
+    /// Decodes a signature, checking its length.
+    fn signature_from_bytes(&self, bytes: &[u8]) -> Result<Self::Signature, SignatureError>;
+
+    fn public_key_bytes(&self) -> usize;
+
+    /// The signature size, or the largest possible signature for schemes
+    /// whose signatures vary in length.
+    fn signature_bytes(&self) -> usize;
+
+    /// Verifies `(message, signature, public key)` triples, spread over the
+    /// rayon thread pool. The report lists which items failed.
+    fn verify_batch(&self, items: &[(&[u8], &Self::Signature, &Self::PublicKey)]) -> BatchVerification {
+        BatchVerification::new(
+            items
+                .par_iter()
+                .map(|(message, signature, pk)| self.verify(message, signature, pk))
+                .collect(),
+        )
+    }
+}
+
+/// The signing half of a signature scheme.
+pub trait Signer: Verifier {
+    type SecretKey: Clone + AsRef<[u8]>;
+
+    fn keygen(&self) -> Result<(Self::PublicKey, Self::SecretKey), SignatureError>;
+    fn sign(&self, message: &[u8], sk: &Self::SecretKey) -> Result<Self::Signature, SignatureError>;
+
+    /// Decodes a secret key, checking its length.
+    fn secret_key_from_bytes(&self, bytes: &[u8]) -> Result<Self::SecretKey, SignatureError>;
+
+    fn secret_key_bytes(&self) -> usize;
+}
+
+/// Identifies a signature algorithm and parameter set, mirroring
+/// [`KemAlgorithm`](crate::kem::KemAlgorithm) on the KEM side.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SignatureAlgorithm {
+    /// Dilithium2 (`pqcrypto-dilithium`)
+    Dilithium2,
+    /// Dilithium3 (`pqcrypto-dilithium`)
+    Dilithium3,
+    /// Dilithium5 (`pqcrypto-dilithium`)
+    Dilithium5,
+    /// ML-DSA-44 (FIPS 204)
+    MlDsa44,
+    /// ML-DSA-65 (FIPS 204)
+    MlDsa65,
+    /// ML-DSA-87 (FIPS 204)
+    MlDsa87,
+    /// SLH-DSA-SHA2-128s (FIPS 205)
+    SlhDsaSha2_128s,
+    /// SLH-DSA-SHA2-128f (FIPS 205)
+    SlhDsaSha2_128f,
+    /// SLH-DSA-SHA2-192s (FIPS 205)
+    SlhDsaSha2_192s,
+    /// SLH-DSA-SHA2-192f (FIPS 205)
+    SlhDsaSha2_192f,
+    /// SLH-DSA-SHA2-256s (FIPS 205)
+    SlhDsaSha2_256s,
+    /// SLH-DSA-SHA2-256f (FIPS 205)
+    SlhDsaSha2_256f,
+    /// SLH-DSA-SHAKE-128s (FIPS 205)
+    SlhDsaShake128s,
+    /// SLH-DSA-SHAKE-128f (FIPS 205)
+    SlhDsaShake128f,
+    /// SLH-DSA-SHAKE-192s (FIPS 205)
+    SlhDsaShake192s,
+    /// SLH-DSA-SHAKE-192f (FIPS 205)
+    SlhDsaShake192f,
+    /// SLH-DSA-SHAKE-256s (FIPS 205)
+    SlhDsaShake256s,
+    /// SLH-DSA-SHAKE-256f (FIPS 205)
+    SlhDsaShake256f,
+    /// Falcon-512 (`pqcrypto-falcon`)
+    Falcon512,
+    /// Falcon-1024 (`pqcrypto-falcon`)
+    Falcon1024,
+    /// Dilithium2 + Ed25519 composite
+    Dilithium2Ed25519,
+    /// Dilithium3 + Ed25519 composite
+    Dilithium3Ed25519,
+}
+
+impl SignatureAlgorithm {
+    /// Every supported signature algorithm, in identifier order.
+    pub const ALL: [SignatureAlgorithm; 22] = [
+        SignatureAlgorithm::Dilithium2,
+        SignatureAlgorithm::Dilithium3,
+        SignatureAlgorithm::Dilithium5,
+        SignatureAlgorithm::MlDsa44,
+        SignatureAlgorithm::MlDsa65,
+        SignatureAlgorithm::MlDsa87,
+        SignatureAlgorithm::SlhDsaSha2_128s,
+        SignatureAlgorithm::SlhDsaSha2_128f,
+        SignatureAlgorithm::SlhDsaSha2_192s,
+        SignatureAlgorithm::SlhDsaSha2_192f,
+        SignatureAlgorithm::SlhDsaSha2_256s,
+        SignatureAlgorithm::SlhDsaSha2_256f,
+        SignatureAlgorithm::SlhDsaShake128s,
+        SignatureAlgorithm::SlhDsaShake128f,
+        SignatureAlgorithm::SlhDsaShake192s,
+        SignatureAlgorithm::SlhDsaShake192f,
+        SignatureAlgorithm::SlhDsaShake256s,
+        SignatureAlgorithm::SlhDsaShake256f,
+        SignatureAlgorithm::Falcon512,
+        SignatureAlgorithm::Falcon1024,
+        SignatureAlgorithm::Dilithium2Ed25519,
+        SignatureAlgorithm::Dilithium3Ed25519,
+    ];
+
+    /// Stable numeric identifier for config files and wire messages.
+    ///
+    /// The high byte names the family (`0x00` Dilithium, `0x01` ML-DSA,
+    /// `0x02` SLH-DSA, `0x03` Falcon, `0x04` Ed25519 composites), the low
+    /// byte the parameter set: its NIST security category, except for
+    /// SLH-DSA, which has several sets per category and numbers them in
+    /// FIPS 205 OID order. Identifiers are never reused or renumbered.
+    pub const fn id(self) -> u16 {
+        match self {
+            SignatureAlgorithm::Dilithium2 => 0x0002,
+            SignatureAlgorithm::Dilithium3 => 0x0003,
+            SignatureAlgorithm::Dilithium5 => 0x0005,
+            SignatureAlgorithm::MlDsa44 => 0x0102,
+            SignatureAlgorithm::MlDsa65 => 0x0103,
+            SignatureAlgorithm::MlDsa87 => 0x0105,
+            SignatureAlgorithm::SlhDsaSha2_128s => 0x0201,
+            SignatureAlgorithm::SlhDsaSha2_128f => 0x0202,
+            SignatureAlgorithm::SlhDsaSha2_192s => 0x0203,
+            SignatureAlgorithm::SlhDsaSha2_192f => 0x0204,
+            SignatureAlgorithm::SlhDsaSha2_256s => 0x0205,
+            SignatureAlgorithm::SlhDsaSha2_256f => 0x0206,
+            SignatureAlgorithm::SlhDsaShake128s => 0x0207,
+            SignatureAlgorithm::SlhDsaShake128f => 0x0208,
+            SignatureAlgorithm::SlhDsaShake192s => 0x0209,
+            SignatureAlgorithm::SlhDsaShake192f => 0x020a,
+            SignatureAlgorithm::SlhDsaShake256s => 0x020b,
+            SignatureAlgorithm::SlhDsaShake256f => 0x020c,
+            SignatureAlgorithm::Falcon512 => 0x0301,
+            SignatureAlgorithm::Falcon1024 => 0x0305,
+            SignatureAlgorithm::Dilithium2Ed25519 => 0x0402,
+            SignatureAlgorithm::Dilithium3Ed25519 => 0x0403,
+        }
+    }
+
+    /// Stable string identifier, also used by `Display`.
+    pub const fn name(self) -> &'static str {
+        match self {
+            SignatureAlgorithm::Dilithium2 => "Dilithium2",
+            SignatureAlgorithm::Dilithium3 => "Dilithium3",
+            SignatureAlgorithm::Dilithium5 => "Dilithium5",
+            SignatureAlgorithm::MlDsa44 => "ML-DSA-44",
+            SignatureAlgorithm::MlDsa65 => "ML-DSA-65",
+            SignatureAlgorithm::MlDsa87 => "ML-DSA-87",
+            SignatureAlgorithm::SlhDsaSha2_128s => "SLH-DSA-SHA2-128s",
+            SignatureAlgorithm::SlhDsaSha2_128f => "SLH-DSA-SHA2-128f",
+            SignatureAlgorithm::SlhDsaSha2_192s => "SLH-DSA-SHA2-192s",
+            SignatureAlgorithm::SlhDsaSha2_192f => "SLH-DSA-SHA2-192f",
+            SignatureAlgorithm::SlhDsaSha2_256s => "SLH-DSA-SHA2-256s",
+            SignatureAlgorithm::SlhDsaSha2_256f => "SLH-DSA-SHA2-256f",
+            SignatureAlgorithm::SlhDsaShake128s => "SLH-DSA-SHAKE-128s",
+            SignatureAlgorithm::SlhDsaShake128f => "SLH-DSA-SHAKE-128f",
+            SignatureAlgorithm::SlhDsaShake192s => "SLH-DSA-SHAKE-192s",
+            SignatureAlgorithm::SlhDsaShake192f => "SLH-DSA-SHAKE-192f",
+            SignatureAlgorithm::SlhDsaShake256s => "SLH-DSA-SHAKE-256s",
+            SignatureAlgorithm::SlhDsaShake256f => "SLH-DSA-SHAKE-256f",
+            SignatureAlgorithm::Falcon512 => "Falcon-512",
+            SignatureAlgorithm::Falcon1024 => "Falcon-1024",
+            SignatureAlgorithm::Dilithium2Ed25519 => "Dilithium2-Ed25519",
+            SignatureAlgorithm::Dilithium3Ed25519 => "Dilithium3-Ed25519",
+        }
+    }
+
+    /// Looks up an algorithm by its [`id`](Self::id).
+    pub fn from_id(id: u16) -> Option<Self> {
+        Self::ALL.into_iter().find(|alg| alg.id() == id)
+    }
+
+    /// Looks up an algorithm by its [`name`](Self::name), ignoring ASCII case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|alg| alg.name().eq_ignore_ascii_case(name))
+    }
+}
+
+impl std::fmt::Display for SignatureAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl std::str::FromStr for SignatureAlgorithm {
+    type Err = SignatureError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_name(s).ok_or_else(|| SignatureError::UnknownAlgorithm(s.to_string()))
+    }
+}
+
+impl TryFrom<u16> for SignatureAlgorithm {
+    type Error = SignatureError;
+
+    fn try_from(id: u16) -> Result<Self, Self::Error> {
+        Self::from_id(id).ok_or_else(|| SignatureError::UnknownAlgorithm(format!("{id:#06x}")))
+    }
+}
+
+impl From<SignatureAlgorithm> for u16 {
+    fn from(alg: SignatureAlgorithm) -> u16 {
+        alg.id()
+    }
+}
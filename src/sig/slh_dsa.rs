@@ -0,0 +1,346 @@
+//! FIPS 205 SLH-DSA, backed by the pure-Rust `slh-dsa` crate.
+//!
+//! SLH-DSA is a stateless hash-based scheme: its security rests only on the
+//! underlying hash function, which makes it the conservative choice for
+//! long-lived root-of-trust keys. The price is size and speed. Public keys
+//! are tiny (32 to 64 bytes) but signatures run from 7856 to 49856 bytes,
+//! and signing takes orders of magnitude longer than with ML-DSA.
+//!
+//! Every security level comes in an `s` (small signatures, slow signing)
+//! and an `f` (fast signing, larger signatures) variant, each over SHA-2 or
+//! SHAKE, for twelve parameter sets in all. As with ML-DSA, signatures are
+//! bound to a context string of up to [`MAX_CONTEXT_BYTES`] bytes.
+//!
+//! ```
+//! use pq_core::sig::slh_dsa::SlhDsaShake128f;
+//!
+//! let scheme = SlhDsaShake128f::new();
+//! let (pk, sk) = scheme.keygen().unwrap();
+//!
+//! let signature = scheme.sign_with_context(b"root key rotation", b"ca", &sk).unwrap();
+//! assert!(scheme.verify_with_context(b"root key rotation", b"ca", &signature, &pk).unwrap());
+//! assert_eq!(signature.as_bytes().len(), SlhDsaShake128f::signature_bytes());
+//! ```
+
+use rand::rngs::OsRng;
+use rand::RngCore;
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+use super::{SignatureAlgorithm, SignatureError, Signer, Verifier};
+
+/// The longest context string FIPS 205 allows.
+pub const MAX_CONTEXT_BYTES: usize = 255;
+
+fn check_context(ctx: &[u8]) -> Result<(), SignatureError> {
+    if ctx.len() > MAX_CONTEXT_BYTES {
+        return Err(SignatureError::ContextTooLong);
+    }
+    Ok(())
+}
+
+/// Generates one SLH-DSA parameter set: the scheme type and its public key,
+/// secret key and signature types, wrapping one of the `slh_dsa` parameter
+/// sets. Every parameter set has its own key types, so `from_bytes` rejects
+/// keys and signatures of another set.
+///
+/// `$n` is the set's security parameter: public keys are `2n` bytes, secret
+/// keys `4n` bytes and key generation seeds `3n` bytes.
+macro_rules! slh_dsa_scheme {
+    (
+        $(#[$meta:meta])*
+        $name:ident, $pk:ident, $sk:ident, $sig:ident, $params:ty, $algorithm:expr,
+        n: $n:expr, signature: $sig_len:expr
+    ) => {
+        #[derive(Debug, Clone)]
+        pub struct $pk {
+            inner: Vec<u8>,
+        }
+
+        impl $pk {
+            pub fn from_bytes(bytes: &[u8]) -> Result<Self, SignatureError> {
+                if bytes.len() != 2 * $n {
+                    return Err(SignatureError::InvalidPublicKey);
+                }
+                Ok(Self { inner: bytes.to_vec() })
+            }
+
+            pub fn as_bytes(&self) -> &[u8] {
+                &self.inner
+            }
+        }
+
+        #[derive(Clone)]
+        pub struct $sk {
+            inner: Vec<u8>,
+        }
+
+        impl $sk {
+            pub fn from_bytes(bytes: &[u8]) -> Result<Self, SignatureError> {
+                if bytes.len() != 4 * $n {
+                    return Err(SignatureError::InvalidSecretKey);
+                }
+                Ok(Self { inner: bytes.to_vec() })
+            }
+
+            pub fn as_bytes(&self) -> &[u8] {
+                &self.inner
+            }
+        }
+
+        impl Drop for $sk {
+            fn drop(&mut self) {
+                self.inner.zeroize();
+            }
+        }
+
+        impl ZeroizeOnDrop for $sk {}
+
+        impl std::fmt::Debug for $sk {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, concat!(stringify!($sk), "(<{} bytes redacted>)"), self.inner.len())
+            }
+        }
+
+        #[derive(Debug, Clone)]
+        pub struct $sig {
+            inner: Vec<u8>,
+        }
+
+        impl $sig {
+            pub fn from_bytes(bytes: &[u8]) -> Result<Self, SignatureError> {
+                if bytes.len() != $sig_len {
+                    return Err(SignatureError::InvalidSignature);
+                }
+                Ok(Self { inner: bytes.to_vec() })
+            }
+
+            pub fn as_bytes(&self) -> &[u8] {
+                &self.inner
+            }
+        }
+
+        impl AsRef<[u8]> for $pk {
+            fn as_ref(&self) -> &[u8] { &self.inner }
+        }
+        impl AsRef<[u8]> for $sk {
+            fn as_ref(&self) -> &[u8] { &self.inner }
+        }
+        impl AsRef<[u8]> for $sig {
+            fn as_ref(&self) -> &[u8] { &self.inner }
+        }
+
+        $(#[$meta])*
+        ///
+        /// Signing is hedged by default, mixing fresh randomness into every
+        /// signature; [`new_deterministic`](Self::new_deterministic) selects
+        /// the deterministic variant instead.
+        #[derive(Debug, Clone, Default)]
+        pub struct $name {
+            deterministic: bool,
+        }
+
+        impl $name {
+            pub fn new() -> Self {
+                Self { deterministic: false }
+            }
+
+            /// Signs with the FIPS 205 deterministic variant, so the same key,
+            /// message and context always produce the same signature.
+            pub fn new_deterministic() -> Self {
+                Self { deterministic: true }
+            }
+
+            pub fn keygen(&self) -> Result<($pk, $sk), SignatureError> {
+                let mut seed = Zeroizing::new([0u8; 3 * $n]);
+                OsRng.fill_bytes(seed.as_mut());
+                self.keygen_from_seed(seed.as_ref())
+            }
+
+            /// Deterministically derives a keypair from a `seed_bytes()`-byte
+            /// seed, the FIPS 205 values `SK.seed || SK.prf || PK.seed`.
+            pub fn keygen_from_seed(&self, seed: &[u8]) -> Result<($pk, $sk), SignatureError> {
+                if seed.len() != 3 * $n {
+                    return Err(SignatureError::InvalidSeed);
+                }
+                let (sk_seed, rest) = seed.split_at($n);
+                let (sk_prf, pk_seed) = rest.split_at($n);
+                let signing_key = slh_dsa::SigningKey::<$params>::slh_keygen_internal(sk_seed, sk_prf, pk_seed);
+
+                let mut sk_bytes = signing_key.to_bytes();
+                let sk = $sk { inner: sk_bytes.to_vec() };
+                sk_bytes.zeroize();
+                Ok(($pk { inner: sk.inner[2 * $n..].to_vec() }, sk))
+            }
+
+            /// Signs `message` with an empty context string.
+            pub fn sign(&self, message: &[u8], sk: &$sk) -> Result<$sig, SignatureError> {
+                self.sign_with_context(message, &[], sk)
+            }
+
+            pub fn verify(&self, message: &[u8], signature: &$sig, pk: &$pk) -> Result<bool, SignatureError> {
+                self.verify_with_context(message, &[], signature, pk)
+            }
+
+            /// Signs `message` bound to `ctx`, which must be at most
+            /// [`MAX_CONTEXT_BYTES`] long. The signature only verifies under the
+            /// same context.
+            pub fn sign_with_context(&self, message: &[u8], ctx: &[u8], sk: &$sk) -> Result<$sig, SignatureError> {
+                check_context(ctx)?;
+                let signing_key = slh_dsa::SigningKey::<$params>::try_from(sk.inner.as_slice())
+                    .map_err(|_| SignatureError::InvalidSecretKey)?;
+
+                let mut opt_rand = [0u8; $n];
+                if !self.deterministic {
+                    OsRng.fill_bytes(&mut opt_rand);
+                }
+                let opt_rand = (!self.deterministic).then_some(&opt_rand[..]);
+                let signature = signing_key
+                    .try_sign_with_context(message, ctx, opt_rand)
+                    .map_err(|_| SignatureError::SigningError)?;
+
+                Ok($sig { inner: signature.to_bytes().to_vec() })
+            }
+
+            pub fn verify_with_context(
+                &self,
+                message: &[u8],
+                ctx: &[u8],
+                signature: &$sig,
+                pk: &$pk,
+            ) -> Result<bool, SignatureError> {
+                check_context(ctx)?;
+                let verifying_key = slh_dsa::VerifyingKey::<$params>::try_from(pk.inner.as_slice())
+                    .map_err(|_| SignatureError::InvalidPublicKey)?;
+                let signature = slh_dsa::Signature::<$params>::try_from(signature.inner.as_slice())
+                    .map_err(|_| SignatureError::InvalidSignature)?;
+
+                Ok(verifying_key.try_verify_with_context(message, ctx, &signature).is_ok())
+            }
+
+            pub fn public_key_bytes() -> usize {
+                2 * $n
+            }
+
+            pub fn secret_key_bytes() -> usize {
+                4 * $n
+            }
+
+            pub fn signature_bytes() -> usize {
+                $sig_len
+            }
+
+            pub fn seed_bytes() -> usize {
+                3 * $n
+            }
+        }
+
+        impl Verifier for $name {
+            type PublicKey = $pk;
+            type Signature = $sig;
+
+            fn algorithm(&self) -> SignatureAlgorithm {
+                $algorithm
+            }
+
+            fn verify(&self, message: &[u8], signature: &$sig, pk: &$pk) -> Result<bool, SignatureError> {
+                $name::verify(self, message, signature, pk)
+            }
+
+            fn public_key_from_bytes(&self, bytes: &[u8]) -> Result<$pk, SignatureError> {
+                $pk::from_bytes(bytes)
+            }
+
+            fn signature_from_bytes(&self, bytes: &[u8]) -> Result<$sig, SignatureError> {
+                $sig::from_bytes(bytes)
+            }
+
+            fn public_key_bytes(&self) -> usize {
+                2 * $n
+            }
+
+            fn signature_bytes(&self) -> usize {
+                $sig_len
+            }
+        }
+
+        impl Signer for $name {
+            type SecretKey = $sk;
+
+            fn keygen(&self) -> Result<($pk, $sk), SignatureError> {
+                $name::keygen(self)
+            }
+
+            fn sign(&self, message: &[u8], sk: &$sk) -> Result<$sig, SignatureError> {
+                $name::sign(self, message, sk)
+            }
+
+            fn secret_key_from_bytes(&self, bytes: &[u8]) -> Result<$sk, SignatureError> {
+                $sk::from_bytes(bytes)
+            }
+
+            fn secret_key_bytes(&self) -> usize {
+                4 * $n
+            }
+        }
+    };
+}
+
+slh_dsa_scheme!(
+    /// SLH-DSA-SHA2-128s (NIST security category 1, small signatures).
+    SlhDsaSha2_128s, SlhDsaSha2_128sPublicKey, SlhDsaSha2_128sSecretKey, SlhDsaSha2_128sSignature,
+    slh_dsa::Sha2_128s, SignatureAlgorithm::SlhDsaSha2_128s, n: 16, signature: 7856
+);
+slh_dsa_scheme!(
+    /// SLH-DSA-SHA2-128f (NIST security category 1, fast signing).
+    SlhDsaSha2_128f, SlhDsaSha2_128fPublicKey, SlhDsaSha2_128fSecretKey, SlhDsaSha2_128fSignature,
+    slh_dsa::Sha2_128f, SignatureAlgorithm::SlhDsaSha2_128f, n: 16, signature: 17088
+);
+slh_dsa_scheme!(
+    /// SLH-DSA-SHA2-192s (NIST security category 3, small signatures).
+    SlhDsaSha2_192s, SlhDsaSha2_192sPublicKey, SlhDsaSha2_192sSecretKey, SlhDsaSha2_192sSignature,
+    slh_dsa::Sha2_192s, SignatureAlgorithm::SlhDsaSha2_192s, n: 24, signature: 16224
+);
+slh_dsa_scheme!(
+    /// SLH-DSA-SHA2-192f (NIST security category 3, fast signing).
+    SlhDsaSha2_192f, SlhDsaSha2_192fPublicKey, SlhDsaSha2_192fSecretKey, SlhDsaSha2_192fSignature,
+    slh_dsa::Sha2_192f, SignatureAlgorithm::SlhDsaSha2_192f, n: 24, signature: 35664
+);
+slh_dsa_scheme!(
+    /// SLH-DSA-SHA2-256s (NIST security category 5, small signatures).
+    SlhDsaSha2_256s, SlhDsaSha2_256sPublicKey, SlhDsaSha2_256sSecretKey, SlhDsaSha2_256sSignature,
+    slh_dsa::Sha2_256s, SignatureAlgorithm::SlhDsaSha2_256s, n: 32, signature: 29792
+);
+slh_dsa_scheme!(
+    /// SLH-DSA-SHA2-256f (NIST security category 5, fast signing).
+    SlhDsaSha2_256f, SlhDsaSha2_256fPublicKey, SlhDsaSha2_256fSecretKey, SlhDsaSha2_256fSignature,
+    slh_dsa::Sha2_256f, SignatureAlgorithm::SlhDsaSha2_256f, n: 32, signature: 49856
+);
+slh_dsa_scheme!(
+    /// SLH-DSA-SHAKE-128s (NIST security category 1, small signatures).
+    SlhDsaShake128s, SlhDsaShake128sPublicKey, SlhDsaShake128sSecretKey, SlhDsaShake128sSignature,
+    slh_dsa::Shake128s, SignatureAlgorithm::SlhDsaShake128s, n: 16, signature: 7856
+);
+slh_dsa_scheme!(
+    /// SLH-DSA-SHAKE-128f (NIST security category 1, fast signing).
+    SlhDsaShake128f, SlhDsaShake128fPublicKey, SlhDsaShake128fSecretKey, SlhDsaShake128fSignature,
+    slh_dsa::Shake128f, SignatureAlgorithm::SlhDsaShake128f, n: 16, signature: 17088
+);
+slh_dsa_scheme!(
+    /// SLH-DSA-SHAKE-192s (NIST security category 3, small signatures).
+    SlhDsaShake192s, SlhDsaShake192sPublicKey, SlhDsaShake192sSecretKey, SlhDsaShake192sSignature,
+    slh_dsa::Shake192s, SignatureAlgorithm::SlhDsaShake192s, n: 24, signature: 16224
+);
+slh_dsa_scheme!(
+    /// SLH-DSA-SHAKE-192f (NIST security category 3, fast signing).
+    SlhDsaShake192f, SlhDsaShake192fPublicKey, SlhDsaShake192fSecretKey, SlhDsaShake192fSignature,
+    slh_dsa::Shake192f, SignatureAlgorithm::SlhDsaShake192f, n: 24, signature: 35664
+);
+slh_dsa_scheme!(
+    /// SLH-DSA-SHAKE-256s (NIST security category 5, small signatures).
+    SlhDsaShake256s, SlhDsaShake256sPublicKey, SlhDsaShake256sSecretKey, SlhDsaShake256sSignature,
+    slh_dsa::Shake256s, SignatureAlgorithm::SlhDsaShake256s, n: 32, signature: 29792
+);
+slh_dsa_scheme!(
+    /// SLH-DSA-SHAKE-256f (NIST security category 5, fast signing).
+    SlhDsaShake256f, SlhDsaShake256fPublicKey, SlhDsaShake256fSecretKey, SlhDsaShake256fSignature,
+    slh_dsa::Shake256f, SignatureAlgorithm::SlhDsaShake256f, n: 32, signature: 49856
+);
@@ -0,0 +1,295 @@
+//! Deciding which peer identity keys to trust.
+//!
+//! A valid handshake signature only shows that the peer holds the secret
+//! key for the `sig_pk` it sent; an attacker can send their own key just as
+//! well. A [`TrustStore`] decides whether that key belongs to the peer the
+//! application meant to reach, and [`PQSession`](crate::PQSession) consults
+//! it on every handshake once one is configured:
+//!
+//! - [`PinnedKeys`]: a fixed set of known keys, optionally per peer name.
+//! - [`TofuStore`]: trust on first use, recording the first key seen for
+//!   each peer name in a file and refusing a different key afterwards.
+//! - [`TrustAnchors`]: the peer's certificate chain must lead to a
+//!   configured root (see [`cert`](crate::cert)) and name the peer.
+//!   [`AnySubject`] accepts any subject, for applications that authorize
+//!   peers by certificate themselves.
+//! - [`KnownIdentities`](crate::identity::KnownIdentities): the handshake
+//!   key must be a device key certified by a known master identity (see
+//!   [`identity`](crate::identity)).
+//!
+//! An unknown identity fails with [`TrustError::UnknownIdentity`] and a key
+//! that differs from the one on record with [`TrustError::IdentityChanged`],
+//! surfaced as [`PQError::UnknownIdentity`](crate::protocol::PQError::UnknownIdentity)
+//! and [`PQError::IdentityChanged`](crate::protocol::PQError::IdentityChanged).
+//!
+//! Keys are compared by [`fingerprint`]: SHA-256 over the algorithm id and
+//! the key bytes, so the same bytes under another scheme do not match.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use crate::cert::{Certificate, CertificateError, TrustAnchors};
+use crate::identity::{DeviceCertificate, IdentityError};
+use crate::sig::{SignatureAlgorithm, Verifier};
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum TrustError {
+    #[error("Peer identity is not known to the trust store")]
+    UnknownIdentity,
+    #[error("Peer identity key differs from the one on record")]
+    IdentityChanged,
+    #[error("No peer name to look the identity up by")]
+    MissingPeerName,
+    #[error("Certificate error: {0}")]
+    Certificate(#[from] CertificateError),
+    #[error("Device certificate error: {0}")]
+    Device(#[from] IdentityError),
+    #[error("Trust store storage error: {0}")]
+    Storage(String),
+}
+
+/// What a handshake tells the trust store about the peer.
+pub struct PeerIdentity<'a, S: Verifier> {
+    /// The name the application expects the peer to have, if it set one
+    /// with [`PQSession::with_expected_peer`](crate::PQSession::with_expected_peer)
+    pub name: Option<&'a str>,
+    pub algorithm: SignatureAlgorithm,
+    /// The key that signed the handshake
+    pub public_key: &'a S::PublicKey,
+    /// The certificate chain sent with the handshake, leaf first
+    pub certificates: &'a [Certificate<S>],
+    /// The device certificate sent with the handshake, if any
+    pub device: Option<&'a DeviceCertificate>,
+}
+
+impl<S: Verifier> PeerIdentity<'_, S> {
+    pub fn fingerprint(&self) -> [u8; 32] {
+        fingerprint(self.algorithm, self.public_key.as_ref())
+    }
+}
+
+/// What a trust store's decision rested on.
+pub enum TrustedPeer<S: Verifier> {
+    /// The key itself is known
+    Key,
+    /// A certificate chain validated up to a trust anchor; holds the leaf
+    Certificate(Certificate<S>),
+    /// A device certificate from a known identity
+    Device(DeviceCertificate),
+}
+
+/// Decides whether a handshake's identity key is trusted.
+pub trait TrustStore<S: Verifier>: Send {
+    /// Accepts or refuses `peer`, whose handshake signature has already
+    /// been verified.
+    fn verify_peer(&mut self, peer: &PeerIdentity<'_, S>) -> Result<TrustedPeer<S>, TrustError>;
+}
+
+/// A store shared by several sessions, e.g. every session of a server.
+impl<S: Verifier, T: TrustStore<S>> TrustStore<S> for Arc<Mutex<T>> {
+    fn verify_peer(&mut self, peer: &PeerIdentity<'_, S>) -> Result<TrustedPeer<S>, TrustError> {
+        let mut store = self.lock().map_err(|_| TrustError::Storage("trust store lock poisoned".into()))?;
+        store.verify_peer(peer)
+    }
+}
+
+/// SHA-256 over the big-endian algorithm id and the public key.
+pub fn fingerprint(algorithm: SignatureAlgorithm, public_key: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(algorithm.id().to_be_bytes());
+    hasher.update(public_key);
+    hasher.finalize().into()
+}
+
+/// A fixed set of trusted keys. Keys pinned under a name are only accepted
+/// for that peer name; keys pinned without one are accepted for any peer.
+#[derive(Debug, Clone, Default)]
+pub struct PinnedKeys {
+    named: HashMap<String, [u8; 32]>,
+    any: Vec<[u8; 32]>,
+}
+
+impl PinnedKeys {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pins `public_key` as the only key accepted for `name`.
+    pub fn pin<V: Verifier>(mut self, name: impl Into<String>, scheme: &V, public_key: &V::PublicKey) -> Self {
+        self.named.insert(name.into(), fingerprint(scheme.algorithm(), public_key.as_ref()));
+        self
+    }
+
+    /// Pins `public_key` for any peer name.
+    pub fn pin_any<V: Verifier>(mut self, scheme: &V, public_key: &V::PublicKey) -> Self {
+        self.any.push(fingerprint(scheme.algorithm(), public_key.as_ref()));
+        self
+    }
+}
+
+impl<S: Verifier> TrustStore<S> for PinnedKeys {
+    fn verify_peer(&mut self, peer: &PeerIdentity<'_, S>) -> Result<TrustedPeer<S>, TrustError> {
+        let fingerprint = peer.fingerprint();
+        if let Some(pinned) = peer.name.and_then(|name| self.named.get(name)) {
+            return if *pinned == fingerprint { Ok(TrustedPeer::Key) } else { Err(TrustError::IdentityChanged) };
+        }
+        if self.any.contains(&fingerprint) {
+            return Ok(TrustedPeer::Key);
+        }
+        Err(TrustError::UnknownIdentity)
+    }
+}
+
+/// Trust on first use, persisted to a file.
+///
+/// The first key seen for a peer name is recorded and trusted from then
+/// on; a later handshake for that name with another key fails with
+/// [`TrustError::IdentityChanged`]. Sessions using this store must set the
+/// peer name with [`PQSession::with_expected_peer`](crate::PQSession::with_expected_peer).
+///
+/// The file has one `name fingerprint` line per peer, the fingerprint in
+/// lowercase hex; blank lines and lines starting with `#` are ignored. It is
+/// rewritten through a temporary file and a rename whenever a peer is
+/// added.
+#[derive(Debug)]
+pub struct TofuStore {
+    path: PathBuf,
+    known: HashMap<String, [u8; 32]>,
+}
+
+impl TofuStore {
+    /// Loads the store at `path`, starting empty if the file does not exist.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e),
+        };
+
+        let mut known = HashMap::new();
+        for (index, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = || io::Error::new(io::ErrorKind::InvalidData, format!("{}:{}: malformed entry", path.display(), index + 1));
+            let (name, hex) = line.split_once(char::is_whitespace).ok_or_else(invalid)?;
+            let fingerprint = decode_hex(hex.trim()).ok_or_else(invalid)?;
+            known.insert(name.to_string(), fingerprint);
+        }
+        Ok(TofuStore { path, known })
+    }
+
+    /// The recorded fingerprint for `name`.
+    pub fn get(&self, name: &str) -> Option<[u8; 32]> {
+        self.known.get(name).copied()
+    }
+
+    /// Forgets `name`, e.g. after a legitimate key rotation, so its next key
+    /// is trusted on first use again.
+    pub fn forget(&mut self, name: &str) -> io::Result<()> {
+        if self.known.contains_key(name) {
+            let mut known = self.known.clone();
+            known.remove(name);
+            self.commit(known)?;
+        }
+        Ok(())
+    }
+
+    /// Writes `known` to disk and only then adopts it, so the store never
+    /// acts on a decision that was not recorded.
+    fn commit(&mut self, known: HashMap<String, [u8; 32]>) -> io::Result<()> {
+        let mut names: Vec<&String> = known.keys().collect();
+        names.sort();
+
+        let tmp = self.path.with_extension("tmp");
+        let mut file = fs::File::create(&tmp)?;
+        writeln!(file, "# pq-core trusted peers: name SHA-256 fingerprint")?;
+        for name in names {
+            writeln!(file, "{} {}", name, encode_hex(&known[name]))?;
+        }
+        file.sync_all()?;
+        fs::rename(&tmp, &self.path)?;
+        self.known = known;
+        Ok(())
+    }
+}
+
+impl<S: Verifier> TrustStore<S> for TofuStore {
+    fn verify_peer(&mut self, peer: &PeerIdentity<'_, S>) -> Result<TrustedPeer<S>, TrustError> {
+        let name = peer.name.ok_or(TrustError::MissingPeerName)?;
+        let fingerprint = peer.fingerprint();
+        match self.known.get(name) {
+            Some(known) if *known == fingerprint => Ok(TrustedPeer::Key),
+            Some(_) => Err(TrustError::IdentityChanged),
+            None => {
+                if name.is_empty() || name.contains(char::is_whitespace) {
+                    return Err(TrustError::Storage(format!("peer name {name:?} cannot be recorded")));
+                }
+                let mut known = self.known.clone();
+                known.insert(name.to_string(), fingerprint);
+                self.commit(known).map_err(|e| TrustError::Storage(e.to_string()))?;
+                Ok(TrustedPeer::Key)
+            }
+        }
+    }
+}
+
+/// Certificate anchors: the peer's chain must validate up to one of the
+/// anchors and certify the handshake key, and the leaf's subject must be
+/// the peer name. Without a peer name every certificate under the anchors
+/// would vouch for every peer, so one is required; see [`AnySubject`] to
+/// opt out.
+impl<S: Verifier> TrustStore<S> for TrustAnchors<S> {
+    fn verify_peer(&mut self, peer: &PeerIdentity<'_, S>) -> Result<TrustedPeer<S>, TrustError> {
+        let name = peer.name.ok_or(TrustError::MissingPeerName)?;
+        let leaf = validate_leaf(self, peer)?;
+        if name != leaf.subject() {
+            return Err(CertificateError::SubjectMismatch(leaf.subject().to_string()).into());
+        }
+        Ok(TrustedPeer::Certificate(leaf))
+    }
+}
+
+/// Certificate anchors that accept any subject: every certificate holder
+/// under the anchors is trusted as any peer, and the application must
+/// check [`PQSession::peer_certificate`](crate::PQSession::peer_certificate)
+/// itself. Meant for servers that authenticate clients by certificate and
+/// authorize them by subject afterwards.
+pub struct AnySubject<S: Verifier>(pub TrustAnchors<S>);
+
+impl<S: Verifier> TrustStore<S> for AnySubject<S> {
+    fn verify_peer(&mut self, peer: &PeerIdentity<'_, S>) -> Result<TrustedPeer<S>, TrustError> {
+        Ok(TrustedPeer::Certificate(validate_leaf(&self.0, peer)?))
+    }
+}
+
+/// Validates the peer's chain and checks that its leaf certifies the
+/// handshake key.
+fn validate_leaf<S: Verifier>(anchors: &TrustAnchors<S>, peer: &PeerIdentity<'_, S>) -> Result<Certificate<S>, TrustError> {
+    let leaf = anchors.validate_now(peer.certificates)?;
+    if leaf.public_key().as_ref() != peer.public_key.as_ref() {
+        return Err(CertificateError::KeyMismatch.into());
+    }
+    Ok(leaf.clone())
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn decode_hex(hex: &str) -> Option<[u8; 32]> {
+    if hex.len() != 64 || !hex.is_ascii() {
+        return None;
+    }
+    let mut out = [0u8; 32];
+    for (i, byte) in out.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).ok()?;
+    }
+    Some(out)
+}
@@ -0,0 +1,529 @@
+//! X.509 v3 certificates and PKCS#10 certificate requests for
+//! post-quantum keys.
+//!
+//! Subject keys and signatures are labelled with the NIST OIDs of
+//! [`encoding::oid`](crate::encoding::oid), following the IETF LAMPS
+//! certificate drafts, so certificates issued here verify with OpenSSL 3.5
+//! and certificates issued by OpenSSL parse and verify here. Signatures are
+//! ML-DSA or SLH-DSA in their pure form with an empty context string;
+//! certified keys can be ML-DSA and SLH-DSA signing keys or ML-KEM
+//! encapsulation keys. Dilithium, Falcon and the composites have no OID
+//! (see [`encoding`](crate::encoding)) and are refused with
+//! [`X509Error::UnsupportedAlgorithm`]; use [`cert`](crate::cert) for them.
+//!
+//! [`CertificateAuthority`] is a minimal CA: it holds a CA certificate and
+//! its secret key, signs [`CertificateRequest`]s into end-entity
+//! certificates, and issues certificates for ML-KEM keys, which cannot sign
+//! a request of their own. Everything else (profiles, revocation,
+//! extensions beyond basic constraints, key usage and key identifiers) is
+//! left to the PKI tooling the certificates feed into.
+//!
+//! ```
+//! use pq_core::cert::Validity;
+//! use pq_core::sig::ml_dsa::{MlDsa44, MlDsa65};
+//! use pq_core::x509::{CertificateAuthority, CertificateRequest};
+//!
+//! let ca_scheme = MlDsa65::new();
+//! let (ca_pk, ca_sk) = ca_scheme.keygen().unwrap();
+//! let ca = CertificateAuthority::self_signed(ca_scheme, "CN=Example Root CA", Validity::for_days(3650), &ca_pk, ca_sk)
+//!     .unwrap();
+//!
+//! let scheme = MlDsa44::new();
+//! let (pk, sk) = scheme.keygen().unwrap();
+//! let csr = CertificateRequest::new(&scheme, "CN=node-1,O=Example", &pk, &sk).unwrap();
+//! let certificate = ca.sign_request(&csr, Validity::for_days(90)).unwrap();
+//!
+//! certificate.verify_issued_by(ca.certificate()).unwrap();
+//! assert_eq!(certificate.subject(), "CN=node-1,O=Example");
+//! assert!(certificate.to_pem().unwrap().starts_with("-----BEGIN CERTIFICATE-----"));
+//! ```
+
+use std::str::FromStr;
+use std::time::Duration;
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use x509_cert::der::asn1::{BitString, GeneralizedTime, OctetString, SetOfVec, UtcTime};
+use x509_cert::der::oid::AssociatedOid;
+use x509_cert::der::pem::LineEnding;
+use x509_cert::der::{DateTime, Decode, DecodePem, Encode, EncodePem};
+use x509_cert::ext::pkix::{AuthorityKeyIdentifier, BasicConstraints, KeyUsage, KeyUsages, SubjectKeyIdentifier};
+use x509_cert::ext::Extension;
+use x509_cert::name::Name;
+use x509_cert::request::{CertReq, CertReqInfo, Version as RequestVersion};
+use x509_cert::serial_number::SerialNumber;
+use x509_cert::spki::{AlgorithmIdentifierOwned, ObjectIdentifier, SubjectPublicKeyInfoOwned};
+use x509_cert::time::Time;
+use x509_cert::{TbsCertificate, Version};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use crate::cert::Validity;
+use crate::encoding::{signature_public_key_der, EncodingError};
+use crate::kem::kem::PublicKey;
+use crate::kem::KemAlgorithm;
+use crate::sig::ml_dsa::{MlDsa44, MlDsa65, MlDsa87};
+use crate::sig::slh_dsa::{
+    SlhDsaSha2_128f, SlhDsaSha2_128s, SlhDsaSha2_192f, SlhDsaSha2_192s, SlhDsaSha2_256f, SlhDsaSha2_256s,
+    SlhDsaShake128f, SlhDsaShake128s, SlhDsaShake192f, SlhDsaShake192s, SlhDsaShake256f, SlhDsaShake256s,
+};
+use crate::sig::{SignatureAlgorithm, SignatureError, Signer, Verifier};
+
+/// Bytes of random serial number; RFC 5280 allows up to 20.
+const SERIAL_BYTES: usize = 16;
+/// Key identifiers are the leftmost 160 bits of SHA-256 of the key
+/// (RFC 7093, method 1).
+const KEY_ID_BYTES: usize = 20;
+/// RFC 5280 encodes dates up to 2049 as UTCTime and later ones as
+/// GeneralizedTime.
+const UTC_TIME_END_YEAR: u16 = 2050;
+
+#[derive(Debug, Error)]
+pub enum X509Error {
+    #[error("Malformed DER or PEM: {0}")]
+    Der(#[from] x509_cert::der::Error),
+    #[error("Invalid distinguished name: {0}")]
+    InvalidName(String),
+    #[error("{0} has no registered OID")]
+    UnsupportedAlgorithm(String),
+    #[error("Unknown algorithm OID: {0}")]
+    UnknownOid(ObjectIdentifier),
+    #[error("Signature does not verify")]
+    BadSignature,
+    #[error("Certificate was not issued by this issuer")]
+    IssuerMismatch,
+    #[error("Certificate is not a CA certificate")]
+    NotCa,
+    #[error("Not a KEM public key")]
+    NotKemKey,
+    #[error("Signature error: {0}")]
+    Signature(#[from] SignatureError),
+    #[error("Key encoding error: {0}")]
+    Encoding(#[from] EncodingError),
+}
+
+/// The algorithm of a certified or requested public key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyAlgorithm {
+    Signature(SignatureAlgorithm),
+    Kem(KemAlgorithm),
+}
+
+impl KeyAlgorithm {
+    pub fn from_oid(oid: ObjectIdentifier) -> Option<Self> {
+        SignatureAlgorithm::from_oid(oid)
+            .map(KeyAlgorithm::Signature)
+            .or_else(|| KemAlgorithm::from_oid(oid).map(KeyAlgorithm::Kem))
+    }
+}
+
+/// A PKCS#10 certificate signing request, signed by the key it requests a
+/// certificate for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CertificateRequest {
+    inner: CertReq,
+}
+
+impl CertificateRequest {
+    /// Builds and signs a request for `pk`, with `subject` in RFC 4514 form
+    /// (e.g. `"CN=node-1,O=Example"`).
+    pub fn new<S: Signer>(scheme: &S, subject: &str, pk: &S::PublicKey, sk: &S::SecretKey) -> Result<Self, X509Error> {
+        let algorithm = algorithm_identifier(scheme.algorithm())?;
+        let info = CertReqInfo {
+            version: RequestVersion::V1,
+            subject: parse_name(subject)?,
+            public_key: SubjectPublicKeyInfoOwned::from_der(&signature_public_key_der(scheme.algorithm(), pk.as_ref())?)?,
+            attributes: SetOfVec::new(),
+        };
+        let signature = scheme.sign(&info.to_der()?, sk)?;
+
+        Ok(CertificateRequest {
+            inner: CertReq {
+                info,
+                algorithm,
+                signature: BitString::from_bytes(signature.as_ref())?,
+            },
+        })
+    }
+
+    pub fn from_der(der: &[u8]) -> Result<Self, X509Error> {
+        Ok(CertificateRequest { inner: CertReq::from_der(der)? })
+    }
+
+    pub fn from_pem(pem: &str) -> Result<Self, X509Error> {
+        Ok(CertificateRequest { inner: CertReq::from_pem(pem)? })
+    }
+
+    pub fn to_der(&self) -> Result<Vec<u8>, X509Error> {
+        Ok(self.inner.to_der()?)
+    }
+
+    pub fn to_pem(&self) -> Result<String, X509Error> {
+        Ok(self.inner.to_pem(LineEnding::LF)?)
+    }
+
+    /// The requested subject, in RFC 4514 form.
+    pub fn subject(&self) -> String {
+        self.inner.info.subject.to_string()
+    }
+
+    pub fn public_key_algorithm(&self) -> Result<KeyAlgorithm, X509Error> {
+        key_algorithm(&self.inner.info.public_key)
+    }
+
+    pub fn public_key(&self) -> &[u8] {
+        self.inner.info.public_key.subject_public_key.raw_bytes()
+    }
+
+    /// Checks the proof of possession: the request is signed by the key it
+    /// names, with that key's own algorithm.
+    pub fn verify(&self) -> Result<(), X509Error> {
+        let algorithm = signature_algorithm(&self.inner.algorithm)?;
+        if self.public_key_algorithm()? != KeyAlgorithm::Signature(algorithm) {
+            return Err(X509Error::BadSignature);
+        }
+        let message = self.inner.info.to_der()?;
+        verify_with(algorithm, &message, self.inner.signature.raw_bytes(), self.public_key())
+    }
+
+    pub fn inner(&self) -> &CertReq {
+        &self.inner
+    }
+}
+
+/// An X.509 v3 certificate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct X509Certificate {
+    inner: x509_cert::Certificate,
+}
+
+impl X509Certificate {
+    pub fn from_der(der: &[u8]) -> Result<Self, X509Error> {
+        Ok(X509Certificate { inner: x509_cert::Certificate::from_der(der)? })
+    }
+
+    pub fn from_pem(pem: &str) -> Result<Self, X509Error> {
+        Ok(X509Certificate { inner: x509_cert::Certificate::from_pem(pem)? })
+    }
+
+    pub fn to_der(&self) -> Result<Vec<u8>, X509Error> {
+        Ok(self.inner.to_der()?)
+    }
+
+    pub fn to_pem(&self) -> Result<String, X509Error> {
+        Ok(self.inner.to_pem(LineEnding::LF)?)
+    }
+
+    /// The subject, in RFC 4514 form.
+    pub fn subject(&self) -> String {
+        self.inner.tbs_certificate.subject.to_string()
+    }
+
+    /// The issuer, in RFC 4514 form.
+    pub fn issuer(&self) -> String {
+        self.inner.tbs_certificate.issuer.to_string()
+    }
+
+    pub fn serial_number(&self) -> &[u8] {
+        self.inner.tbs_certificate.serial_number.as_bytes()
+    }
+
+    pub fn validity(&self) -> Validity {
+        let validity = &self.inner.tbs_certificate.validity;
+        Validity::new(
+            validity.not_before.to_unix_duration().as_secs(),
+            validity.not_after.to_unix_duration().as_secs(),
+        )
+    }
+
+    pub fn public_key_algorithm(&self) -> Result<KeyAlgorithm, X509Error> {
+        key_algorithm(&self.inner.tbs_certificate.subject_public_key_info)
+    }
+
+    /// The raw certified key.
+    pub fn public_key(&self) -> &[u8] {
+        self.inner.tbs_certificate.subject_public_key_info.subject_public_key.raw_bytes()
+    }
+
+    /// The certified key as a KEM public key, tagged with its algorithm.
+    pub fn kem_public_key(&self) -> Result<PublicKey, X509Error> {
+        if !matches!(self.public_key_algorithm()?, KeyAlgorithm::Kem(_)) {
+            return Err(X509Error::NotKemKey);
+        }
+        let der = self.inner.tbs_certificate.subject_public_key_info.to_der()?;
+        Ok(PublicKey::from_public_key_der(&der)?)
+    }
+
+    /// The algorithm of the issuer's signature.
+    pub fn signature_algorithm(&self) -> Result<SignatureAlgorithm, X509Error> {
+        signature_algorithm(&self.inner.signature_algorithm)
+    }
+
+    /// Whether the basic constraints extension marks this as a CA.
+    pub fn is_ca(&self) -> bool {
+        self.extensions()
+            .find(|ext| ext.extn_id == BasicConstraints::OID)
+            .and_then(|ext| BasicConstraints::from_der(ext.extn_value.as_bytes()).ok())
+            .is_some_and(|constraints| constraints.ca)
+    }
+
+    /// Whether this certificate may issue others: a CA whose key usage, if
+    /// present, includes keyCertSign.
+    pub fn can_issue(&self) -> bool {
+        let key_usage = self
+            .extensions()
+            .find(|ext| ext.extn_id == KeyUsage::OID)
+            .map(|ext| KeyUsage::from_der(ext.extn_value.as_bytes()));
+        self.is_ca()
+            && match key_usage {
+                Some(Ok(usage)) => usage.key_cert_sign(),
+                Some(Err(_)) => false,
+                None => true,
+            }
+    }
+
+    /// Checks that `issuer` is a CA allowed to sign certificates, is named
+    /// as this certificate's issuer and that its key signed this
+    /// certificate.
+    pub fn verify_issued_by(&self, issuer: &X509Certificate) -> Result<(), X509Error> {
+        if !issuer.can_issue() {
+            return Err(X509Error::NotCa);
+        }
+        let tbs = &self.inner.tbs_certificate;
+        if tbs.issuer != issuer.inner.tbs_certificate.subject || tbs.signature != self.inner.signature_algorithm {
+            return Err(X509Error::IssuerMismatch);
+        }
+        let algorithm = self.signature_algorithm()?;
+        if issuer.public_key_algorithm()? != KeyAlgorithm::Signature(algorithm) {
+            return Err(X509Error::IssuerMismatch);
+        }
+        verify_with(algorithm, &tbs.to_der()?, self.inner.signature.raw_bytes(), issuer.public_key())
+    }
+
+    pub fn inner(&self) -> &x509_cert::Certificate {
+        &self.inner
+    }
+
+    fn extensions(&self) -> impl Iterator<Item = &Extension> {
+        self.inner.tbs_certificate.extensions.iter().flatten()
+    }
+}
+
+/// A certificate authority: a CA certificate and the secret key of scheme
+/// `S` that signs with it.
+pub struct CertificateAuthority<S: Signer> {
+    scheme: S,
+    certificate: X509Certificate,
+    secret_key: S::SecretKey,
+}
+
+impl<S: Signer> CertificateAuthority<S> {
+    /// Wraps an existing CA certificate and its secret key.
+    pub fn new(scheme: S, certificate: X509Certificate, secret_key: S::SecretKey) -> Result<Self, X509Error> {
+        if !certificate.can_issue() {
+            return Err(X509Error::NotCa);
+        }
+        if certificate.public_key_algorithm()? != KeyAlgorithm::Signature(scheme.algorithm()) {
+            return Err(X509Error::IssuerMismatch);
+        }
+        Ok(CertificateAuthority { scheme, certificate, secret_key })
+    }
+
+    /// Creates a root CA with a self-signed certificate for `pk`.
+    pub fn self_signed(
+        scheme: S,
+        subject: &str,
+        validity: Validity,
+        pk: &S::PublicKey,
+        secret_key: S::SecretKey,
+    ) -> Result<Self, X509Error> {
+        let name = parse_name(subject)?;
+        let algorithm = algorithm_identifier(scheme.algorithm())?;
+        let key_id = key_identifier(pk.as_ref())?;
+        let extensions = vec![
+            extension(&BasicConstraints { ca: true, path_len_constraint: None }, true)?,
+            extension(&KeyUsage(KeyUsages::KeyCertSign | KeyUsages::CRLSign), true)?,
+            extension(&SubjectKeyIdentifier(key_id.clone()), false)?,
+        ];
+        let tbs = tbs_certificate(
+            name.clone(),
+            name,
+            validity,
+            SubjectPublicKeyInfoOwned::from_der(&signature_public_key_der(scheme.algorithm(), pk.as_ref())?)?,
+            algorithm,
+            extensions,
+        )?;
+        let certificate = sign_tbs(&scheme, tbs, &secret_key)?;
+        Ok(CertificateAuthority { scheme, certificate, secret_key })
+    }
+
+    pub fn certificate(&self) -> &X509Certificate {
+        &self.certificate
+    }
+
+    /// Verifies `request` and issues an end-entity certificate for its key,
+    /// valid for signing.
+    pub fn sign_request(&self, request: &CertificateRequest, validity: Validity) -> Result<X509Certificate, X509Error> {
+        request.verify()?;
+        let info = &request.inner.info;
+        self.issue(
+            info.subject.clone(),
+            info.public_key.clone(),
+            validity,
+            KeyUsage(KeyUsages::DigitalSignature.into()),
+        )
+    }
+
+    /// Issues an end-entity certificate for an ML-KEM public key, which
+    /// cannot sign a request itself. `pk` must be tagged with its algorithm.
+    pub fn issue_kem_certificate(&self, subject: &str, pk: &PublicKey, validity: Validity) -> Result<X509Certificate, X509Error> {
+        let der = pk.to_public_key_der()?;
+        self.issue(
+            parse_name(subject)?,
+            SubjectPublicKeyInfoOwned::from_der(&der)?,
+            validity,
+            KeyUsage(KeyUsages::KeyEncipherment.into()),
+        )
+    }
+
+    fn issue(
+        &self,
+        subject: Name,
+        public_key: SubjectPublicKeyInfoOwned,
+        validity: Validity,
+        key_usage: KeyUsage,
+    ) -> Result<X509Certificate, X509Error> {
+        let issuer_key_id = key_identifier(self.certificate.public_key())?;
+        let extensions = vec![
+            extension(&BasicConstraints { ca: false, path_len_constraint: None }, true)?,
+            extension(&key_usage, true)?,
+            extension(&SubjectKeyIdentifier(key_identifier(public_key.subject_public_key.raw_bytes())?), false)?,
+            extension(
+                &AuthorityKeyIdentifier { key_identifier: Some(issuer_key_id), ..Default::default() },
+                false,
+            )?,
+        ];
+        let tbs = tbs_certificate(
+            subject,
+            self.certificate.inner.tbs_certificate.subject.clone(),
+            validity,
+            public_key,
+            algorithm_identifier(self.scheme.algorithm())?,
+            extensions,
+        )?;
+        sign_tbs(&self.scheme, tbs, &self.secret_key)
+    }
+}
+
+fn tbs_certificate(
+    subject: Name,
+    issuer: Name,
+    validity: Validity,
+    subject_public_key_info: SubjectPublicKeyInfoOwned,
+    signature: AlgorithmIdentifierOwned,
+    extensions: Vec<Extension>,
+) -> Result<TbsCertificate, X509Error> {
+    let mut serial = [0u8; SERIAL_BYTES];
+    OsRng.fill_bytes(&mut serial);
+    // Positive, and without a leading zero byte to strip
+    serial[0] = (serial[0] & 0x7f) | 0x40;
+
+    Ok(TbsCertificate {
+        version: Version::V3,
+        serial_number: SerialNumber::new(&serial)?,
+        signature,
+        issuer,
+        validity: x509_cert::time::Validity {
+            not_before: x509_time(validity.not_before)?,
+            not_after: x509_time(validity.not_after)?,
+        },
+        subject,
+        subject_public_key_info,
+        issuer_unique_id: None,
+        subject_unique_id: None,
+        extensions: Some(extensions),
+    })
+}
+
+fn sign_tbs<S: Signer>(scheme: &S, tbs: TbsCertificate, sk: &S::SecretKey) -> Result<X509Certificate, X509Error> {
+    let signature = scheme.sign(&tbs.to_der()?, sk)?;
+    Ok(X509Certificate {
+        inner: x509_cert::Certificate {
+            signature_algorithm: tbs.signature.clone(),
+            tbs_certificate: tbs,
+            signature: BitString::from_bytes(signature.as_ref())?,
+        },
+    })
+}
+
+/// The AlgorithmIdentifier for `algorithm`. The LAMPS drafts require the
+/// parameters to be absent.
+fn algorithm_identifier(algorithm: SignatureAlgorithm) -> Result<AlgorithmIdentifierOwned, X509Error> {
+    let oid = algorithm.oid().ok_or_else(|| X509Error::UnsupportedAlgorithm(algorithm.to_string()))?;
+    Ok(AlgorithmIdentifierOwned { oid, parameters: None })
+}
+
+fn signature_algorithm(identifier: &AlgorithmIdentifierOwned) -> Result<SignatureAlgorithm, X509Error> {
+    SignatureAlgorithm::from_oid(identifier.oid).ok_or(X509Error::UnknownOid(identifier.oid))
+}
+
+fn key_algorithm(spki: &SubjectPublicKeyInfoOwned) -> Result<KeyAlgorithm, X509Error> {
+    KeyAlgorithm::from_oid(spki.algorithm.oid).ok_or(X509Error::UnknownOid(spki.algorithm.oid))
+}
+
+fn parse_name(name: &str) -> Result<Name, X509Error> {
+    Name::from_str(name).map_err(|_| X509Error::InvalidName(name.to_string()))
+}
+
+fn key_identifier(key: &[u8]) -> Result<OctetString, X509Error> {
+    Ok(OctetString::new(&Sha256::digest(key)[..KEY_ID_BYTES])?)
+}
+
+fn extension<T: AssociatedOid + Encode>(value: &T, critical: bool) -> Result<Extension, X509Error> {
+    Ok(Extension {
+        extn_id: T::OID,
+        critical,
+        extn_value: OctetString::new(value.to_der()?)?,
+    })
+}
+
+fn x509_time(unix_seconds: u64) -> Result<Time, X509Error> {
+    let date_time = DateTime::from_unix_duration(Duration::from_secs(unix_seconds))?;
+    if date_time.year() < UTC_TIME_END_YEAR {
+        Ok(UtcTime::from_date_time(date_time)?.into())
+    } else {
+        Ok(GeneralizedTime::from_date_time(date_time).into())
+    }
+}
+
+/// Verifies a signature made with a key of `algorithm`, for every
+/// algorithm with an OID.
+fn verify_with(algorithm: SignatureAlgorithm, message: &[u8], signature: &[u8], public_key: &[u8]) -> Result<(), X509Error> {
+    fn check<V: Verifier>(scheme: V, message: &[u8], signature: &[u8], public_key: &[u8]) -> Result<bool, SignatureError> {
+        let pk = scheme.public_key_from_bytes(public_key)?;
+        let signature = scheme.signature_from_bytes(signature)?;
+        scheme.verify(message, &signature, &pk)
+    }
+
+    let valid = match algorithm {
+        SignatureAlgorithm::MlDsa44 => check(MlDsa44::new(), message, signature, public_key),
+        SignatureAlgorithm::MlDsa65 => check(MlDsa65::new(), message, signature, public_key),
+        SignatureAlgorithm::MlDsa87 => check(MlDsa87::new(), message, signature, public_key),
+        SignatureAlgorithm::SlhDsaSha2_128s => check(SlhDsaSha2_128s::new(), message, signature, public_key),
+        SignatureAlgorithm::SlhDsaSha2_128f => check(SlhDsaSha2_128f::new(), message, signature, public_key),
+        SignatureAlgorithm::SlhDsaSha2_192s => check(SlhDsaSha2_192s::new(), message, signature, public_key),
+        SignatureAlgorithm::SlhDsaSha2_192f => check(SlhDsaSha2_192f::new(), message, signature, public_key),
+        SignatureAlgorithm::SlhDsaSha2_256s => check(SlhDsaSha2_256s::new(), message, signature, public_key),
+        SignatureAlgorithm::SlhDsaSha2_256f => check(SlhDsaSha2_256f::new(), message, signature, public_key),
+        SignatureAlgorithm::SlhDsaShake128s => check(SlhDsaShake128s::new(), message, signature, public_key),
+        SignatureAlgorithm::SlhDsaShake128f => check(SlhDsaShake128f::new(), message, signature, public_key),
+        SignatureAlgorithm::SlhDsaShake192s => check(SlhDsaShake192s::new(), message, signature, public_key),
+        SignatureAlgorithm::SlhDsaShake192f => check(SlhDsaShake192f::new(), message, signature, public_key),
+        SignatureAlgorithm::SlhDsaShake256s => check(SlhDsaShake256s::new(), message, signature, public_key),
+        SignatureAlgorithm::SlhDsaShake256f => check(SlhDsaShake256f::new(), message, signature, public_key),
+        other => return Err(X509Error::UnsupportedAlgorithm(other.to_string())),
+    }?;
+
+    if !valid {
+        return Err(X509Error::BadSignature);
+    }
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use pq_core::kem::kem::{Ciphertext, KemError, PublicKey};
+use pq_core::kem::{registry, Kem, KemAlgorithm, Kyber512, MlKem768};
+use pq_core::sig::dilithium::{Dilithium, DilithiumError, DilithiumSignature};
+use pq_core::sig::ml_dsa::MlDsa44;
+use pq_core::sig::Verifier;
+
+#[test]
+fn test_batch_encaps_decaps_roundtrip() {
+    let kem = Kyber512::new();
+    let (pk, sk) = kem.keygen().unwrap();
+    let pks = vec![pk; 64];
+
+    let encapsulated: Vec<_> = kem.batch_encaps(&pks).into_iter().map(Result::unwrap).collect();
+    let cts: Vec<Ciphertext> = encapsulated.iter().map(|(ct, _)| ct.clone()).collect();
+    let secrets = kem.batch_decaps(&cts, &sk);
+
+    assert_eq!(secrets.len(), 64);
+    for ((_, expected), got) in encapsulated.iter().zip(secrets) {
+        assert_eq!(&got.unwrap(), expected);
+    }
+}
+
+#[test]
+fn test_batch_encaps_keeps_order_and_per_item_errors() {
+    let kem = MlKem768::new();
+    let keys: Vec<_> = (0..8).map(|_| kem.keygen().unwrap()).collect();
+    let mut pks: Vec<PublicKey> = keys.iter().map(|(pk, _)| pk.clone()).collect();
+    pks.insert(3, PublicKey::from_vec(vec![0u8; 10]));
+
+    let results = kem.batch_encaps(&pks);
+    assert_eq!(results.len(), 9);
+    assert!(matches!(results[3], Err(KemError::InvalidKeySize)));
+
+    // Every other entry decapsulates under the key at the same position
+    let mut keys = keys.iter();
+    for (i, result) in results.iter().enumerate() {
+        if i == 3 {
+            continue;
+        }
+        let (_, sk) = keys.next().unwrap();
+        let (ct, ss) = result.as_ref().unwrap();
+        assert_eq!(&kem.decaps(ct, sk).unwrap(), ss);
+    }
+}
+
+#[test]
+fn test_batch_decaps_per_item_errors() {
+    let kem = registry::new_kem(KemAlgorithm::X25519MlKem512);
+    let (pk, sk) = kem.keygen().unwrap();
+    let (ct, ss) = kem.encaps(&pk).unwrap();
+    let cts = vec![ct.clone(), Ciphertext::from_vec(vec![1, 2, 3]), ct];
+
+    let results = kem.batch_decaps(&cts, &sk);
+    assert_eq!(results[0].as_ref().unwrap(), &ss);
+    assert!(matches!(results[1], Err(KemError::InvalidCiphertextSize)));
+    assert_eq!(results[2].as_ref().unwrap(), &ss);
+    assert!(kem.batch_decaps(&[], &sk).is_empty());
+}
+
+#[test]
+fn test_dilithium_batch_verify() {
+    let sig = Dilithium::new();
+    let (pk, sk) = sig.keygen().unwrap();
+    let (other_pk, _) = sig.keygen().unwrap();
+    let messages: Vec<Vec<u8>> = (0..16u8).map(|i| vec![i; 100]).collect();
+    let signatures: Vec<DilithiumSignature> = messages.iter().map(|m| sig.sign(m, &sk).unwrap()).collect();
+
+    let mut items: Vec<_> = messages.iter().zip(&signatures).map(|(m, s)| (m.as_slice(), s, &pk)).collect();
+    items[5].0 = b"forged";
+    items[9].2 = &other_pk;
+
+    let results = sig.batch_verify(&items);
+    assert_eq!(results.len(), 16);
+    for (i, result) in results.into_iter().enumerate() {
+        let expected: Result<bool, DilithiumError> = Ok(i != 5 && i != 9);
+        assert_eq!(result, expected, "item {i}");
+    }
+}
+
+#[test]
+fn test_generic_verify_batch_reports_failures() {
+    let scheme = MlDsa44::new();
+    let (pk, sk) = scheme.keygen().unwrap();
+    let messages: Vec<Vec<u8>> = (0..12u8).map(|i| vec![i; 64]).collect();
+    let signatures: Vec<_> = messages.iter().map(|m| scheme.sign(m, &sk).unwrap()).collect();
+
+    let mut items: Vec<_> = messages.iter().zip(&signatures).map(|(m, s)| (m.as_slice(), s, &pk)).collect();
+    let report = scheme.verify_batch(&items);
+    assert_eq!(report.len(), 12);
+    assert!(report.all_valid());
+    assert!(report.failed().is_empty());
+
+    items[3].0 = b"forged";
+    items[7].1 = &signatures[8];
+    let report = scheme.verify_batch(&items);
+    assert!(!report.all_valid());
+    assert_eq!(report.failed(), vec![3, 7]);
+    assert!(report.is_valid(0) && !report.is_valid(3) && !report.is_valid(12));
+
+    assert!(scheme.verify_batch(&[]).all_valid());
+}
@@ -0,0 +1,297 @@
+use pq_core::cert::{
+    Certificate, CertificateError, CertificateParams, KeyUsage, TrustAnchors, Validity, MAX_CHAIN_LENGTH,
+};
+use pq_core::kem::{Kem, MlKem768};
+use pq_core::protocol::{PQError, PQSession};
+use pq_core::sig::dilithium::{Dilithium, DilithiumSecretKey, Dilithium3};
+use pq_core::sig::ml_dsa::MlDsa44;
+use pq_core::sig::SignatureAlgorithm;
+
+const NOW: u64 = 1_800_000_000;
+
+fn validity() -> Validity {
+    Validity::new(NOW - 1000, NOW + 1000)
+}
+
+/// A root, an intermediate CA and a leaf, all valid over `validity`, with
+/// the secret keys of the leaf and the intermediate.
+struct Pki {
+    root: Certificate,
+    intermediate: Certificate,
+    intermediate_sk: DilithiumSecretKey,
+    leaf: Certificate,
+    leaf_sk: DilithiumSecretKey,
+}
+
+fn pki(validity: Validity) -> Pki {
+    let scheme = Dilithium::new();
+    let (root_pk, root_sk) = scheme.keygen().unwrap();
+    let root = Certificate::self_signed(
+        &scheme,
+        CertificateParams::new("Root CA", validity, KeyUsage::CERT_SIGN),
+        &root_pk,
+        &root_sk,
+    )
+    .unwrap();
+
+    let (intermediate_pk, intermediate_sk) = scheme.keygen().unwrap();
+    let intermediate = Certificate::issue(
+        &scheme,
+        CertificateParams::new("Issuing CA", validity, KeyUsage::CERT_SIGN),
+        &intermediate_pk,
+        &root,
+        &root_sk,
+    )
+    .unwrap();
+
+    let (leaf_pk, leaf_sk) = scheme.keygen().unwrap();
+    let leaf = Certificate::issue(
+        &scheme,
+        CertificateParams::new("node-1", validity, KeyUsage::DIGITAL_SIGNATURE),
+        &leaf_pk,
+        &intermediate,
+        &intermediate_sk,
+    )
+    .unwrap();
+
+    Pki { root, intermediate, intermediate_sk, leaf, leaf_sk }
+}
+
+#[test]
+fn test_encoding_round_trip() {
+    let Pki { leaf, .. } = pki(validity());
+    let bytes = leaf.to_bytes();
+    assert_eq!(&bytes[..4], b"PQCT");
+
+    let decoded = Certificate::from_bytes(&Dilithium::new(), &bytes).unwrap();
+    assert_eq!(decoded, leaf);
+    assert_eq!(decoded.subject(), "node-1");
+    assert_eq!(decoded.issuer(), "Issuing CA");
+    assert_eq!(decoded.serial(), leaf.serial());
+    assert_eq!(decoded.validity(), validity());
+    assert_eq!(decoded.key_usage(), KeyUsage::DIGITAL_SIGNATURE);
+    assert_eq!(decoded.algorithm(), SignatureAlgorithm::Dilithium2);
+    assert_eq!(decoded.signature_algorithm(), SignatureAlgorithm::Dilithium2);
+    assert_eq!(decoded.public_key().as_bytes(), leaf.public_key().as_bytes());
+}
+
+#[test]
+fn test_decoding_errors() {
+    let Pki { leaf, .. } = pki(validity());
+    let bytes = leaf.to_bytes();
+    let scheme = Dilithium::new();
+
+    let mut bad = bytes.clone();
+    bad[0] = b'X';
+    assert_eq!(Certificate::from_bytes(&scheme, &bad).err(), Some(CertificateError::BadMagic));
+    bad = bytes.clone();
+    bad[4] = 2;
+    assert_eq!(Certificate::from_bytes(&scheme, &bad).err(), Some(CertificateError::UnsupportedVersion(2)));
+    assert_eq!(Certificate::from_bytes(&scheme, &bytes[..100]).err(), Some(CertificateError::Truncated));
+
+    let mut trailing = bytes.clone();
+    trailing.push(0);
+    assert_eq!(Certificate::from_bytes(&scheme, &trailing).err(), Some(CertificateError::Malformed));
+
+    // Undefined key usage bits
+    bad = bytes.clone();
+    bad[33] = 0x80;
+    assert_eq!(Certificate::from_bytes(&scheme, &bad).err(), Some(CertificateError::Malformed));
+
+    // A Dilithium certificate is not an ML-DSA certificate
+    assert_eq!(
+        Certificate::from_bytes(&MlDsa44::new(), &bytes).err(),
+        Some(CertificateError::AlgorithmMismatch)
+    );
+    assert_eq!(
+        Certificate::from_bytes(&Dilithium3::new(), &bytes).err(),
+        Some(CertificateError::AlgorithmMismatch)
+    );
+}
+
+#[test]
+fn test_chain_validation() {
+    let Pki { root, intermediate, leaf, .. } = pki(validity());
+    let anchors = TrustAnchors::new(Dilithium::new()).with_anchor(root.clone());
+
+    // With or without the root
+    let chain = [leaf.clone(), intermediate.clone()];
+    assert_eq!(anchors.validate(&chain, NOW).unwrap(), &leaf);
+    let full = [leaf.clone(), intermediate.clone(), root.clone()];
+    assert_eq!(anchors.validate(&full, NOW).unwrap(), &leaf);
+
+    // Missing the intermediate
+    assert_eq!(anchors.validate(std::slice::from_ref(&leaf), NOW).err(), Some(CertificateError::UntrustedChain));
+    assert_eq!(anchors.validate(&[], NOW).err(), Some(CertificateError::EmptyChain));
+
+    // Another root with the same name is not trusted
+    let other = pki(validity());
+    let impostor = TrustAnchors::new(Dilithium::new()).with_anchor(other.root);
+    assert_eq!(impostor.validate(&chain, NOW).err(), Some(CertificateError::UntrustedChain));
+
+    // Out of order
+    assert_eq!(
+        anchors.validate(&[leaf.clone(), root.clone(), intermediate.clone()], NOW).err(),
+        Some(CertificateError::IssuerMismatch("node-1".into()))
+    );
+
+    // The intermediate anchored directly
+    let pinned = TrustAnchors::new(Dilithium::new()).with_anchor(intermediate.clone());
+    assert!(pinned.validate(std::slice::from_ref(&leaf), NOW).is_ok());
+
+    let long = vec![leaf.clone(); MAX_CHAIN_LENGTH + 1];
+    assert_eq!(anchors.validate(&long, NOW).err(), Some(CertificateError::ChainTooLong));
+}
+
+#[test]
+fn test_validity_and_key_usage() {
+    let Pki { root, intermediate, intermediate_sk, .. } = pki(validity());
+    let anchors = TrustAnchors::new(Dilithium::new()).with_anchor(root.clone());
+
+    let expired = pki(Validity::new(NOW - 1000, NOW - 1));
+    let expired_anchors = TrustAnchors::new(Dilithium::new()).with_anchor(expired.root.clone());
+    assert_eq!(
+        expired_anchors.validate(&[expired.leaf.clone(), expired.intermediate.clone()], NOW).err(),
+        Some(CertificateError::OutsideValidity("node-1".into()))
+    );
+    assert!(expired_anchors.validate(&[expired.leaf, expired.intermediate], NOW - 10).is_ok());
+
+    // Every certificate up to the anchor must be valid, not just the leaf
+    let scheme = Dilithium::new();
+    let (long_lived_pk, _) = scheme.keygen().unwrap();
+    let long_lived = Certificate::issue(
+        &scheme,
+        CertificateParams::new("node-9", Validity::new(NOW, NOW + 5000), KeyUsage::DIGITAL_SIGNATURE),
+        &long_lived_pk,
+        &intermediate,
+        &intermediate_sk,
+    )
+    .unwrap();
+    assert_eq!(
+        anchors.validate(&[long_lived, intermediate.clone()], NOW + 1001).err(),
+        Some(CertificateError::OutsideValidity("Issuing CA".into()))
+    );
+
+    // A signing-only key cannot issue, and a CA key cannot sign handshakes
+    let (pk, sk) = scheme.keygen().unwrap();
+    let signing_only = Certificate::issue(
+        &scheme,
+        CertificateParams::new("node-2", validity(), KeyUsage::DIGITAL_SIGNATURE),
+        &pk,
+        &intermediate,
+        &intermediate_sk,
+    )
+    .unwrap();
+    let (other_pk, _) = scheme.keygen().unwrap();
+    assert_eq!(
+        Certificate::issue(
+            &scheme,
+            CertificateParams::new("node-3", validity(), KeyUsage::DIGITAL_SIGNATURE),
+            &other_pk,
+            &signing_only,
+            &sk,
+        )
+        .err(),
+        Some(CertificateError::KeyUsage("node-2".into()))
+    );
+    assert_eq!(
+        anchors.validate(std::slice::from_ref(&intermediate), NOW).err(),
+        Some(CertificateError::KeyUsage("Issuing CA".into()))
+    );
+
+    let both = KeyUsage::DIGITAL_SIGNATURE | KeyUsage::CERT_SIGN;
+    assert!(both.contains(KeyUsage::CERT_SIGN) && !KeyUsage::CERT_SIGN.contains(both));
+}
+
+#[test]
+fn test_tampered_certificate_fails() {
+    let Pki { root, intermediate, leaf, .. } = pki(validity());
+    let anchors = TrustAnchors::new(Dilithium::new()).with_anchor(root);
+
+    // Extend the validity window by editing the not-after field
+    let mut bytes = leaf.to_bytes();
+    bytes[31] ^= 0x01;
+    let tampered = Certificate::from_bytes(&Dilithium::new(), &bytes).unwrap();
+    assert_eq!(
+        anchors.validate(&[tampered, intermediate], NOW).err(),
+        Some(CertificateError::BadSignature)
+    );
+}
+
+#[test]
+fn test_handshake_with_certificate_chains() {
+    let server = pki(Validity::for_days(1));
+    let client = pki(Validity::for_days(1));
+
+    let mut alice = PQSession::with_signer(Box::new(MlKem768::new()), Dilithium::new())
+        .with_certificate_chain(vec![client.leaf.clone(), client.intermediate.clone()], client.leaf_sk)
+        .unwrap()
+        .with_trust_anchors(TrustAnchors::new(Dilithium::new()).with_anchor(server.root.clone()))
+        .with_expected_peer("node-1");
+    let mut bob = PQSession::with_signer(Box::new(MlKem768::new()), Dilithium::new())
+        .with_certificate_chain(vec![server.leaf.clone(), server.intermediate.clone()], server.leaf_sk)
+        .unwrap()
+        .with_trust_anchors(TrustAnchors::new(Dilithium::new()).with_anchor(client.root.clone()))
+        .with_expected_peer("node-1");
+
+    let hello = alice.initiate_handshake().unwrap();
+    assert_eq!(hello.certificates.len(), 2);
+    let reply = bob.process_handshake(hello).unwrap();
+    alice.complete_handshake(reply).unwrap();
+
+    assert_eq!(alice.peer_certificate(), Some(&server.leaf));
+    assert_eq!(bob.peer_certificate(), Some(&client.leaf));
+    let ct = alice.encrypt(b"certified");
+    assert_eq!(bob.decrypt(&ct).unwrap(), b"certified");
+
+    // A peer without a chain, or with a chain for someone else's key, is refused
+    let anchors = || TrustAnchors::new(Dilithium::new()).with_anchor(server.root.clone());
+    let mut carol = PQSession::new().with_trust_anchors(anchors()).with_expected_peer("node-1");
+    let bare = PQSession::new().initiate_handshake().unwrap();
+    assert_eq!(
+        carol.process_handshake(bare).err(),
+        Some(PQError::Certificate(CertificateError::EmptyChain))
+    );
+
+    // The chain is signed with the handshake, so it cannot be swapped in
+    let mut stolen = PQSession::new().initiate_handshake().unwrap();
+    stolen.certificates = vec![server.leaf.clone(), server.intermediate.clone()];
+    assert_eq!(carol.process_handshake(stolen).err(), Some(PQError::InvalidSignature));
+
+    let (own_pk, own_sk) = Dilithium::new().keygen().unwrap();
+    let (_, unused_sk) = Dilithium::new().keygen().unwrap();
+    let mut mallory = PQSession::new()
+        .with_certificate_chain(vec![server.leaf.clone(), server.intermediate.clone()], unused_sk)
+        .unwrap()
+        .with_identity(own_pk, own_sk);
+    assert_eq!(
+        carol.process_handshake(mallory.initiate_handshake().unwrap()).err(),
+        Some(PQError::Certificate(CertificateError::KeyMismatch))
+    );
+    assert!(carol.peer_certificate().is_none());
+
+    let (_, sk) = Dilithium::new().keygen().unwrap();
+    assert!(matches!(
+        PQSession::new().with_certificate_chain(vec![], sk),
+        Err(PQError::Certificate(CertificateError::EmptyChain))
+    ));
+}
+
+#[test]
+fn test_anchors_reject_swapped_ciphertext() {
+    let server = pki(Validity::for_days(1));
+    let mut genuine = PQSession::with_signer(Box::new(MlKem768::new()), Dilithium::new())
+        .with_certificate_chain(vec![server.leaf.clone(), server.intermediate.clone()], server.leaf_sk)
+        .unwrap();
+    let mut client = PQSession::with_signer(Box::new(MlKem768::new()), Dilithium::new())
+        .with_trust_anchors(TrustAnchors::new(Dilithium::new()).with_anchor(server.root.clone()))
+        .with_expected_peer("node-1");
+
+    // The genuine server's chain and signature, with an attacker's ciphertext
+    let hello = client.initiate_handshake().unwrap();
+    let (forged, _) = MlKem768::new().encaps(&hello.kem_pk).unwrap();
+    let mut reply = genuine.process_handshake(hello).unwrap();
+    reply.ciphertext = forged;
+    assert_eq!(client.complete_handshake(reply).err(), Some(PQError::InvalidSignature));
+    assert!(client.peer_certificate().is_none());
+}
@@ -0,0 +1,106 @@
+use pq_core::kem::X25519MlKem768;
+use pq_core::protocol::{PQError, PQSession};
+use pq_core::sig::composite::{
+    CompositePublicKey, CompositeSignature, Dilithium2Ed25519, Dilithium3Ed25519, MAX_CONTEXT_BYTES,
+};
+use pq_core::sig::dilithium::{Dilithium, DilithiumPublicKey, DilithiumSignature};
+use pq_core::sig::{SignatureAlgorithm, SignatureError, Signer, Verifier};
+
+#[test]
+fn test_sign_and_verify() {
+    let scheme = Dilithium2Ed25519::new();
+    let (pk, sk) = scheme.keygen().unwrap();
+    assert_eq!(pk.as_bytes().len(), Dilithium::public_key_bytes() + 32);
+    assert_eq!(sk.as_bytes().len(), Dilithium::secret_key_bytes() + 32);
+
+    let signature = scheme.sign(b"message", &sk).unwrap();
+    assert_eq!(signature.as_bytes().len(), Dilithium::signature_bytes() + 64);
+    assert!(scheme.verify(b"message", &signature, &pk).unwrap());
+    assert!(!scheme.verify(b"messagf", &signature, &pk).unwrap());
+
+    let signature = scheme.sign_with_context(b"message", b"handshake", &sk).unwrap();
+    assert!(scheme.verify_with_context(b"message", b"handshake", &signature, &pk).unwrap());
+    assert!(!scheme.verify(b"message", &signature, &pk).unwrap());
+
+    let long = [0u8; MAX_CONTEXT_BYTES + 1];
+    assert_eq!(scheme.sign_with_context(b"message", &long, &sk).err(), Some(SignatureError::ContextTooLong));
+
+    // Secret keys survive an encode/decode round trip
+    let decoded = scheme.secret_key_from_bytes(sk.as_bytes()).unwrap();
+    assert!(scheme.verify(b"again", &scheme.sign(b"again", &decoded).unwrap(), &pk).unwrap());
+
+    // Debug output never shows key material
+    assert_eq!(format!("{sk:?}"), format!("CompositeSecretKey(<{} bytes redacted>)", sk.as_bytes().len()));
+}
+
+#[test]
+fn test_both_components_must_verify() {
+    let scheme = Dilithium2Ed25519::new();
+    let (pk, sk) = scheme.keygen().unwrap();
+    let signature = scheme.sign(b"message", &sk).unwrap();
+    let split = Dilithium::signature_bytes();
+
+    // Break the Dilithium half, then the Ed25519 half
+    for index in [10, split + 10] {
+        let mut bytes = signature.as_bytes().to_vec();
+        bytes[index] ^= 1;
+        let tampered = CompositeSignature::from_bytes(&bytes).unwrap();
+        assert!(!scheme.verify(b"message", &tampered, &pk).unwrap(), "byte {index}");
+    }
+
+    // Mixing the halves of two valid signatures over the same message fails
+    let (other_pk, other_sk) = scheme.keygen().unwrap();
+    let other = scheme.sign(b"message", &other_sk).unwrap();
+    let mixed = [&signature.as_bytes()[..split], &other.as_bytes()[split..]].concat();
+    let mixed = CompositeSignature::from_bytes(&mixed).unwrap();
+    assert!(!scheme.verify(b"message", &mixed, &pk).unwrap());
+    assert!(!scheme.verify(b"message", &mixed, &other_pk).unwrap());
+
+    // Neither is a key made of one half of each
+    let mixed_pk = [&pk.as_bytes()[..Dilithium::public_key_bytes()], &other_pk.as_bytes()[Dilithium::public_key_bytes()..]].concat();
+    let mixed_pk = CompositePublicKey::from_bytes(&mixed_pk).unwrap();
+    assert!(!scheme.verify(b"message", &signature, &mixed_pk).unwrap());
+}
+
+#[test]
+fn test_component_signature_is_not_standalone() {
+    let scheme = Dilithium2Ed25519::new();
+    let (pk, sk) = scheme.keygen().unwrap();
+    let signature = scheme.sign(b"message", &sk).unwrap();
+
+    // The Dilithium half signs the composite message representative, not
+    // the message itself
+    let pq_pk = DilithiumPublicKey::from_bytes(&pk.as_bytes()[..Dilithium::public_key_bytes()]).unwrap();
+    let pq_sig = DilithiumSignature::from_bytes(&signature.as_bytes()[..Dilithium::signature_bytes()]).unwrap();
+    assert!(!Dilithium::new().verify(b"message", &pq_sig, &pq_pk).unwrap());
+}
+
+#[test]
+fn test_sizes_and_ids() {
+    let d3 = Dilithium3Ed25519::new();
+    assert_eq!(Verifier::algorithm(&d3), SignatureAlgorithm::Dilithium3Ed25519);
+    assert_eq!(Verifier::signature_bytes(&d3), Dilithium3Ed25519::signature_bytes());
+    assert_eq!(Signer::secret_key_bytes(&d3), Dilithium3Ed25519::secret_key_bytes());
+
+    let (pk2, _) = Dilithium2Ed25519::new().keygen().unwrap();
+    assert_eq!(d3.public_key_from_bytes(pk2.as_bytes()).err(), Some(SignatureError::InvalidPublicKey));
+
+    assert_eq!(SignatureAlgorithm::Dilithium2Ed25519.id(), 0x0402);
+    assert_eq!(
+        "dilithium2-ed25519".parse::<SignatureAlgorithm>().unwrap(),
+        SignatureAlgorithm::Dilithium2Ed25519
+    );
+    assert_eq!(SignatureAlgorithm::Dilithium2Ed25519.oid(), None);
+}
+
+#[test]
+fn test_handshake_with_forged_ed25519_half_is_refused() {
+    let mut alice = PQSession::with_signer(Box::new(X25519MlKem768::new()), Dilithium2Ed25519::new());
+    let mut carol = PQSession::with_signer(Box::new(X25519MlKem768::new()), Dilithium2Ed25519::new());
+    let mut hello = alice.initiate_handshake().unwrap();
+    let mut bytes = hello.signature.as_bytes().to_vec();
+    let last = bytes.len() - 1;
+    bytes[last] ^= 1;
+    hello.signature = CompositeSignature::from_bytes(&bytes).unwrap();
+    assert!(matches!(carol.process_handshake(hello), Err(PQError::InvalidSignature)));
+}
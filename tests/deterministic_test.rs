@@ -0,0 +1,95 @@
+use pq_core::kem::{Kem, Kyber512, Kyber768, Kyber1024, MlKem512, MlKem768, MlKem1024, X25519Kyber768, X25519MlKem768};
+use pq_core::kem::kem::KemError;
+use pq_core::sig::dilithium::{Dilithium, DilithiumError};
+
+fn seeded_roundtrip(kem: &dyn Kem) {
+    let seed_len = kem.keygen_seed_bytes().expect("seeded keygen supported");
+    let coins_len = kem.encaps_coins_bytes().expect("deterministic encaps supported");
+    let seed: Vec<u8> = (0..seed_len as u8).collect();
+    let coins = vec![0x5a; coins_len];
+
+    let (pk1, sk1) = kem.keygen_from_seed(&seed).unwrap();
+    let (pk2, sk2) = kem.keygen_from_seed(&seed).unwrap();
+    assert_eq!(pk1, pk2);
+    assert_eq!(sk1, sk2);
+    assert_eq!(pk1.algorithm(), Some(kem.algorithm()));
+
+    let (ct1, ss1) = kem.encaps_deterministic(&pk1, &coins).unwrap();
+    let (ct2, ss2) = kem.encaps_deterministic(&pk1, &coins).unwrap();
+    assert_eq!(ct1, ct2);
+    assert_eq!(ss1, ss2);
+    assert_eq!(kem.decaps(&ct1, &sk1).unwrap(), ss1);
+
+    assert!(matches!(kem.keygen_from_seed(&seed[1..]), Err(KemError::InvalidSeedSize)));
+    assert!(matches!(kem.encaps_deterministic(&pk1, &coins[1..]), Err(KemError::InvalidSeedSize)));
+}
+
+#[test]
+fn test_dilithium_keygen_from_seed_is_reproducible() {
+    let sig = Dilithium::new();
+    let seed = [7u8; 32];
+
+    let (pk1, sk1) = sig.keygen_from_seed(&seed).unwrap();
+    let (pk2, sk2) = sig.keygen_from_seed(&seed).unwrap();
+    assert_eq!(pk1.as_bytes(), pk2.as_bytes());
+    assert_eq!(sk1.as_bytes(), sk2.as_bytes());
+    assert_eq!(pk1.as_bytes().len(), Dilithium::public_key_bytes());
+    assert_eq!(sk1.as_bytes().len(), Dilithium::secret_key_bytes());
+
+    let (pk3, _) = sig.keygen_from_seed(&[8u8; 32]).unwrap();
+    assert_ne!(pk1.as_bytes(), pk3.as_bytes());
+}
+
+#[test]
+fn test_dilithium_seeded_keys_sign_and_verify() {
+    let sig = Dilithium::new();
+    let (pk, sk) = sig.keygen_from_seed(&[42u8; 32]).unwrap();
+
+    let message = b"seeded signing key";
+    let signature = sig.sign(message, &sk).unwrap();
+    assert!(sig.verify(message, &signature, &pk).unwrap());
+    assert!(!sig.verify(b"another message", &signature, &pk).unwrap());
+
+    // Signing is deterministic, so a recovered key reproduces signatures
+    let (_, recovered) = sig.keygen_from_seed(&[42u8; 32]).unwrap();
+    assert_eq!(signature.as_bytes(), sig.sign(message, &recovered).unwrap().as_bytes());
+}
+
+#[test]
+fn test_dilithium_rejects_bad_seed_length() {
+    assert!(matches!(Dilithium::new().keygen_from_seed(&[0u8; 16]), Err(DilithiumError::InvalidSeed)));
+}
+
+#[test]
+fn test_mlkem_seeded_keygen_and_encaps() {
+    seeded_roundtrip(&MlKem512::new());
+    seeded_roundtrip(&MlKem768::new());
+    seeded_roundtrip(&MlKem1024::new());
+}
+
+#[test]
+fn test_hybrid_seeded_keygen_and_encaps() {
+    seeded_roundtrip(&X25519MlKem768::new());
+    assert_eq!(X25519MlKem768::new().keygen_seed_bytes(), Some(32 + 64));
+}
+
+#[test]
+fn test_pqcrypto_kyber_seeded_keygen_and_encaps() {
+    seeded_roundtrip(&Kyber512::new());
+    seeded_roundtrip(&Kyber768::new());
+    seeded_roundtrip(&Kyber1024::new());
+    seeded_roundtrip(&X25519Kyber768::new());
+    assert_eq!(Kyber512::new().keygen_seed_bytes(), Some(64));
+
+    // Seeded keys and ciphertexts interoperate with the C backend
+    let kem = Kyber768::new();
+    let (pk, sk) = kem.keygen_from_seed(&[3u8; 64]).unwrap();
+    let (ct, ss) = kem.encaps(&pk).unwrap();
+    assert_eq!(kem.decaps(&ct, &sk).unwrap(), ss);
+
+    let (other_pk, _) = MlKem768::new().keygen().unwrap();
+    assert!(matches!(
+        kem.encaps_deterministic(&other_pk, &[0u8; 32]),
+        Err(KemError::AlgorithmMismatch { .. })
+    ));
+}
@@ -0,0 +1,91 @@
+use pq_core::kem::MlKem768;
+use pq_core::protocol::PQSession;
+use pq_core::sig::falcon::{Falcon512, Falcon512PublicKey, Falcon512Signature, Falcon1024};
+use pq_core::sig::ml_dsa::MlDsa44;
+use pq_core::sig::{SignatureAlgorithm, SignatureError, Signer, Verifier};
+
+#[test]
+fn test_sign_and_verify() {
+    let scheme = Falcon512::new();
+    let (pk, sk) = scheme.keygen().unwrap();
+    assert_eq!(pk.as_bytes().len(), Falcon512::public_key_bytes());
+    assert_eq!(sk.as_bytes().len(), Falcon512::secret_key_bytes());
+
+    for i in 0..10u8 {
+        let message = [i; 64];
+        let signature = scheme.sign(&message, &sk).unwrap();
+        assert!(signature.as_bytes().len() <= Falcon512::signature_bytes());
+        assert!(scheme.verify(&message, &signature, &pk).unwrap());
+        assert!(!scheme.verify(&message[1..], &signature, &pk).unwrap());
+
+        let decoded = scheme.signature_from_bytes(signature.as_bytes()).unwrap();
+        assert!(scheme.verify(&message, &decoded, &pk).unwrap());
+    }
+}
+
+#[test]
+fn test_tampered_signature_fails() {
+    let scheme = Falcon1024::new();
+    let (pk, sk) = scheme.keygen().unwrap();
+    let signature = scheme.sign(b"message", &sk).unwrap();
+
+    let mut bytes = signature.as_bytes().to_vec();
+    let last = bytes.len() - 1;
+    bytes[last] ^= 1;
+    let tampered = scheme.signature_from_bytes(&bytes).unwrap();
+    assert!(!scheme.verify(b"message", &tampered, &pk).unwrap());
+}
+
+#[test]
+fn test_signature_decoding() {
+    let (_, sk) = Falcon1024::new().keygen().unwrap();
+    let signature = Falcon1024::new().sign(b"message", &sk).unwrap();
+
+    // A Falcon-1024 signature carries its own header byte
+    assert_eq!(
+        Falcon512Signature::from_bytes(&signature.as_bytes()[..600]).err(),
+        Some(SignatureError::InvalidSignature)
+    );
+
+    let mut bytes = vec![0x39; Falcon512::signature_bytes()];
+    assert!(Falcon512Signature::from_bytes(&bytes).is_ok());
+    assert!(Falcon512Signature::from_bytes(&bytes[..41]).is_err());
+    bytes.push(0);
+    assert_eq!(Falcon512Signature::from_bytes(&bytes).err(), Some(SignatureError::InvalidSignature));
+
+    let (pk1024, _) = Falcon1024::new().keygen().unwrap();
+    assert_eq!(Falcon512PublicKey::from_bytes(pk1024.as_bytes()).err(), Some(SignatureError::InvalidPublicKey));
+}
+
+/// What a handshake spends on the identity key and its signature.
+fn identity_budget<S: Verifier>(scheme: &S) -> usize {
+    scheme.public_key_bytes() + scheme.signature_bytes()
+}
+
+#[test]
+fn test_sizes_and_ids() {
+    assert_eq!(identity_budget(&Falcon512::new()), 897 + 666);
+    assert_eq!(identity_budget(&Falcon1024::new()), 1793 + 1280);
+    assert!(identity_budget(&Falcon512::new()) < identity_budget(&MlDsa44::new()));
+    assert_eq!(Signer::secret_key_bytes(&Falcon512::new()), 1281);
+
+    assert_eq!(Verifier::algorithm(&Falcon512::new()), SignatureAlgorithm::Falcon512);
+    assert_eq!(SignatureAlgorithm::Falcon512.id(), 0x0301);
+    assert_eq!(SignatureAlgorithm::Falcon1024.id(), 0x0305);
+    assert_eq!("falcon-1024".parse::<SignatureAlgorithm>().unwrap(), SignatureAlgorithm::Falcon1024);
+    assert_eq!(SignatureAlgorithm::Falcon512.oid(), None);
+}
+
+#[test]
+fn test_secret_key_debug_is_redacted() {
+    let (_, sk) = Falcon512::new().keygen().unwrap();
+    assert_eq!(format!("{sk:?}"), "Falcon512SecretKey(<1281 bytes redacted>)");
+}
+
+#[test]
+fn test_handshake_signature_fits_falcon_budget() {
+    let mut alice = PQSession::with_signer(Box::new(MlKem768::new()), Falcon512::new());
+
+    let hello = alice.initiate_handshake().unwrap();
+    assert!(hello.signature.as_bytes().len() <= Verifier::signature_bytes(&Falcon512::new()));
+}
@@ -0,0 +1,143 @@
+use pq_core::hpke::{Hpke, HpkeAead, HpkeError, HpkeKem, Psk};
+use pq_core::kem::KemAlgorithm;
+
+const PSK: Psk<'static> = Psk { psk: &[0x5a; 32], id: b"psk-2024" };
+
+fn suites() -> [Hpke; 3] {
+    [
+        Hpke::new(HpkeKem::Pq(KemAlgorithm::Kyber512), HpkeAead::Aes256Gcm),
+        Hpke::new(HpkeKem::Pq(KemAlgorithm::X25519Kyber512), HpkeAead::Aes256Gcm),
+        Hpke::new(HpkeKem::Pq(KemAlgorithm::X25519MlKem768), HpkeAead::Aes128Gcm),
+    ]
+}
+
+fn roundtrip(hpke: &Hpke, psk: Option<Psk<'_>>, auth: bool) {
+    let (pk_r, sk_r) = hpke.generate_key_pair().unwrap();
+    let (pk_s, sk_s) = hpke.generate_key_pair().unwrap();
+    let (sk_s, pk_s) = if auth { (Some(&sk_s), Some(&pk_s)) } else { (None, None) };
+
+    let (enc, mut sender) = hpke.setup_sender(&pk_r, b"info", psk, sk_s).unwrap();
+    let mut recipient = hpke.setup_recipient(&enc, &sk_r, b"info", psk, pk_s).unwrap();
+
+    for i in 0..5u8 {
+        let ct = sender.seal(&[i], &[i; 40]).unwrap();
+        assert_eq!(recipient.open(&[i], &ct).unwrap(), [i; 40]);
+    }
+    assert_eq!(sender.sequence_number(), 5);
+    assert_eq!(
+        sender.export(b"context", 64).unwrap(),
+        recipient.export(b"context", 64).unwrap()
+    );
+    assert_ne!(sender.export(b"context", 32).unwrap(), sender.export(b"other", 32).unwrap());
+}
+
+#[test]
+fn test_base_and_psk_modes() {
+    for hpke in suites() {
+        roundtrip(&hpke, None, false);
+        roundtrip(&hpke, Some(PSK), false);
+    }
+}
+
+#[test]
+fn test_auth_modes_with_hybrid() {
+    let hpke = Hpke::new(HpkeKem::Pq(KemAlgorithm::X25519Kyber512), HpkeAead::Aes256Gcm);
+    roundtrip(&hpke, None, true);
+    roundtrip(&hpke, Some(PSK), true);
+
+    // The recipient must name the right sender
+    let (pk_r, sk_r) = hpke.generate_key_pair().unwrap();
+    let (_, sk_s) = hpke.generate_key_pair().unwrap();
+    let (other_pk, _) = hpke.generate_key_pair().unwrap();
+    let (enc, mut sender) = hpke.setup_sender(&pk_r, b"", None, Some(&sk_s)).unwrap();
+    let mut recipient = hpke.setup_recipient(&enc, &sk_r, b"", None, Some(&other_pk)).unwrap();
+    let ct = sender.seal(b"", b"hi").unwrap();
+    assert!(matches!(recipient.open(b"", &ct), Err(HpkeError::OpenError)));
+}
+
+#[test]
+fn test_auth_unsupported_for_pq_only_kem() {
+    let hpke = Hpke::new(HpkeKem::Pq(KemAlgorithm::Kyber512), HpkeAead::Aes256Gcm);
+    let (pk_r, _) = hpke.generate_key_pair().unwrap();
+    let (_, sk_s) = hpke.generate_key_pair().unwrap();
+    assert!(!hpke.kem().supports_auth());
+    assert!(matches!(
+        hpke.setup_sender(&pk_r, b"", None, Some(&sk_s)),
+        Err(HpkeError::AuthUnsupported(HpkeKem::Pq(KemAlgorithm::Kyber512)))
+    ));
+}
+
+#[test]
+fn test_mismatched_inputs_fail_to_open() {
+    for hpke in suites() {
+        let (pk_r, sk_r) = hpke.generate_key_pair().unwrap();
+        let (enc, mut sender) = hpke.setup_sender(&pk_r, b"info", Some(PSK), None).unwrap();
+        let ct = sender.seal(b"aad", b"payload").unwrap();
+
+        let wrong_psk = Psk { psk: &[1; 32], id: PSK.id };
+        for (info, psk) in [(&b"other"[..], Some(PSK)), (b"info", Some(wrong_psk)), (b"info", None)] {
+            let mut recipient = hpke.setup_recipient(&enc, &sk_r, info, psk, None).unwrap();
+            assert!(matches!(recipient.open(b"aad", &ct), Err(HpkeError::OpenError)));
+            // A failed open does not advance the sequence number
+            assert_eq!(recipient.sequence_number(), 0);
+        }
+
+        let mut recipient = hpke.setup_recipient(&enc, &sk_r, b"info", Some(PSK), None).unwrap();
+        assert!(matches!(recipient.open(b"other aad", &ct), Err(HpkeError::OpenError)));
+        assert_eq!(recipient.open(b"aad", &ct).unwrap(), b"payload");
+    }
+}
+
+#[test]
+fn test_out_of_order_messages_fail() {
+    let hpke = Hpke::new(HpkeKem::Pq(KemAlgorithm::Kyber512), HpkeAead::Aes256Gcm);
+    let (pk_r, sk_r) = hpke.generate_key_pair().unwrap();
+    let (enc, mut sender) = hpke.setup_sender(&pk_r, b"", None, None).unwrap();
+    let mut recipient = hpke.setup_recipient(&enc, &sk_r, b"", None, None).unwrap();
+
+    let first = sender.seal(b"", b"one").unwrap();
+    let second = sender.seal(b"", b"two").unwrap();
+    assert!(recipient.open(b"", &second).is_err());
+    assert_eq!(recipient.open(b"", &first).unwrap(), b"one");
+    assert_eq!(recipient.open(b"", &second).unwrap(), b"two");
+}
+
+#[test]
+fn test_export_only_and_invalid_psk() {
+    let hpke = Hpke::new(HpkeKem::Pq(KemAlgorithm::X25519Kyber512), HpkeAead::ExportOnly);
+    let (pk_r, sk_r) = hpke.generate_key_pair().unwrap();
+    let (enc, mut sender) = hpke.setup_sender(&pk_r, b"", None, None).unwrap();
+    let recipient = hpke.setup_recipient(&enc, &sk_r, b"", None, None).unwrap();
+    assert!(matches!(sender.seal(b"", b"x"), Err(HpkeError::ExportOnly)));
+    assert_eq!(sender.export(b"k", 16).unwrap(), recipient.export(b"k", 16).unwrap());
+    assert!(matches!(sender.export(b"k", 255 * 32 + 1), Err(HpkeError::ExportTooLong)));
+
+    let empty = Psk { psk: &[], id: b"id" };
+    assert!(matches!(hpke.setup_sender(&pk_r, b"", Some(empty), None), Err(HpkeError::InvalidPsk)));
+}
+
+#[test]
+fn test_kem_ids() {
+    assert_eq!(HpkeKem::X25519HkdfSha256.id(), 0x0020);
+    assert_eq!(HpkeKem::Pq(KemAlgorithm::MlKem768).id(), 0x0041);
+    assert_eq!(HpkeKem::Pq(KemAlgorithm::Kyber512).id(), 0xF001);
+    assert_eq!(HpkeKem::Pq(KemAlgorithm::X25519Kyber512).id(), 0xF201);
+    assert_eq!(HpkeKem::from_id(0x0042), Some(HpkeKem::Pq(KemAlgorithm::MlKem1024)));
+    assert_eq!(HpkeKem::from_id(0x1234), None);
+    assert_eq!(HpkeAead::from_id(0x0002), Some(HpkeAead::Aes256Gcm));
+}
+
+#[test]
+fn test_derive_key_pair() {
+    for hpke in suites() {
+        // Any length of input keying material, expanded to the KEM's seed
+        let (pk, sk) = hpke.derive_key_pair(&[7u8; 32]).unwrap();
+        let (pk2, sk2) = hpke.derive_key_pair(&[7u8; 32]).unwrap();
+        assert_eq!((&pk, &sk), (&pk2, &sk2));
+        assert_ne!(hpke.derive_key_pair(&[7u8; 33]).unwrap().0, pk);
+
+        let (enc, mut sender) = hpke.setup_sender(&pk, b"info", None, None).unwrap();
+        let mut recipient = hpke.setup_recipient(&enc, &sk, b"info", None, None).unwrap();
+        assert_eq!(recipient.open(b"", &sender.seal(b"", b"derived").unwrap()).unwrap(), b"derived");
+    }
+}
@@ -0,0 +1,226 @@
+use pq_core::cert::{CertificateError, Validity};
+use pq_core::identity::{DeviceCertificate, IdentityError, KnownIdentities, MasterKey, RevocationList};
+use pq_core::kem::kem::{PublicKey, SecretKey};
+use pq_core::kem::{Kem, KemAlgorithm, MlKem768};
+use pq_core::protocol::{PQError, PQSession};
+use pq_core::sig::dilithium::{Dilithium, DilithiumSecretKey};
+use pq_core::sig::ml_dsa::MlDsa44;
+use pq_core::sig::SignatureAlgorithm;
+use pq_core::trust::TrustStore;
+use pq_core::BidirectionalSession;
+use std::sync::{Arc, Mutex};
+
+const NOW: u64 = 1_800_000_000;
+
+/// A device of some master key: its certificate and secret keys.
+struct Device {
+    certificate: DeviceCertificate,
+    sk: DilithiumSecretKey,
+    kem_sk: SecretKey,
+}
+
+fn device(master: &MasterKey, name: &str, validity: Validity) -> Device {
+    let scheme = Dilithium::new();
+    let (pk, sk) = scheme.keygen().unwrap();
+    let (kem_pk, kem_sk) = MlKem768::new().keygen().unwrap();
+    let certificate = master.certify_device(name, &scheme, &pk, &kem_pk, validity).unwrap();
+    Device { certificate, sk, kem_sk }
+}
+
+impl Device {
+    fn session(&self) -> PQSession {
+        PQSession::new()
+            .with_device(self.certificate.clone(), self.sk.clone(), self.kem_sk.clone())
+            .unwrap()
+    }
+}
+
+/// Runs a handshake from `device` to a server trusting `store`.
+fn connect(device: &Device, store: impl TrustStore<Dilithium> + 'static) -> Result<PQSession, PQError> {
+    let mut client = device.session();
+    let mut server = PQSession::new().with_trust_store(store).with_expected_peer("alice");
+    let reply = server.process_handshake(client.initiate_handshake()?)?;
+    client.complete_handshake(reply)?;
+    Ok(server)
+}
+
+#[test]
+fn test_device_certificates() {
+    let master = MasterKey::generate().unwrap();
+    let identity = master.identity();
+    let phone = device(&master, "phone", Validity::new(NOW - 1000, NOW + 1000)).certificate;
+
+    let decoded = DeviceCertificate::from_bytes(&phone.to_bytes()).unwrap();
+    assert_eq!(decoded, phone);
+    assert_eq!(decoded.name(), "phone");
+    assert_eq!(decoded.identity(), master.id());
+    assert_eq!(decoded.signing_algorithm(), SignatureAlgorithm::Dilithium2);
+    assert_eq!(decoded.kem_public_key().algorithm(), Some(KemAlgorithm::MlKem768));
+
+    identity.verify_device_at(&decoded, NOW).unwrap();
+    assert_eq!(identity.verify_device_at(&phone, NOW + 1001), Err(IdentityError::Expired("phone".into())));
+
+    // Another master did not certify it
+    let other = MasterKey::generate().unwrap().identity();
+    assert_eq!(other.verify_device_at(&phone, NOW), Err(IdentityError::WrongIdentity));
+
+    // Renaming the device breaks the signature
+    let mut bytes = phone.to_bytes();
+    let name_at = bytes.windows(5).position(|w| w == b"phone").unwrap();
+    bytes[name_at] = b'P';
+    let tampered = DeviceCertificate::from_bytes(&bytes).unwrap();
+    assert_eq!(identity.verify_device_at(&tampered, NOW), Err(IdentityError::BadSignature));
+
+    assert_eq!(
+        DeviceCertificate::from_bytes(&bytes[..50]).err(),
+        Some(IdentityError::Encoding(CertificateError::Truncated))
+    );
+    assert_eq!(
+        phone.signing_public_key(&MlDsa44::new()).err(),
+        Some(IdentityError::AlgorithmMismatch(SignatureAlgorithm::Dilithium2))
+    );
+
+    let scheme = Dilithium::new();
+    let (pk, _) = scheme.keygen().unwrap();
+    let untagged = PublicKey::from_vec(vec![0; 1184]);
+    assert_eq!(
+        master.certify_device("tablet", &scheme, &pk, &untagged, Validity::for_days(1)).err(),
+        Some(IdentityError::UntaggedKemKey)
+    );
+}
+
+#[test]
+fn test_revocation() {
+    let master = MasterKey::generate().unwrap();
+    let mut identity = master.identity();
+    let phone = device(&master, "phone", Validity::new(NOW - 1000, NOW + 1000)).certificate;
+    let laptop = device(&master, "laptop", Validity::new(NOW - 1000, NOW + 1000)).certificate;
+
+    let first = master.revoke(1, &[phone.serial()]).unwrap();
+    let decoded = RevocationList::from_bytes(&first.to_bytes()).unwrap();
+    assert_eq!(decoded, first);
+    assert!(decoded.contains(phone.serial()) && !decoded.contains(laptop.serial()));
+
+    identity.apply_revocations(&decoded).unwrap();
+    assert_eq!(identity.verify_device_at(&phone, NOW), Err(IdentityError::Revoked("phone".into())));
+    identity.verify_device_at(&laptop, NOW).unwrap();
+
+    // An older list cannot undo the revocation
+    let empty = master.revoke(0, &[]).unwrap();
+    assert_eq!(identity.apply_revocations(&empty), Err(IdentityError::StaleRevocations));
+    assert_eq!(identity.apply_revocations(&first), Err(IdentityError::StaleRevocations));
+    assert!(identity.is_revoked(phone.serial()));
+
+    // Only the master key can revoke
+    let forged = MasterKey::generate().unwrap().revoke(2, &[laptop.serial()]).unwrap();
+    assert_eq!(identity.apply_revocations(&forged), Err(IdentityError::WrongIdentity));
+}
+
+#[test]
+fn test_handshake_accepts_any_certified_device() {
+    let master = MasterKey::generate().unwrap();
+    let phone = device(&master, "phone", Validity::for_days(1));
+    let laptop = device(&master, "laptop", Validity::for_days(1));
+    let store = Arc::new(Mutex::new(KnownIdentities::new().trust("alice", master.identity())));
+
+    for device in [&phone, &laptop] {
+        let server = connect(device, store.clone()).unwrap();
+        assert_eq!(server.peer_identity(), Some(master.id()));
+        assert_eq!(server.peer_device(), Some(&device.certificate));
+    }
+
+    // A revoked device is refused, the others still work
+    let list = master.revoke(1, &[phone.certificate.serial()]).unwrap();
+    store.lock().unwrap().apply_revocations(&list).unwrap();
+    assert_eq!(connect(&phone, store.clone()).err(), Some(PQError::Device(IdentityError::Revoked("phone".into()))));
+    assert!(connect(&laptop, store.clone()).is_ok());
+
+    // Another identity claiming the name, and an expired device
+    let mallory = MasterKey::generate().unwrap();
+    let impostor = device(&mallory, "phone", Validity::for_days(1));
+    assert_eq!(connect(&impostor, store.clone()).err(), Some(PQError::IdentityChanged));
+    assert_eq!(
+        connect(&impostor, KnownIdentities::new().trust("bob", mallory.identity())).err(),
+        Some(PQError::UnknownIdentity)
+    );
+    let expired = device(&master, "old-phone", Validity::new(NOW - 1000, NOW - 1));
+    assert_eq!(
+        connect(&expired, store.clone()).err(),
+        Some(PQError::Device(IdentityError::Expired("old-phone".into())))
+    );
+
+    // A bare key, or someone else's device certificate, is refused
+    let mut server = PQSession::new().with_trust_store(store.clone()).with_expected_peer("alice");
+    assert_eq!(
+        server.process_handshake(PQSession::new().initiate_handshake().unwrap()).err(),
+        Some(PQError::UnknownIdentity)
+    );
+    let mut stolen = PQSession::new().initiate_handshake().unwrap();
+    stolen.device = Some(laptop.certificate.clone());
+    assert_eq!(server.process_handshake(stolen).err(), Some(PQError::InvalidSignature));
+
+    let (own_pk, own_sk) = Dilithium::new().keygen().unwrap();
+    let mut mallory = PQSession::new()
+        .with_device(laptop.certificate.clone(), own_sk.clone(), laptop.kem_sk.clone())
+        .unwrap()
+        .with_identity(own_pk, own_sk);
+    assert_eq!(
+        server.process_handshake(mallory.initiate_handshake().unwrap()).err(),
+        Some(PQError::Device(IdentityError::KeyMismatch))
+    );
+}
+
+#[test]
+fn test_handshake_uses_certified_kem_key() {
+    let master = MasterKey::generate().unwrap();
+    let phone = device(&master, "phone", Validity::for_days(1));
+    let store = || KnownIdentities::new().trust("alice", master.identity());
+
+    let mut client = phone.session();
+    let mut server = PQSession::new().with_trust_store(store()).with_expected_peer("alice");
+    let reply = server.process_handshake(client.initiate_handshake().unwrap()).unwrap();
+    assert!(reply.device_ciphertext.is_some());
+    client.complete_handshake(reply).unwrap();
+    let ct = client.encrypt(b"from the phone");
+    assert_eq!(server.decrypt(&ct).unwrap(), b"from the phone");
+
+    // The device signing key alone does not give the session keys
+    let (_, wrong_kem_sk) = MlKem768::new().keygen().unwrap();
+    let mut thief = PQSession::new()
+        .with_device(phone.certificate.clone(), phone.sk.clone(), wrong_kem_sk)
+        .unwrap();
+    let mut server = PQSession::new().with_trust_store(store()).with_expected_peer("alice");
+    let reply = server.process_handshake(thief.initiate_handshake().unwrap()).unwrap();
+    thief.complete_handshake(reply).unwrap();
+    let ct = thief.encrypt(b"from the thief");
+    assert_eq!(server.decrypt(&ct).err(), Some(PQError::InvalidCiphertext));
+
+    // A device refuses a reply without the device encapsulation
+    let mut client = phone.session();
+    let mut server = PQSession::new();
+    let mut reply = server.process_handshake(client.initiate_handshake().unwrap()).unwrap();
+    reply.device_ciphertext = None;
+    assert_eq!(client.complete_handshake(reply).err(), Some(PQError::InvalidSignature));
+}
+
+#[test]
+fn test_bidirectional_peer_is_master_identity() {
+    let alice = MasterKey::generate().unwrap();
+    let bob = MasterKey::generate().unwrap();
+    let phone = device(&alice, "phone", Validity::for_days(1)).certificate;
+    let laptop = device(&alice, "laptop", Validity::for_days(1)).certificate;
+    let server = device(&bob, "server", Validity::for_days(1)).certificate;
+
+    let bob_session = BidirectionalSession::between_devices([0xBB; 32], [0xAA; 32], &server, &phone);
+    assert_eq!(bob_session.peer_id, alice.id());
+    assert_eq!(bob_session.my_id, bob.id());
+
+    // Envelopes from any of Alice's devices carry her identity
+    for device in [&phone, &laptop] {
+        let sender = BidirectionalSession::between_devices([0xAA; 32], [0xBB; 32], device, &server);
+        let seq = sender.send(b"hello", 1).unwrap();
+        let envelope = sender.sender_state.lock().unwrap().unacked_messages[&seq].0.clone();
+        assert_eq!(envelope.sender_id, alice.id());
+        bob_session.receive(envelope).unwrap();
+    }
+}
@@ -20,4 +20,69 @@ fn test_full_session() {
     let decrypted = bob.decrypt(&ciphertext).unwrap();
 
     assert_eq!(plaintext, decrypted.as_slice());
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_full_session_kyber1024() {
+    use pq_core::kem::Kyber1024;
+
+    let mut alice = PQSession::with_kem(Box::new(Kyber1024::new()));
+    let mut bob = PQSession::with_kem(Box::new(Kyber1024::new()));
+
+    let handshake = alice.initiate_handshake().expect("Alice handshake failed");
+    let response = bob.process_handshake(handshake).unwrap();
+    alice.complete_handshake(response).unwrap();
+
+    let plaintext = b"PQ-Core level 5 message";
+    let ciphertext = alice.encrypt(plaintext);
+    let decrypted = bob.decrypt(&ciphertext).unwrap();
+
+    assert_eq!(plaintext, decrypted.as_slice());
+}
+
+#[test]
+fn test_full_session_mlkem768() {
+    use pq_core::kem::MlKem768;
+
+    let mut alice = PQSession::with_kem(Box::new(MlKem768::new()));
+    let mut bob = PQSession::with_kem(Box::new(MlKem768::new()));
+
+    let handshake = alice.initiate_handshake().expect("Alice handshake failed");
+    let response = bob.process_handshake(handshake).unwrap();
+    alice.complete_handshake(response).unwrap();
+
+    let plaintext = b"PQ-Core ML-KEM message";
+    let ciphertext = alice.encrypt(plaintext);
+    let decrypted = bob.decrypt(&ciphertext).unwrap();
+
+    assert_eq!(plaintext, decrypted.as_slice());
+}
+
+#[test]
+fn test_session_rejects_mismatched_kem() {
+    use pq_core::kem::MlKem512;
+
+    let mut alice = PQSession::new();
+    let mut bob = PQSession::with_kem(Box::new(MlKem512::new()));
+
+    let handshake = alice.initiate_handshake().expect("Alice handshake failed");
+    assert!(bob.process_handshake(handshake).is_err());
+}
+
+#[test]
+fn test_full_session_hybrid() {
+    use pq_core::kem::X25519Kyber512;
+
+    let mut alice = PQSession::with_kem(Box::new(X25519Kyber512::new()));
+    let mut bob = PQSession::with_kem(Box::new(X25519Kyber512::new()));
+
+    let handshake = alice.initiate_handshake().expect("Alice handshake failed");
+    let response = bob.process_handshake(handshake).unwrap();
+    alice.complete_handshake(response).unwrap();
+
+    let plaintext = b"PQ-Core hybrid message";
+    let ciphertext = alice.encrypt(plaintext);
+    let decrypted = bob.decrypt(&ciphertext).unwrap();
+
+    assert_eq!(plaintext, decrypted.as_slice());
+}
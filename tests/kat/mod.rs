@@ -0,0 +1,136 @@
+//! Shared helpers for the known-answer tests: a parser for NIST `.rsp`
+//! files and the AES-256-CTR DRBG the NIST KAT generators draw from.
+
+use aes_gcm::aes::Aes256;
+use aes_gcm::aes::cipher::{BlockEncrypt, KeyInit};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// One `count = N` block of a `.rsp` file.
+pub struct KatVector {
+    fields: HashMap<String, String>,
+}
+
+impl KatVector {
+    pub fn count(&self) -> usize {
+        self.number("count")
+    }
+
+    /// Parses the named decimal field, e.g. `mlen`.
+    pub fn number(&self, name: &str) -> usize {
+        self.field(name).parse().unwrap_or_else(|_| panic!("field `{name}` is not a number"))
+    }
+
+    /// The named field as written, e.g. a hash function name.
+    pub fn text(&self, name: &str) -> &str {
+        self.field(name)
+    }
+
+    /// Hex-decodes the named field.
+    pub fn bytes(&self, name: &str) -> Vec<u8> {
+        decode_hex(self.field(name))
+    }
+
+    fn field(&self, name: &str) -> &str {
+        self.fields.get(name).unwrap_or_else(|| panic!("missing field `{name}`"))
+    }
+}
+
+/// Parses every vector in `tests/kat/<name>`.
+pub fn load(name: &str) -> Vec<KatVector> {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/kat").join(name);
+    let text = std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {e}", path.display()));
+    parse_rsp(&text)
+}
+
+/// Parses `.rsp` text into vectors. Comment lines (`#`) and section headers
+/// (`[...]`) are skipped; each `count` line starts a new vector.
+pub fn parse_rsp(text: &str) -> Vec<KatVector> {
+    let mut vectors = Vec::new();
+    let mut current: Option<HashMap<String, String>> = None;
+
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') || line.starts_with('[') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            panic!("malformed .rsp line: {line}");
+        };
+        let (key, value) = (key.trim(), value.trim());
+        if key == "count" {
+            if let Some(fields) = current.take() {
+                vectors.push(KatVector { fields });
+            }
+            current = Some(HashMap::new());
+        }
+        current
+            .as_mut()
+            .expect("field before first `count`")
+            .insert(key.to_string(), value.to_string());
+    }
+    vectors.extend(current.map(|fields| KatVector { fields }));
+    vectors
+}
+
+fn decode_hex(hex: &str) -> Vec<u8> {
+    assert!(hex.len().is_multiple_of(2), "odd-length hex string");
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).expect("invalid hex digit"))
+        .collect()
+}
+
+/// The `randombytes` DRBG from NIST's `rng.c` (AES-256 in counter mode,
+/// without derivation function or reseeding).
+///
+/// Seeding it with a vector's `seed` and drawing bytes in the same order as
+/// the reference implementation reproduces the randomness behind that
+/// vector, which is then fed to `keygen_from_seed` and friends.
+pub struct NistDrbg {
+    key: [u8; 32],
+    v: [u8; 16],
+}
+
+impl NistDrbg {
+    pub fn new(entropy_input: &[u8]) -> Self {
+        let seed_material: [u8; 48] = entropy_input.try_into().expect("48-byte DRBG seed");
+        let mut drbg = NistDrbg { key: [0; 32], v: [0; 16] };
+        drbg.update(Some(&seed_material));
+        drbg
+    }
+
+    pub fn random_bytes(&mut self, len: usize) -> Vec<u8> {
+        let mut out = Vec::with_capacity(len);
+        while out.len() < len {
+            let block = self.next_block();
+            let take = (len - out.len()).min(16);
+            out.extend_from_slice(&block[..take]);
+        }
+        self.update(None);
+        out
+    }
+
+    fn next_block(&mut self) -> [u8; 16] {
+        for byte in self.v.iter_mut().rev() {
+            *byte = byte.wrapping_add(1);
+            if *byte != 0 {
+                break;
+            }
+        }
+        let mut block = self.v.into();
+        Aes256::new(&self.key.into()).encrypt_block(&mut block);
+        block.into()
+    }
+
+    fn update(&mut self, provided_data: Option<&[u8; 48]>) {
+        let mut temp = [0u8; 48];
+        for chunk in temp.chunks_exact_mut(16) {
+            chunk.copy_from_slice(&self.next_block());
+        }
+        if let Some(data) = provided_data {
+            temp.iter_mut().zip(data).for_each(|(t, d)| *t ^= d);
+        }
+        self.key.copy_from_slice(&temp[..32]);
+        self.v.copy_from_slice(&temp[32..]);
+    }
+}
@@ -0,0 +1,419 @@
+//! Known-answer tests against the NIST submission `.rsp` vectors.
+//!
+//! The files in `tests/kat/` are the `nistkat` outputs used by PQClean and
+//! liboqs. Each one hashes to the published `nistkat-sha256` (PQClean
+//! `META.yml`) or `single` (liboqs `tests/KATs/*/kats.json`) value, so they
+//! can be re-checked with `sha256sum`.
+//!
+//! Implementations that accept seeds have their randomness replayed from
+//! the NIST DRBG and are checked end to end. For the pqcrypto-backed Kyber
+//! KEMs that covers the C backend's decapsulation and the pure-Rust seeded
+//! paths; the `native-kyber` feature adds the `NativeKyber*` types.
+//!
+//! The `ml_dsa_*.rsp` files cover ML-DSA and HashML-DSA with context
+//! strings. They come from the independent `fips204` crate and, for the
+//! plain ML-DSA vectors, agree with OpenSSL 3.5. The `slh_dsa_*.rsp` files
+//! were produced with OpenSSL 3.5's deterministic SLH-DSA signing.
+//!
+//! `hpke_x25519_sha256.rsp` holds the RFC 9180 vectors for the HPKE layer,
+//! converted from the CFRG `test-vectors.json`.
+
+mod kat;
+
+use kat::NistDrbg;
+use pq_core::hpke::{Hpke, HpkeAead, HpkeKem, Psk};
+use pq_core::kem::kem::{Ciphertext, PublicKey, SecretKey, SharedSecret};
+use pq_core::kem::{
+    Kem, KemAlgorithm, Kyber512, Kyber768, Kyber1024, MlKem512, MlKem768, MlKem1024, X25519Kyber512, X25519Kyber768,
+    X25519Kyber1024, X25519MlKem512, X25519MlKem768, X25519MlKem1024,
+};
+use pq_core::sig::dilithium::{Dilithium, Dilithium3, Dilithium5, DilithiumPublicKey, DilithiumSecretKey};
+use pq_core::sig::falcon::{Falcon512, Falcon512SecretKey, Falcon1024, Falcon1024SecretKey};
+use pq_core::sig::ml_dsa::{MlDsa44, MlDsa65, MlDsa87, PreHash};
+use pq_core::sig::slh_dsa::{SlhDsaSha2_128s, SlhDsaShake128f};
+use pq_core::sig::{SignatureError, Signer};
+use sha3::{Digest, Sha3_256};
+use x25519_dalek::{PublicKey as X25519PublicKey, StaticSecret};
+
+/// Checks `kem` against every vector in `file`.
+fn check_kem_kat(kem: &dyn Kem, file: &str) {
+    let vectors = kat::load(file);
+    assert!(!vectors.is_empty(), "{file} has no vectors");
+
+    for v in vectors {
+        let (pk, sk, ct, ss) = (v.bytes("pk"), v.bytes("sk"), v.bytes("ct"), v.bytes("ss"));
+        let count = v.count();
+        assert_eq!(pk.len(), kem.public_key_bytes(), "{file} #{count}: pk size");
+        assert_eq!(sk.len(), kem.secret_key_bytes(), "{file} #{count}: sk size");
+        assert_eq!(ct.len(), kem.ciphertext_bytes(), "{file} #{count}: ct size");
+
+        let decapsulated = kem.decaps(&Ciphertext::from_vec(ct.clone()), &SecretKey::from_vec(sk.clone())).unwrap();
+        assert_eq!(decapsulated.as_ref(), ss.as_slice(), "{file} #{count}: decaps");
+
+        let Some(seed_len) = kem.keygen_seed_bytes() else { continue };
+        let mut drbg = NistDrbg::new(&v.bytes("seed"));
+        let (got_pk, got_sk) = kem.keygen_from_seed(&keygen_seed(kem, &mut drbg, seed_len)).unwrap();
+        assert_eq!(got_pk.as_ref(), pk.as_slice(), "{file} #{count}: keygen pk");
+        assert_eq!(got_sk.as_ref(), sk.as_slice(), "{file} #{count}: keygen sk");
+
+        let coins_len = kem.encaps_coins_bytes().expect("seeded KEMs encapsulate deterministically");
+        let (got_ct, got_ss) = kem.encaps_deterministic(&got_pk, &drbg.random_bytes(coins_len)).unwrap();
+        assert_eq!(got_ct.as_ref(), ct.as_slice(), "{file} #{count}: encaps ct");
+        assert_eq!(got_ss.as_ref(), ss.as_slice(), "{file} #{count}: encaps ss");
+    }
+}
+
+/// Draws a key generation seed the way the reference code does: round-3
+/// Kyber draws `d` and `z` separately, ML-KEM draws `d || z` at once.
+fn keygen_seed(kem: &dyn Kem, drbg: &mut NistDrbg, len: usize) -> Vec<u8> {
+    match kem.algorithm() {
+        KemAlgorithm::Kyber512 | KemAlgorithm::Kyber768 | KemAlgorithm::Kyber1024
+        | KemAlgorithm::X25519Kyber512 | KemAlgorithm::X25519Kyber768 | KemAlgorithm::X25519Kyber1024 => {
+            [drbg.random_bytes(len / 2), drbg.random_bytes(len / 2)].concat()
+        }
+        _ => drbg.random_bytes(len),
+    }
+}
+
+const X25519_STATIC: [u8; 32] = [0x42; 32];
+const X25519_EPHEMERAL: [u8; 32] = [0x24; 32];
+
+/// The hybrid combiner from `kem::hybrid`, restated so a change to the
+/// wire format shows up here.
+fn hybrid_combine(ss_pq: &[u8], ss_x: &[u8], ct_pq: &[u8], ct_x: &[u8], pk_pq: &[u8], pk_x: &[u8]) -> Vec<u8> {
+    let mut hasher = Sha3_256::new();
+    hasher.update(b"PQ-Core X25519 hybrid KEM v1");
+    for part in [ss_pq, ss_x, ct_pq, ct_x, pk_pq, pk_x] {
+        hasher.update((part.len() as u32).to_be_bytes());
+        hasher.update(part);
+    }
+    hasher.finalize().to_vec()
+}
+
+/// Checks a hybrid KEM whose post-quantum half is covered by `file`. The
+/// X25519 half uses fixed keys, so the expected shared secret follows from
+/// the KAT shared secret and the combiner.
+fn check_hybrid_kat(kem: &dyn Kem, file: &str) {
+    let x_sk = StaticSecret::from(X25519_STATIC);
+    let x_pk = X25519PublicKey::from(&x_sk);
+    let x_eph = StaticSecret::from(X25519_EPHEMERAL);
+    let x_ct = X25519PublicKey::from(&x_eph);
+    let x_ss = x_eph.diffie_hellman(&x_pk);
+
+    for v in kat::load(file) {
+        let (pk, sk, ct, ss) = (v.bytes("pk"), v.bytes("sk"), v.bytes("ct"), v.bytes("ss"));
+        let count = v.count();
+        let hybrid_pk = [x_pk.as_bytes().as_slice(), &pk].concat();
+        let hybrid_sk = [x_sk.as_bytes().as_slice(), x_pk.as_bytes(), &sk, &pk].concat();
+        let hybrid_ct = [x_ct.as_bytes().as_slice(), &ct].concat();
+        let expected = hybrid_combine(&ss, x_ss.as_bytes(), &ct, x_ct.as_bytes(), &pk, x_pk.as_bytes());
+
+        let decapsulated = kem.decaps(&Ciphertext::from_vec(hybrid_ct.clone()), &SecretKey::from_vec(hybrid_sk.clone())).unwrap();
+        assert_eq!(decapsulated, SharedSecret::from_vec(expected.clone()), "{file} #{count}: hybrid decaps");
+
+        let Some(seed_len) = kem.keygen_seed_bytes() else { continue };
+        let mut drbg = NistDrbg::new(&v.bytes("seed"));
+        let seed = [X25519_STATIC.as_slice(), &keygen_seed(kem, &mut drbg, seed_len - 32)].concat();
+        let (got_pk, got_sk) = kem.keygen_from_seed(&seed).unwrap();
+        assert_eq!(got_pk.as_ref(), hybrid_pk.as_slice(), "{file} #{count}: hybrid keygen pk");
+        assert_eq!(got_sk.as_ref(), hybrid_sk.as_slice(), "{file} #{count}: hybrid keygen sk");
+
+        let coins_len = kem.encaps_coins_bytes().expect("seeded KEMs encapsulate deterministically");
+        let coins = [X25519_EPHEMERAL.as_slice(), &drbg.random_bytes(coins_len - 32)].concat();
+        let (got_ct, got_ss) = kem.encaps_deterministic(&got_pk, &coins).unwrap();
+        assert_eq!(got_ct.as_ref(), hybrid_ct.as_slice(), "{file} #{count}: hybrid encaps ct");
+        assert_eq!(got_ss.as_ref(), expected.as_slice(), "{file} #{count}: hybrid encaps ss");
+    }
+}
+
+#[test]
+fn test_kyber_kats() {
+    check_kem_kat(&Kyber512::new(), "kyber512.rsp");
+    check_kem_kat(&Kyber768::new(), "kyber768.rsp");
+    check_kem_kat(&Kyber1024::new(), "kyber1024.rsp");
+}
+
+#[cfg(feature = "native-kyber")]
+#[test]
+fn test_native_kyber_kats() {
+    use pq_core::kem::{HybridKem, NativeKyber512, NativeKyber768, NativeKyber1024};
+
+    check_kem_kat(&NativeKyber512::new(), "kyber512.rsp");
+    check_kem_kat(&NativeKyber768::new(), "kyber768.rsp");
+    check_kem_kat(&NativeKyber1024::new(), "kyber1024.rsp");
+    check_hybrid_kat(&HybridKem::<NativeKyber512>::new(), "kyber512.rsp");
+    check_hybrid_kat(&HybridKem::<NativeKyber768>::new(), "kyber768.rsp");
+    check_hybrid_kat(&HybridKem::<NativeKyber1024>::new(), "kyber1024.rsp");
+}
+
+#[test]
+fn test_ml_kem_kats() {
+    check_kem_kat(&MlKem512::new(), "ml_kem_512.rsp");
+    check_kem_kat(&MlKem768::new(), "ml_kem_768.rsp");
+    check_kem_kat(&MlKem1024::new(), "ml_kem_1024.rsp");
+}
+
+#[test]
+fn test_hybrid_kats() {
+    check_hybrid_kat(&X25519Kyber512::new(), "kyber512.rsp");
+    check_hybrid_kat(&X25519Kyber768::new(), "kyber768.rsp");
+    check_hybrid_kat(&X25519Kyber1024::new(), "kyber1024.rsp");
+    check_hybrid_kat(&X25519MlKem512::new(), "ml_kem_512.rsp");
+    check_hybrid_kat(&X25519MlKem768::new(), "ml_kem_768.rsp");
+    check_hybrid_kat(&X25519MlKem1024::new(), "ml_kem_1024.rsp");
+}
+
+/// Checks a Dilithium parameter set against every vector in `file`, with
+/// keys derived from the DRBG output by `keygen_from_seed`.
+fn check_dilithium_kat<S: Signer>(
+    scheme: &S,
+    keygen_from_seed: impl Fn(&[u8]) -> Result<(S::PublicKey, S::SecretKey), SignatureError>,
+    file: &str,
+) {
+    let vectors = kat::load(file);
+    assert!(!vectors.is_empty(), "{file} has no vectors");
+
+    for v in vectors {
+        let (msg, pk, sk, sm) = (v.bytes("msg"), v.bytes("pk"), v.bytes("sk"), v.bytes("sm"));
+        let count = v.count();
+        assert_eq!(msg.len(), v.number("mlen"));
+
+        let mut drbg = NistDrbg::new(&v.bytes("seed"));
+        let (got_pk, got_sk) = keygen_from_seed(&drbg.random_bytes(Dilithium::seed_bytes())).unwrap();
+        assert_eq!(got_pk.as_ref(), pk.as_slice(), "{file} #{count}: keygen pk");
+        assert_eq!(got_sk.as_ref(), sk.as_slice(), "{file} #{count}: keygen sk");
+
+        // Signing is deterministic, so the signature must match exactly. The
+        // vectors hold the attached form: signature followed by the message.
+        let (expected_sig, signed_msg) = sm.split_at(scheme.signature_bytes());
+        assert_eq!(signed_msg, msg.as_slice(), "{file} #{count}: sm layout");
+        let signed = scheme.sign(&msg, &got_sk).unwrap();
+        assert_eq!(signed.as_ref(), expected_sig, "{file} #{count}: sign");
+        assert!(scheme.signature_from_bytes(expected_sig).is_ok());
+
+        let pk = scheme.public_key_from_bytes(&pk).unwrap();
+        assert!(scheme.verify(&msg, &signed, &pk).unwrap());
+    }
+}
+
+#[test]
+fn test_dilithium_kats() {
+    let sig = Dilithium::new();
+    check_dilithium_kat(&sig, |seed| sig.keygen_from_seed(seed), "dilithium2.rsp");
+    let sig = Dilithium3::new();
+    check_dilithium_kat(&sig, |seed| sig.keygen_from_seed(seed), "dilithium3.rsp");
+    let sig = Dilithium5::new();
+    check_dilithium_kat(&sig, |seed| sig.keygen_from_seed(seed), "dilithium5.rsp");
+}
+
+#[test]
+fn test_dilithium2_attached_kat() {
+    let sig = Dilithium::new();
+
+    for v in kat::load("dilithium2.rsp") {
+        let (msg, sm) = (v.bytes("msg"), v.bytes("sm"));
+        let sk = DilithiumSecretKey::from_bytes(&v.bytes("sk")).unwrap();
+        let pk = DilithiumPublicKey::from_bytes(&v.bytes("pk")).unwrap();
+        assert_eq!(sig.sign_attached(&msg, &sk).unwrap(), sm, "dilithium2 #{}: sign_attached", v.count());
+        assert_eq!(sig.open_attached(&sm, &pk).unwrap(), msg);
+    }
+}
+
+/// Checks an ML-DSA parameter set against every vector in `file`: seeded
+/// keygen, deterministic signing (plain or pre-hashed, with the vector's
+/// context string) and verification.
+macro_rules! check_ml_dsa_kat {
+    ($scheme:ty, $file:expr) => {{
+        let scheme = <$scheme>::new_deterministic();
+        let vectors = kat::load($file);
+        assert!(!vectors.is_empty(), "{} has no vectors", $file);
+
+        for v in vectors {
+            let (msg, ctx, sig) = (v.bytes("msg"), v.bytes("ctx"), v.bytes("sig"));
+            let count = v.count();
+            assert_eq!(msg.len(), v.number("mlen"));
+
+            let (pk, sk) = scheme.keygen_from_seed(&v.bytes("seed")).unwrap();
+            assert_eq!(pk.as_bytes(), v.bytes("pk").as_slice(), "{} #{count}: keygen pk", $file);
+            assert_eq!(sk.as_bytes(), v.bytes("sk").as_slice(), "{} #{count}: keygen sk", $file);
+
+            if v.number("mode") == 0 {
+                let got = scheme.sign_with_context(&msg, &ctx, &sk).unwrap();
+                assert_eq!(got.as_bytes(), sig.as_slice(), "{} #{count}: sign", $file);
+                assert!(scheme.verify_with_context(&msg, &ctx, &got, &pk).unwrap());
+                assert!(!scheme.verify_with_context(&msg, b"other context", &got, &pk).unwrap());
+            } else {
+                let ph = match v.text("ph") {
+                    "SHA-256" => PreHash::Sha256,
+                    "SHA-512" => PreHash::Sha512,
+                    "SHAKE-128" => PreHash::Shake128,
+                    other => panic!("unknown pre-hash {other}"),
+                };
+                // Feed the message in pieces, as a streaming caller would
+                let hash = |msg: &[u8]| {
+                    let mut hasher = ph.hasher();
+                    msg.chunks(64).for_each(|chunk| hasher.update(chunk));
+                    hasher
+                };
+                let got = scheme.sign_prehashed(hash(&msg), &ctx, &sk).unwrap();
+                assert_eq!(got.as_bytes(), sig.as_slice(), "{} #{count}: sign_prehashed", $file);
+                assert!(scheme.verify_prehashed(hash(&msg), &ctx, &got, &pk).unwrap());
+                assert!(!scheme.verify_with_context(&msg, &ctx, &got, &pk).unwrap());
+            }
+        }
+    }};
+}
+
+#[test]
+fn test_ml_dsa_kats() {
+    check_ml_dsa_kat!(MlDsa44, "ml_dsa_44.rsp");
+    check_ml_dsa_kat!(MlDsa65, "ml_dsa_65.rsp");
+    check_ml_dsa_kat!(MlDsa87, "ml_dsa_87.rsp");
+}
+
+/// Checks an SLH-DSA parameter set against every vector in `file`: seeded
+/// keygen, deterministic signing with the vector's context string, and
+/// verification.
+macro_rules! check_slh_dsa_kat {
+    ($scheme:ty, $file:expr) => {{
+        let scheme = <$scheme>::new_deterministic();
+        let vectors = kat::load($file);
+        assert!(!vectors.is_empty(), "{} has no vectors", $file);
+
+        for v in vectors {
+            let (msg, ctx, sig) = (v.bytes("msg"), v.bytes("ctx"), v.bytes("sig"));
+            let count = v.count();
+            assert_eq!(msg.len(), v.number("mlen"));
+
+            let (pk, sk) = scheme.keygen_from_seed(&v.bytes("seed")).unwrap();
+            assert_eq!(pk.as_bytes(), v.bytes("pk").as_slice(), "{} #{count}: keygen pk", $file);
+            assert_eq!(sk.as_bytes(), v.bytes("sk").as_slice(), "{} #{count}: keygen sk", $file);
+
+            let got = scheme.sign_with_context(&msg, &ctx, &sk).unwrap();
+            assert_eq!(got.as_bytes(), sig.as_slice(), "{} #{count}: sign", $file);
+            assert!(scheme.verify_with_context(&msg, &ctx, &got, &pk).unwrap());
+            assert!(!scheme.verify_with_context(&msg, b"other context", &got, &pk).unwrap());
+        }
+    }};
+}
+
+#[test]
+fn test_slh_dsa_kats() {
+    check_slh_dsa_kat!(SlhDsaSha2_128s, "slh_dsa_sha2_128s.rsp");
+    check_slh_dsa_kat!(SlhDsaShake128f, "slh_dsa_shake_128f.rsp");
+}
+
+/// Checks a Falcon parameter set against every vector in `file`. Falcon
+/// draws its signing randomness from the OS RNG, so only verification is
+/// known-answer: the vector's attached signed message is converted to the
+/// detached form and must verify, and a fresh signature made with the
+/// vector's secret key must verify under its public key.
+fn check_falcon_kat<S: Signer>(
+    scheme: &S,
+    secret_key_from_bytes: impl Fn(&[u8]) -> Result<S::SecretKey, SignatureError>,
+    logn: u8,
+    file: &str,
+) {
+    const NONCE_BYTES: usize = 40;
+    let vectors = kat::load(file);
+    assert!(!vectors.is_empty(), "{file} has no vectors");
+
+    for v in vectors {
+        let (msg, pk, sk, sm) = (v.bytes("msg"), v.bytes("pk"), v.bytes("sk"), v.bytes("sm"));
+        let count = v.count();
+        assert_eq!(msg.len(), v.number("mlen"));
+        assert_eq!(pk.len(), scheme.public_key_bytes(), "{file} #{count}: pk size");
+        assert_eq!(sk.len(), scheme.secret_key_bytes(), "{file} #{count}: sk size");
+
+        // Attached layout: signature length (2) | nonce | message | 0x20 + logn | compressed
+        let (nonce, rest) = sm[2..].split_at(NONCE_BYTES);
+        let (signed_msg, esig) = rest.split_at(msg.len());
+        assert_eq!(signed_msg, msg.as_slice(), "{file} #{count}: sm layout");
+        assert_eq!(esig[0], 0x20 + logn, "{file} #{count}: sm header");
+        let detached = [&[0x30 + logn], nonce, &esig[1..]].concat();
+
+        let pk = scheme.public_key_from_bytes(&pk).unwrap();
+        let expected = scheme.signature_from_bytes(&detached).unwrap();
+        assert!(scheme.verify(&msg, &expected, &pk).unwrap(), "{file} #{count}: verify");
+        assert!(!scheme.verify(&msg[1..], &expected, &pk).unwrap());
+
+        let sk = secret_key_from_bytes(&sk).unwrap();
+        let signed = scheme.sign(&msg, &sk).unwrap();
+        assert!(scheme.verify(&msg, &signed, &pk).unwrap(), "{file} #{count}: sign");
+    }
+}
+
+#[test]
+fn test_falcon_kats() {
+    check_falcon_kat(&Falcon512::new(), Falcon512SecretKey::from_bytes, 9, "falcon512.rsp");
+    check_falcon_kat(&Falcon1024::new(), Falcon1024SecretKey::from_bytes, 10, "falcon1024.rsp");
+}
+
+#[test]
+fn test_hpke_rfc9180_vectors() {
+    for v in kat::load("hpke_x25519_sha256.rsp") {
+        let count = v.count();
+        let aead = HpkeAead::from_id(v.number("aead_id") as u16).unwrap();
+        let hpke = Hpke::new(HpkeKem::X25519HkdfSha256, aead);
+        let mode = v.number("mode");
+        let info = v.bytes("info");
+
+        let (pk_r, sk_r) = hpke.derive_key_pair(&v.bytes("ikmR")).unwrap();
+        assert_eq!(pk_r.as_ref(), v.bytes("pkRm").as_slice(), "hpke #{count}: pkR");
+        assert_eq!(sk_r.as_ref(), v.bytes("skRm").as_slice(), "hpke #{count}: skR");
+
+        let (psk, psk_id) = if mode & 1 == 1 { (v.bytes("psk"), v.bytes("psk_id")) } else { (vec![], vec![]) };
+        let psk = (mode & 1 == 1).then_some(Psk { psk: &psk, id: &psk_id });
+        let (sk_s, pk_s) = if mode & 2 == 2 {
+            (Some(SecretKey::from_vec(v.bytes("skSm"))), Some(PublicKey::from_vec(v.bytes("pkSm"))))
+        } else {
+            (None, None)
+        };
+
+        let (enc, mut sender) = hpke
+            .setup_sender_deterministic(&pk_r, &info, psk, sk_s.as_ref(), &v.bytes("ikmE"))
+            .unwrap();
+        assert_eq!(enc, v.bytes("enc"), "hpke #{count}: enc");
+        let mut recipient = hpke.setup_recipient(&enc, &sk_r, &info, psk, pk_s.as_ref()).unwrap();
+
+        if aead != HpkeAead::ExportOnly {
+            for i in 0..3 {
+                // Advance both contexts to the vector's sequence number
+                while sender.sequence_number() < v.number(&format!("seq{i}")) as u64 {
+                    let ct = sender.seal(b"", b"").unwrap();
+                    recipient.open(b"", &ct).unwrap();
+                }
+                let (aad, pt) = (v.bytes(&format!("aad{i}")), v.bytes(&format!("pt{i}")));
+                let ct = sender.seal(&aad, &pt).unwrap();
+                assert_eq!(ct, v.bytes(&format!("ct{i}")), "hpke #{count}: ct{i}");
+                assert_eq!(recipient.open(&aad, &ct).unwrap(), pt);
+            }
+        }
+
+        for i in 0..3 {
+            let (context, len) = (v.bytes(&format!("exporter_context{i}")), v.number(&format!("L{i}")));
+            let expected = v.bytes(&format!("exported_value{i}"));
+            assert_eq!(sender.export(&context, len).unwrap().as_slice(), expected, "hpke #{count}: export{i}");
+            assert_eq!(recipient.export(&context, len).unwrap().as_slice(), expected);
+        }
+    }
+}
+
+#[test]
+fn test_rsp_parser() {
+    let text = "# Kyber512\n\ncount = 0\nseed = 00FF\nmlen = 2\n\ncount = 1\nseed = 0A\n";
+    let vectors = kat::parse_rsp(text);
+    assert_eq!(vectors.len(), 2);
+    assert_eq!(vectors[0].count(), 0);
+    assert_eq!(vectors[0].bytes("seed"), [0x00, 0xff]);
+    assert_eq!(vectors[1].count(), 1);
+    assert_eq!(vectors[1].bytes("seed"), [0x0a]);
+}
+
+#[test]
+fn test_nist_drbg_reproduces_kat_seed() {
+    // nistkat seeds its DRBG with 0..48 and draws each vector's seed from it
+    let entropy: Vec<u8> = (0..48).collect();
+    let mut drbg = NistDrbg::new(&entropy);
+    assert_eq!(drbg.random_bytes(48), kat::load("kyber512.rsp")[0].bytes("seed"));
+}
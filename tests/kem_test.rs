@@ -0,0 +1,209 @@
+use pq_core::kem::{Kem, KemAlgorithm, Kyber512, Kyber768, Kyber1024, MlKem512, MlKem768, MlKem1024};
+use pq_core::kem::{X25519Kyber512, X25519Kyber768, X25519Kyber1024, X25519MlKem512, X25519MlKem768, X25519MlKem1024};
+use pq_core::kem::kem::{Ciphertext, KemError, PublicKey, SecretKey};
+
+fn roundtrip(kem: &dyn Kem) {
+    let (pk, sk) = kem.keygen().expect("keygen failed");
+    assert_eq!(pk.as_ref().len(), kem.public_key_bytes());
+    assert_eq!(sk.as_ref().len(), kem.secret_key_bytes());
+
+    let (ct, ss_sender) = kem.encaps(&pk).expect("encaps failed");
+    assert_eq!(ct.as_ref().len(), kem.ciphertext_bytes());
+    assert_eq!(ss_sender.as_ref().len(), kem.shared_secret_bytes());
+
+    let ss_receiver = kem.decaps(&ct, &sk).expect("decaps failed");
+    assert_eq!(ss_sender.as_ref(), ss_receiver.as_ref());
+}
+
+fn rejects_wrong_sizes(kem: &dyn Kem) {
+    let (_, sk) = kem.keygen().expect("keygen failed");
+
+    let short_pk = PublicKey::from_vec(vec![0u8; kem.public_key_bytes() - 1]);
+    assert!(matches!(kem.encaps(&short_pk), Err(KemError::InvalidKeySize)));
+
+    let short_ct = Ciphertext::from_vec(vec![0u8; kem.ciphertext_bytes() - 1]);
+    assert!(matches!(kem.decaps(&short_ct, &sk), Err(KemError::InvalidCiphertextSize)));
+}
+
+#[test]
+fn test_kyber512_roundtrip() {
+    roundtrip(&Kyber512::new());
+    rejects_wrong_sizes(&Kyber512::new());
+}
+
+#[test]
+fn test_kyber768_roundtrip() {
+    roundtrip(&Kyber768::new());
+    rejects_wrong_sizes(&Kyber768::new());
+}
+
+#[test]
+fn test_kyber1024_roundtrip() {
+    roundtrip(&Kyber1024::new());
+    rejects_wrong_sizes(&Kyber1024::new());
+}
+
+#[test]
+fn test_kyber_parameter_sets_are_not_interchangeable() {
+    let (pk512, _) = Kyber512::new().keygen().unwrap();
+    assert!(matches!(Kyber768::new().encaps(&pk512), Err(KemError::AlgorithmMismatch { .. })));
+
+    let untagged = PublicKey::from_vec(pk512.as_ref().to_vec());
+    assert!(matches!(Kyber768::new().encaps(&untagged), Err(KemError::InvalidKeySize)));
+    assert!(matches!(Kyber1024::new().encaps(&untagged), Err(KemError::InvalidKeySize)));
+}
+
+#[test]
+fn test_mlkem_roundtrip() {
+    for kem in [&MlKem512::new() as &dyn Kem, &MlKem768::new(), &MlKem1024::new()] {
+        roundtrip(kem);
+        rejects_wrong_sizes(kem);
+    }
+}
+
+#[test]
+fn test_public_keys_are_tagged_with_their_algorithm() {
+    let (kyber_pk, _) = Kyber512::new().keygen().unwrap();
+    let (mlkem_pk, _) = MlKem512::new().keygen().unwrap();
+
+    assert_eq!(kyber_pk.algorithm(), Some(KemAlgorithm::Kyber512));
+    assert_eq!(mlkem_pk.algorithm(), Some(KemAlgorithm::MlKem512));
+    assert_eq!(kyber_pk.as_ref().len(), mlkem_pk.as_ref().len());
+
+    assert!(matches!(
+        MlKem512::new().encaps(&kyber_pk),
+        Err(KemError::AlgorithmMismatch { expected: KemAlgorithm::MlKem512, found: KemAlgorithm::Kyber512 })
+    ));
+    assert!(matches!(
+        Kyber512::new().encaps(&mlkem_pk),
+        Err(KemError::AlgorithmMismatch { expected: KemAlgorithm::Kyber512, found: KemAlgorithm::MlKem512 })
+    ));
+
+    // Raw bytes carry no tag and are accepted by any KEM of the right size
+    let untagged = PublicKey::from_vec(mlkem_pk.as_ref().to_vec());
+    assert_eq!(untagged.algorithm(), None);
+    assert!(MlKem512::new().encaps(&untagged).is_ok());
+}
+
+#[test]
+fn test_mlkem_encapsulation_key_modulus_check() {
+    let kem = MlKem768::new();
+    let (pk, _) = kem.keygen().unwrap();
+
+    // Force the first 12-bit coefficient to 0xfff >= q
+    let mut bytes = pk.as_ref().to_vec();
+    bytes[0] = 0xff;
+    bytes[1] |= 0x0f;
+    let bad_pk = PublicKey::from_vec(bytes);
+
+    assert!(matches!(kem.encaps(&bad_pk), Err(KemError::InvalidEncapsulationKey)));
+}
+
+#[test]
+fn test_mlkem_decapsulation_key_hash_check() {
+    let kem = MlKem512::new();
+    let (pk, sk) = kem.keygen().unwrap();
+    let (ct, _) = kem.encaps(&pk).unwrap();
+
+    // Corrupt H(ek), which sits right before the trailing 32-byte z
+    let mut bytes = sk.as_ref().to_vec();
+    let h_pos = bytes.len() - 64;
+    bytes[h_pos] ^= 0x01;
+    let bad_sk = SecretKey::from_vec(bytes);
+
+    assert!(matches!(kem.decaps(&ct, &bad_sk), Err(KemError::InvalidDecapsulationKey)));
+}
+
+#[test]
+fn test_hybrid_roundtrip() {
+    let kems: [&dyn Kem; 6] = [
+        &X25519Kyber512::new(),
+        &X25519Kyber768::new(),
+        &X25519Kyber1024::new(),
+        &X25519MlKem512::new(),
+        &X25519MlKem768::new(),
+        &X25519MlKem1024::new(),
+    ];
+    for kem in kems {
+        roundtrip(kem);
+        rejects_wrong_sizes(kem);
+        assert_eq!(kem.public_key_bytes(), 32 + match kem.algorithm() {
+            KemAlgorithm::X25519Kyber512 => Kyber512::new().public_key_bytes(),
+            KemAlgorithm::X25519Kyber768 => Kyber768::new().public_key_bytes(),
+            KemAlgorithm::X25519Kyber1024 => Kyber1024::new().public_key_bytes(),
+            KemAlgorithm::X25519MlKem512 => MlKem512::new().public_key_bytes(),
+            KemAlgorithm::X25519MlKem768 => MlKem768::new().public_key_bytes(),
+            KemAlgorithm::X25519MlKem1024 => MlKem1024::new().public_key_bytes(),
+            other => panic!("unexpected algorithm {other}"),
+        });
+    }
+}
+
+#[test]
+fn test_hybrid_rejects_plain_kyber_key() {
+    let (kyber_pk, _) = Kyber512::new().keygen().unwrap();
+    assert!(matches!(
+        X25519Kyber512::new().encaps(&kyber_pk),
+        Err(KemError::AlgorithmMismatch { .. })
+    ));
+}
+
+#[test]
+fn test_hybrid_binds_both_ciphertexts() {
+    let kem = X25519Kyber768::new();
+    let (pk, sk) = kem.keygen().unwrap();
+    let (ct, ss) = kem.encaps(&pk).unwrap();
+
+    // Tampering with the Kyber half changes the secret (implicit rejection)
+    let mut bytes = ct.as_ref().to_vec();
+    bytes[40] ^= 0x01;
+    let tampered = kem.decaps(&Ciphertext::from_vec(bytes), &sk).unwrap();
+    assert_ne!(ss.as_ref(), tampered.as_ref());
+
+    // Tampering with the X25519 half changes the secret as well
+    let mut bytes = ct.as_ref().to_vec();
+    bytes[0] ^= 0x02;
+    let tampered = kem.decaps(&Ciphertext::from_vec(bytes), &sk).unwrap();
+    assert_ne!(ss.as_ref(), tampered.as_ref());
+}
+
+#[test]
+fn test_hybrid_rejects_low_order_x25519_point() {
+    let kem = X25519Kyber512::new();
+    let (pk, sk) = kem.keygen().unwrap();
+    let (ct, _) = kem.encaps(&pk).unwrap();
+
+    let mut bytes = ct.as_ref().to_vec();
+    bytes[..32].fill(0);
+    assert!(matches!(
+        kem.decaps(&Ciphertext::from_vec(bytes), &sk),
+        Err(KemError::DecapsulationError)
+    ));
+}
+
+#[test]
+fn test_secret_types_redact_debug_output() {
+    let kem = Kyber512::new();
+    let (pk, sk) = kem.keygen().unwrap();
+    let (_, ss) = kem.encaps(&pk).unwrap();
+
+    let sk_debug = format!("{:?}", sk);
+    let ss_debug = format!("{:?}", ss);
+    assert_eq!(sk_debug, format!("SecretKey(<{} bytes redacted>)", kem.secret_key_bytes()));
+    assert_eq!(ss_debug, format!("SharedSecret(<{} bytes redacted>)", kem.shared_secret_bytes()));
+}
+
+#[test]
+fn test_secret_types_equality() {
+    let kem = MlKem512::new();
+    let (pk, sk) = kem.keygen().unwrap();
+    let (ct, ss) = kem.encaps(&pk).unwrap();
+
+    assert_eq!(sk, sk.clone());
+    assert_eq!(ss, kem.decaps(&ct, &sk).unwrap());
+
+    let mut other = ss.as_ref().to_vec();
+    other[31] ^= 0x80;
+    assert_ne!(ss, pq_core::kem::kem::SharedSecret::from_vec(other));
+    assert_ne!(ss, pq_core::kem::kem::SharedSecret::from_vec(vec![0u8; 16]));
+}
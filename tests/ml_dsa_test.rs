@@ -0,0 +1,112 @@
+use std::io::{self, Read};
+use pq_core::sig::ml_dsa::{MlDsa44, MlDsa44Signature, MlDsa65, MlDsa65PublicKey, MlDsa87, PreHash, MAX_CONTEXT_BYTES};
+use pq_core::sig::{SignatureAlgorithm, SignatureError, Verifier};
+
+#[test]
+fn test_sign_and_verify_with_context() {
+    let scheme = MlDsa65::new();
+    let (pk, sk) = scheme.keygen().unwrap();
+
+    let signature = scheme.sign_with_context(b"payload", b"firmware", &sk).unwrap();
+    assert_eq!(signature.as_bytes().len(), MlDsa65::signature_bytes());
+    assert!(scheme.verify_with_context(b"payload", b"firmware", &signature, &pk).unwrap());
+    assert!(!scheme.verify_with_context(b"payload", b"config", &signature, &pk).unwrap());
+    assert!(!scheme.verify(b"payload", &signature, &pk).unwrap());
+
+    // Hedged signing draws fresh randomness for every signature
+    let again = scheme.sign_with_context(b"payload", b"firmware", &sk).unwrap();
+    assert_ne!(signature.as_bytes(), again.as_bytes());
+
+    let long = [0u8; MAX_CONTEXT_BYTES + 1];
+    assert_eq!(scheme.sign_with_context(b"payload", &long, &sk).err(), Some(SignatureError::ContextTooLong));
+    assert!(scheme.sign_with_context(b"payload", &long[1..], &sk).is_ok());
+}
+
+#[test]
+fn test_prehashed_signing_streams_large_inputs() {
+    let scheme = MlDsa87::new();
+    let (pk, sk) = scheme.keygen().unwrap();
+    let artifact = || io::repeat(0xa5).take(1 << 20);
+
+    for ph in [PreHash::Sha256, PreHash::Sha512, PreHash::Shake128, PreHash::Shake256] {
+        let mut hasher = ph.hasher();
+        io::copy(&mut artifact(), &mut hasher).unwrap();
+        let signature = scheme.sign_prehashed(hasher, b"release", &sk).unwrap();
+
+        let mut hasher = ph.hasher();
+        io::copy(&mut artifact(), &mut hasher).unwrap();
+        assert!(scheme.verify_prehashed(hasher.clone(), b"release", &signature, &pk).unwrap(), "{ph:?}");
+        assert!(!scheme.verify_prehashed(hasher, b"", &signature, &pk).unwrap());
+
+        let mut other = ph.hasher();
+        io::copy(&mut io::repeat(0xa5).take((1 << 20) - 1), &mut other).unwrap();
+        assert!(!scheme.verify_prehashed(other, b"release", &signature, &pk).unwrap());
+    }
+
+    // The pre-hash function is part of what is signed
+    let mut hasher = PreHash::Sha512.hasher();
+    hasher.update(b"abc");
+    let signature = scheme.sign_prehashed(hasher, b"", &sk).unwrap();
+    let mut hasher = PreHash::Shake256.hasher();
+    hasher.update(b"abc");
+    assert!(!scheme.verify_prehashed(hasher, b"", &signature, &pk).unwrap());
+}
+
+#[test]
+fn test_keys_are_level_specific() {
+    let (pk44, _) = MlDsa44::new().keygen().unwrap();
+    let (pk65, _) = MlDsa65::new().keygen().unwrap();
+    assert_eq!(MlDsa65PublicKey::from_bytes(pk44.as_bytes()).err(), Some(SignatureError::InvalidPublicKey));
+    assert!(MlDsa65PublicKey::from_bytes(pk65.as_bytes()).is_ok());
+
+    assert_eq!(Verifier::algorithm(&MlDsa87::new()), SignatureAlgorithm::MlDsa87);
+    assert_eq!(SignatureAlgorithm::MlDsa44.id(), 0x0102);
+    assert_eq!("ml-dsa-65".parse::<SignatureAlgorithm>().unwrap(), SignatureAlgorithm::MlDsa65);
+    assert!(SignatureAlgorithm::MlDsa87.oid().is_some());
+}
+
+#[test]
+fn test_secret_key_debug_is_redacted() {
+    let (_, sk) = MlDsa44::new().keygen().unwrap();
+    let debug = format!("{sk:?}");
+    assert_eq!(debug, format!("MlDsa44SecretKey(<{} bytes redacted>)", MlDsa44::secret_key_bytes()));
+    let key_bytes = format!("{:?}", &sk.as_bytes()[..8]);
+    assert!(!debug.contains(key_bytes.trim_end_matches(']')));
+}
+
+#[test]
+fn test_repeated_hint_positions_are_rejected() {
+    const OMEGA: usize = 80;
+    const K: usize = 4;
+    let scheme = MlDsa44::new_deterministic();
+    let (pk, sk) = scheme.keygen_from_seed(&[7u8; 32]).unwrap();
+
+    // Find a signature whose hint has a spare slot and a non-empty first row
+    let (message, signature) = (0u32..)
+        .map(|i| {
+            let message = i.to_be_bytes();
+            (message, scheme.sign(&message, &sk).unwrap())
+        })
+        .find(|(_, sig)| {
+            let cuts = &sig.as_bytes()[sig.as_bytes().len() - K..];
+            cuts[0] > 0 && usize::from(cuts[K - 1]) < OMEGA
+        })
+        .unwrap();
+    assert!(scheme.verify(&message, &signature, &pk).unwrap());
+
+    // Repeat the first hint position: the decoded hint is unchanged, but the
+    // encoding is not the canonical one FIPS 204 requires
+    let mut bytes = signature.as_bytes().to_vec();
+    let hint_start = bytes.len() - OMEGA - K;
+    let used = usize::from(bytes[bytes.len() - 1]);
+    let first = bytes[hint_start];
+    bytes.copy_within(hint_start..hint_start + used, hint_start + 1);
+    bytes[hint_start] = first;
+    let cuts = bytes.len() - K;
+    for cut in &mut bytes[cuts..] {
+        *cut += 1;
+    }
+
+    let malleated = MlDsa44Signature::from_bytes(&bytes).unwrap();
+    assert!(!scheme.verify(&message, &malleated, &pk).unwrap());
+}
@@ -0,0 +1,111 @@
+use pq_core::kem::kem::PublicKey;
+use pq_core::kem::{Kem, KemAlgorithm, Kyber512, Kyber768, X25519MlKem768};
+use pq_core::multi_recipient::{self, MultiRecipientError};
+
+const STANZA_LEN: usize = 16 + 768 + 48;
+
+#[test]
+fn test_every_recipient_can_decrypt() {
+    let kem = Kyber512::new();
+    let keys: Vec<_> = (0..24).map(|_| kem.keygen().unwrap()).collect();
+    let recipients: Vec<PublicKey> = keys.iter().map(|(pk, _)| pk.clone()).collect();
+
+    let message = multi_recipient::encrypt(&recipients, b"doc-7", b"quarterly report").unwrap();
+    assert_eq!(&message[..4], b"PQMR");
+    assert_eq!(u16::from_be_bytes([message[5], message[6]]), KemAlgorithm::Kyber512.id());
+    assert_eq!(u16::from_be_bytes([message[7], message[8]]), 24);
+    // The payload is encrypted once, not per recipient
+    assert_eq!(message.len(), 9 + 24 * STANZA_LEN + b"quarterly report".len() + 16);
+
+    for (_, sk) in &keys {
+        assert_eq!(multi_recipient::decrypt(sk, b"doc-7", &message).unwrap(), b"quarterly report");
+    }
+}
+
+#[test]
+fn test_non_recipient_and_wrong_aad_rejected() {
+    let kem = Kyber512::new();
+    let (pk, sk) = kem.keygen().unwrap();
+    let (_, outsider) = kem.keygen().unwrap();
+    let message = multi_recipient::encrypt(&[pk], b"aad", b"payload").unwrap();
+
+    assert!(matches!(multi_recipient::decrypt(&outsider, b"aad", &message), Err(MultiRecipientError::NotARecipient)));
+    assert!(matches!(multi_recipient::decrypt(&sk, b"other", &message), Err(MultiRecipientError::DecryptionFailed)));
+}
+
+#[test]
+fn test_stanzas_reveal_nothing_about_recipients() {
+    let kem = Kyber512::new();
+    let (pk, _) = kem.keygen().unwrap();
+    let recipients = vec![pk.clone(), pk];
+
+    // Encrypting twice to the same keys yields unrelated stanzas, so key IDs
+    // cannot be matched against a public key or across messages
+    let a = multi_recipient::encrypt(&recipients, b"", b"x").unwrap();
+    let b = multi_recipient::encrypt(&recipients, b"", b"x").unwrap();
+    let ids = |m: &[u8]| -> Vec<Vec<u8>> { m[9..].chunks(STANZA_LEN).take(2).map(|s| s[..16].to_vec()).collect() };
+    let (ids_a, ids_b) = (ids(&a), ids(&b));
+    assert_ne!(ids_a[0], ids_a[1]);
+    assert!(ids_a.iter().all(|id| !ids_b.contains(id)));
+}
+
+#[test]
+fn test_stanza_tampering_detected() {
+    let kem = Kyber512::new();
+    let (alice_pk, alice_sk) = kem.keygen().unwrap();
+    let (bob_pk, _) = kem.keygen().unwrap();
+    let message = multi_recipient::encrypt(&[alice_pk, bob_pk], b"", b"payload").unwrap();
+
+    // Corrupting any stanza invalidates the payload for everyone
+    for stanza in 0..2 {
+        let mut tampered = message.clone();
+        tampered[9 + stanza * STANZA_LEN + STANZA_LEN - 1] ^= 1;
+        assert!(multi_recipient::decrypt(&alice_sk, b"", &tampered).is_err());
+    }
+
+    // Dropping a stanza is detected too
+    let mut dropped = message[..9].to_vec();
+    dropped[8] = 1;
+    for stanza in message[9..9 + 2 * STANZA_LEN].chunks(STANZA_LEN) {
+        let mut candidate = dropped.clone();
+        candidate.extend_from_slice(stanza);
+        candidate.extend_from_slice(&message[9 + 2 * STANZA_LEN..]);
+        assert!(multi_recipient::decrypt(&alice_sk, b"", &candidate).is_err());
+    }
+}
+
+#[test]
+fn test_invalid_inputs() {
+    assert!(matches!(multi_recipient::encrypt(&[], b"", b""), Err(MultiRecipientError::NoRecipients)));
+
+    let (pk512, sk512) = Kyber512::new().keygen().unwrap();
+    let (pk768, _) = Kyber768::new().keygen().unwrap();
+    assert!(matches!(
+        multi_recipient::encrypt(&[pk512.clone(), pk768], b"", b""),
+        Err(MultiRecipientError::MixedAlgorithms)
+    ));
+
+    let raw = PublicKey::from_vec(pk512.as_ref().to_vec());
+    assert!(matches!(multi_recipient::encrypt(std::slice::from_ref(&raw), b"", b""), Err(MultiRecipientError::MissingAlgorithm)));
+    let message = multi_recipient::encrypt_with(&Kyber512::new(), &[raw], b"", b"m").unwrap();
+    assert_eq!(multi_recipient::decrypt(&sk512, b"", &message).unwrap(), b"m");
+
+    assert!(matches!(multi_recipient::decrypt(&sk512, b"", &message[..20]), Err(MultiRecipientError::Truncated)));
+    let mut bad = message.clone();
+    bad[4] = 9;
+    assert!(matches!(multi_recipient::decrypt(&sk512, b"", &bad), Err(MultiRecipientError::UnsupportedVersion(9))));
+    let mut bad = message;
+    bad[0] = 0;
+    assert!(matches!(multi_recipient::decrypt(&sk512, b"", &bad), Err(MultiRecipientError::BadMagic)));
+}
+
+#[test]
+fn test_hybrid_recipients() {
+    let kem = X25519MlKem768::new();
+    let keys: Vec<_> = (0..3).map(|_| kem.keygen().unwrap()).collect();
+    let recipients: Vec<_> = keys.iter().map(|(pk, _)| pk.clone()).collect();
+    let message = multi_recipient::encrypt(&recipients, b"", b"hybrid").unwrap();
+    for (_, sk) in &keys {
+        assert_eq!(multi_recipient::decrypt(sk, b"", &message).unwrap(), b"hybrid");
+    }
+}
@@ -0,0 +1,84 @@
+//! Differential tests between the pure-Rust Kyber and the `pqcrypto-kyber`
+//! backend. Run with `cargo test --features native-kyber`.
+#![cfg(feature = "native-kyber")]
+
+use pq_core::kem::kem::Ciphertext;
+use pq_core::kem::{
+    HybridKem, Kem, Kyber512, Kyber768, Kyber1024, NativeKyber512, NativeKyber768, NativeKyber1024,
+    X25519Kyber768,
+};
+
+const ROUNDS: usize = 8;
+
+/// Runs keys and ciphertexts from each backend through the other.
+fn differential(native: &dyn Kem, backend: &dyn Kem) {
+    assert_eq!(native.algorithm(), backend.algorithm());
+    assert_eq!(native.public_key_bytes(), backend.public_key_bytes());
+    assert_eq!(native.secret_key_bytes(), backend.secret_key_bytes());
+    assert_eq!(native.ciphertext_bytes(), backend.ciphertext_bytes());
+    assert_eq!(native.shared_secret_bytes(), backend.shared_secret_bytes());
+
+    for _ in 0..ROUNDS {
+        // Native keys, C encapsulation
+        let (pk, sk) = native.keygen().unwrap();
+        let (ct, ss) = backend.encaps(&pk).unwrap();
+        assert_eq!(native.decaps(&ct, &sk).unwrap(), ss);
+        assert_eq!(backend.decaps(&ct, &sk).unwrap(), ss);
+
+        // C keys, native encapsulation
+        let (pk, sk) = backend.keygen().unwrap();
+        let (ct, ss) = native.encaps(&pk).unwrap();
+        assert_eq!(backend.decaps(&ct, &sk).unwrap(), ss);
+        assert_eq!(native.decaps(&ct, &sk).unwrap(), ss);
+
+        // Implicit rejection must produce the same pseudo-random secret
+        let mut tampered = ct.as_ref().to_vec();
+        tampered[0] ^= 1;
+        let tampered = Ciphertext::from_vec(tampered);
+        let rejected = backend.decaps(&tampered, &sk).unwrap();
+        assert_eq!(native.decaps(&tampered, &sk).unwrap(), rejected);
+        assert_ne!(rejected, ss);
+    }
+}
+
+#[test]
+fn test_native_kyber512_matches_backend() {
+    differential(&NativeKyber512::new(), &Kyber512::new());
+}
+
+#[test]
+fn test_native_kyber768_matches_backend() {
+    differential(&NativeKyber768::new(), &Kyber768::new());
+}
+
+#[test]
+fn test_native_kyber1024_matches_backend() {
+    differential(&NativeKyber1024::new(), &Kyber1024::new());
+}
+
+#[test]
+fn test_native_kyber_is_deterministic() {
+    let kem = NativeKyber768::new();
+    let seed = [3u8; 64];
+    let (pk1, sk1) = kem.keygen_from_seed(&seed).unwrap();
+    let (pk2, sk2) = kem.keygen_from_seed(&seed).unwrap();
+    assert_eq!(pk1, pk2);
+    assert_eq!(sk1, sk2);
+
+    let (ct1, ss1) = kem.encaps_deterministic(&pk1, &[9u8; 32]).unwrap();
+    let (ct2, ss2) = kem.encaps_deterministic(&pk1, &[9u8; 32]).unwrap();
+    assert_eq!(ct1, ct2);
+    assert_eq!(ss1, ss2);
+    assert_eq!(Kyber768::new().decaps(&ct1, &sk1).unwrap(), ss1);
+}
+
+#[test]
+fn test_native_kyber_in_hybrid() {
+    let native = HybridKem::<NativeKyber768>::new();
+    let backend = X25519Kyber768::new();
+    assert_eq!(native.algorithm(), backend.algorithm());
+
+    let (pk, sk) = backend.keygen().unwrap();
+    let (ct, ss) = native.encaps(&pk).unwrap();
+    assert_eq!(backend.decaps(&ct, &sk).unwrap(), ss);
+}
@@ -7,8 +7,9 @@
 /// - Sequence number validation
 /// - Acknowledgment mechanism
 /// - Replay protection
-
 use pq_core::{PQSession, BidirectionalSession, MessageEnvelope, MessageType};
+use pq_core::protocol::PQError;
+use pq_core::sig::dilithium::Dilithium;
 use sha2::{Sha256, Digest};
 
 fn hash_identity(name: &str) -> [u8; 32] {
@@ -105,8 +106,8 @@ fn test_message_ordering_monotonic_sequences() {
     }
 
     // Verify all sequences are monotonically increasing and unique
-    for i in 0..sequences.len() {
-        assert_eq!(sequences[i], i as u64, "Sequence at {} should be {}", i, i);
+    for (i, seq) in sequences.iter().enumerate() {
+        assert_eq!(*seq, i as u64, "Sequence at {} should be {}", i, i);
     }
 }
 
@@ -380,3 +381,79 @@ fn test_receiver_state_gap_detection() {
     assert!(missing.contains(&8));
     assert!(missing.contains(&9));
 }
+
+/// The envelope `session` recorded for `seq`, as it would go on the wire.
+fn sent_envelope(session: &BidirectionalSession, seq: u64) -> MessageEnvelope {
+    session.sender_state.lock().unwrap().unacked_messages[&seq].0.clone()
+}
+
+fn signed_pair() -> (BidirectionalSession, BidirectionalSession) {
+    let dilithium = Dilithium::new();
+    let (alice_pk, alice_sk) = dilithium.keygen().unwrap();
+    let (bob_pk, bob_sk) = dilithium.keygen().unwrap();
+    let alice_id = hash_identity("alice");
+    let bob_id = hash_identity("bob");
+
+    let alice = BidirectionalSession::new([0xAA; 32], [0xBB; 32], alice_id, bob_id)
+        .with_envelope_signing(alice_sk, bob_pk);
+    let bob = BidirectionalSession::new([0xBB; 32], [0xAA; 32], bob_id, alice_id)
+        .with_envelope_signing(bob_sk, alice_pk);
+    (alice, bob)
+}
+
+#[test]
+fn test_signed_envelopes() {
+    let (alice, bob) = signed_pair();
+
+    let seq = alice.send(b"signed", 1).unwrap();
+    let envelope = sent_envelope(&alice, seq);
+    assert_eq!(envelope.signature.len(), Dilithium::signature_bytes());
+
+    // Any change to a signed field is caught
+    let mut tampered = envelope.clone();
+    tampered.transaction_id = 2;
+    assert!(matches!(bob.receive(tampered), Err(PQError::InvalidSignature)));
+    let mut tampered = envelope.clone();
+    tampered.ack_up_to = Some(7);
+    assert!(matches!(bob.receive(tampered), Err(PQError::InvalidSignature)));
+    let mut tampered = envelope.clone();
+    tampered.missing_seqs = vec![3];
+    assert!(matches!(bob.receive(tampered), Err(PQError::InvalidSignature)));
+    let mut unsigned = envelope.clone();
+    unsigned.signature.clear();
+    assert!(matches!(bob.receive(unsigned), Err(PQError::InvalidSignature)));
+
+    bob.receive(envelope).unwrap();
+    assert_eq!(bob.get_next_complete_transaction(), Some((1, vec![b"signed".to_vec()])));
+}
+
+#[test]
+fn test_receive_batch_verifies_together() {
+    let (alice, bob) = signed_pair();
+
+    let mut envelopes: Vec<MessageEnvelope> = (0..8u8)
+        .map(|i| {
+            let seq = alice.send(&[i; 32], i as u128).unwrap();
+            sent_envelope(&alice, seq)
+        })
+        .collect();
+    envelopes.reverse();
+
+    // Index 2 is seq 5 with a forged payload, index 6 is seq 1 from a stranger
+    envelopes[2].payload = b"forged".to_vec();
+    envelopes[6].sender_id = hash_identity("mallory");
+
+    let rejected = bob.receive_batch(envelopes).unwrap();
+    assert_eq!(rejected, vec![2, 6]);
+
+    // Seq 0 is delivered; the rest wait behind the rejected seq 1
+    let (ack_up_to, missing) = bob.get_ack_state().unwrap();
+    assert_eq!(ack_up_to, 0);
+    assert_eq!(&missing[..2], &[1, 5]);
+
+    // A retransmitted envelope is accepted
+    let rejected = bob.receive_batch(vec![sent_envelope(&alice, 1)]).unwrap();
+    assert!(rejected.is_empty());
+    assert_eq!(bob.get_ack_state().unwrap().0, 1);
+    assert_eq!(bob.get_next_complete_transaction(), Some((0, vec![vec![0; 32]])));
+}
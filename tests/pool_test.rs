@@ -0,0 +1,105 @@
+use pq_core::kem::pool::{KeyPool, PoolConfig};
+use pq_core::kem::{Kem, KemAlgorithm, Kyber512, MlKem768};
+use pq_core::PQSession;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Polls until the pool holds `count` keypairs.
+fn wait_for(pool: &KeyPool, count: usize) {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while pool.available() < count {
+        assert!(Instant::now() < deadline, "pool stuck at {} of {count}", pool.available());
+        std::thread::sleep(Duration::from_millis(5));
+    }
+}
+
+#[test]
+fn test_pool_fills_and_refills() {
+    let pool = KeyPool::new(Box::new(Kyber512::new()), PoolConfig { size: 8, refill_threshold: 3 });
+    assert_eq!(pool.algorithm(), KemAlgorithm::Kyber512);
+    wait_for(&pool, 8);
+    std::thread::sleep(Duration::from_millis(20));
+    assert_eq!(pool.available(), 8, "pool grew past its size");
+
+    // Above the threshold nothing is generated
+    for _ in 0..4 {
+        pool.take().unwrap();
+    }
+    std::thread::sleep(Duration::from_millis(20));
+    assert_eq!(pool.available(), 4);
+
+    // Dropping to the threshold triggers a refill back to full size
+    pool.take().unwrap();
+    wait_for(&pool, 8);
+}
+
+#[test]
+fn test_pooled_keypairs_are_valid_and_unique() {
+    let kem = MlKem768::new();
+    let pool = KeyPool::new(Box::new(MlKem768::new()), PoolConfig { size: 4, refill_threshold: 1 });
+    wait_for(&pool, 4);
+
+    let mut seen = Vec::new();
+    for _ in 0..10 {
+        let (pk, sk) = pool.take().unwrap();
+        assert_eq!(pk.algorithm(), Some(KemAlgorithm::MlKem768));
+        let (ct, ss) = kem.encaps(&pk).unwrap();
+        assert_eq!(kem.decaps(&ct, &sk).unwrap(), ss);
+        assert!(!seen.contains(&pk));
+        seen.push(pk);
+    }
+}
+
+#[test]
+fn test_shutdown_wipes_pool_and_falls_back_to_inline_keygen() {
+    let pool = KeyPool::new(Box::new(Kyber512::new()), PoolConfig::default());
+    wait_for(&pool, 1);
+    pool.shutdown();
+    assert_eq!(pool.available(), 0);
+
+    // Still usable, without the background thread
+    pool.take().unwrap();
+    std::thread::sleep(Duration::from_millis(20));
+    assert_eq!(pool.available(), 0);
+    pool.shutdown();
+}
+
+#[test]
+#[should_panic(expected = "refill threshold")]
+fn test_invalid_config_rejected() {
+    KeyPool::new(Box::new(Kyber512::new()), PoolConfig { size: 4, refill_threshold: 4 });
+}
+
+#[test]
+fn test_sessions_share_a_pool() {
+    let pool = Arc::new(KeyPool::new(Box::new(Kyber512::new()), PoolConfig { size: 16, refill_threshold: 4 }));
+    wait_for(&pool, 16);
+
+    for _ in 0..3 {
+        let mut alice = PQSession::with_key_pool(Arc::clone(&pool));
+        let mut bob = PQSession::with_key_pool(Arc::clone(&pool));
+
+        let hello = alice.initiate_handshake().unwrap();
+        let reply = bob.process_handshake(hello).unwrap();
+        alice.complete_handshake(reply).unwrap();
+
+        let ct = bob.encrypt(b"pooled");
+        assert_eq!(alice.decrypt(&ct).unwrap(), b"pooled");
+    }
+}
+
+#[test]
+fn test_handshake_takes_one_keypair_per_side() {
+    let pool = Arc::new(KeyPool::new(Box::new(Kyber512::new()), PoolConfig { size: 16, refill_threshold: 4 }));
+    wait_for(&pool, 16);
+
+    // Creating sessions draws nothing; each side takes its ephemeral key
+    // only when it joins the handshake
+    let mut alice = PQSession::with_key_pool(Arc::clone(&pool));
+    let mut bob = PQSession::with_key_pool(Arc::clone(&pool));
+    assert_eq!(pool.available(), 16);
+
+    let reply = bob.process_handshake(alice.initiate_handshake().unwrap()).unwrap();
+    alice.complete_handshake(reply).unwrap();
+    assert_eq!(pool.available(), 14);
+}
@@ -0,0 +1,72 @@
+use pq_core::PQSession;
+use pq_core::kem::kem::KemError;
+use pq_core::kem::{registry, KemAlgorithm};
+use pq_core::sig::SignatureAlgorithm;
+use pq_core::sig::dilithium::DilithiumError;
+use std::collections::HashSet;
+
+#[test]
+fn test_kem_ids_and_names_roundtrip() {
+    let mut ids = HashSet::new();
+    for alg in KemAlgorithm::ALL {
+        assert!(ids.insert(alg.id()), "duplicate id for {alg}");
+        assert_eq!(KemAlgorithm::try_from(alg.id()).unwrap(), alg);
+        assert_eq!(alg.to_string().parse::<KemAlgorithm>().unwrap(), alg);
+        assert_eq!(alg.name().to_lowercase().parse::<KemAlgorithm>().unwrap(), alg);
+    }
+}
+
+#[test]
+fn test_kem_ids_are_stable() {
+    // These values are persisted and sent on the wire; never change them
+    assert_eq!(KemAlgorithm::Kyber512.id(), 0x0001);
+    assert_eq!(KemAlgorithm::MlKem768.id(), 0x0102);
+    assert_eq!(KemAlgorithm::X25519Kyber1024.id(), 0x0203);
+    assert_eq!(KemAlgorithm::X25519MlKem512.id(), 0x0301);
+    assert_eq!(KemAlgorithm::X25519MlKem768.name(), "X25519+ML-KEM-768");
+}
+
+#[test]
+fn test_registry_instantiates_every_kem() {
+    for alg in KemAlgorithm::ALL {
+        let kem = registry::new_kem(alg);
+        assert_eq!(kem.algorithm(), alg);
+
+        let (pk, sk) = kem.keygen().unwrap();
+        let (ct, ss) = kem.encaps(&pk).unwrap();
+        assert_eq!(kem.decaps(&ct, &sk).unwrap(), ss);
+
+        assert_eq!(registry::kem_from_id(alg.id()).unwrap().algorithm(), alg);
+        assert_eq!(registry::kem_from_name(alg.name()).unwrap().algorithm(), alg);
+    }
+}
+
+#[test]
+fn test_registry_rejects_unknown_identifiers() {
+    assert!(matches!(registry::kem_from_id(0xffff), Err(KemError::UnknownAlgorithm(_))));
+    assert!(matches!(registry::kem_from_name("Kyber9000"), Err(KemError::UnknownAlgorithm(_))));
+}
+
+#[test]
+fn test_signature_algorithm_identifiers() {
+    for alg in SignatureAlgorithm::ALL {
+        assert_eq!(SignatureAlgorithm::try_from(alg.id()).unwrap(), alg);
+        assert_eq!(alg.to_string().parse::<SignatureAlgorithm>().unwrap(), alg);
+    }
+    assert_eq!(SignatureAlgorithm::Dilithium2.id(), 0x0002);
+    assert!(matches!("RSA".parse::<SignatureAlgorithm>(), Err(DilithiumError::UnknownAlgorithm(_))));
+}
+
+#[test]
+fn test_session_from_configured_algorithm() {
+    let alg: KemAlgorithm = "x25519+ml-kem-768".parse().unwrap();
+    let mut alice = PQSession::with_kem_algorithm(alg);
+    let mut bob = PQSession::with_kem_algorithm(alg);
+
+    let handshake = alice.initiate_handshake().unwrap();
+    let response = bob.process_handshake(handshake).unwrap();
+    alice.complete_handshake(response).unwrap();
+
+    let ct = alice.encrypt(b"configured");
+    assert_eq!(bob.decrypt(&ct).unwrap(), b"configured");
+}
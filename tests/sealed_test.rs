@@ -0,0 +1,80 @@
+use pq_core::kem::kem::PublicKey;
+use pq_core::kem::{registry, Kem, KemAlgorithm, Kyber512, MlKem768};
+use pq_core::sealed::{self, SealError};
+
+#[test]
+fn test_seal_open_roundtrip_all_kems() {
+    for algorithm in KemAlgorithm::ALL {
+        let (pk, sk) = registry::new_kem(algorithm).keygen().unwrap();
+        let boxed = sealed::seal(&pk, b"batch-7", b"offline payload").unwrap();
+        assert_eq!(boxed.len(), sealed::overhead(algorithm) + b"offline payload".len());
+        assert_eq!(&boxed[..4], b"PQSB");
+        assert_eq!(boxed[4], sealed::VERSION);
+        assert_eq!(u16::from_be_bytes([boxed[5], boxed[6]]), algorithm.id());
+        assert_eq!(sealed::open(&sk, b"batch-7", &boxed).unwrap(), b"offline payload", "{algorithm}");
+    }
+}
+
+#[test]
+fn test_seal_is_randomized() {
+    let (pk, sk) = MlKem768::new().keygen().unwrap();
+    let a = sealed::seal(&pk, b"", b"same").unwrap();
+    let b = sealed::seal(&pk, b"", b"same").unwrap();
+    assert_ne!(a, b);
+    assert_eq!(sealed::open(&sk, b"", &a).unwrap(), sealed::open(&sk, b"", &b).unwrap());
+}
+
+#[test]
+fn test_open_rejects_wrong_aad_key_or_tampering() {
+    let kem = MlKem768::new();
+    let (pk, sk) = kem.keygen().unwrap();
+    let (_, other_sk) = kem.keygen().unwrap();
+    let boxed = sealed::seal(&pk, b"aad", b"secret").unwrap();
+
+    assert!(matches!(sealed::open(&sk, b"other", &boxed), Err(SealError::DecryptionFailed)));
+    assert!(matches!(sealed::open(&other_sk, b"aad", &boxed), Err(SealError::DecryptionFailed)));
+
+    // Flip one bit in the KEM ciphertext and in the AEAD ciphertext
+    for index in [10, boxed.len() - 1] {
+        let mut tampered = boxed.clone();
+        tampered[index] ^= 1;
+        assert!(matches!(sealed::open(&sk, b"aad", &tampered), Err(SealError::DecryptionFailed)));
+    }
+}
+
+#[test]
+fn test_open_rejects_malformed_boxes() {
+    let (pk, sk) = MlKem768::new().keygen().unwrap();
+    let boxed = sealed::seal(&pk, b"", b"x").unwrap();
+
+    assert!(matches!(sealed::open(&sk, b"", &boxed[..5]), Err(SealError::Truncated)));
+    assert!(matches!(sealed::open(&sk, b"", &boxed[..100]), Err(SealError::Truncated)));
+
+    let mut bad = boxed.clone();
+    bad[0] = b'X';
+    assert!(matches!(sealed::open(&sk, b"", &bad), Err(SealError::BadMagic)));
+
+    let mut bad = boxed.clone();
+    bad[4] = 2;
+    assert!(matches!(sealed::open(&sk, b"", &bad), Err(SealError::UnsupportedVersion(2))));
+
+    let mut bad = boxed.clone();
+    bad[5..7].copy_from_slice(&0xffffu16.to_be_bytes());
+    assert!(matches!(sealed::open(&sk, b"", &bad), Err(SealError::Kem(_))));
+
+    // Header names a different KEM than the recipient key belongs to
+    let mut bad = boxed;
+    bad[5..7].copy_from_slice(&KemAlgorithm::Kyber768.id().to_be_bytes());
+    assert!(sealed::open(&sk, b"", &bad).is_err());
+}
+
+#[test]
+fn test_seal_untagged_key() {
+    let kem = Kyber512::new();
+    let (pk, sk) = kem.keygen().unwrap();
+    let raw = PublicKey::from_vec(pk.as_ref().to_vec());
+    assert!(matches!(sealed::seal(&raw, b"", b"m"), Err(SealError::MissingAlgorithm)));
+
+    let boxed = sealed::seal_with(&kem, &raw, b"", b"m").unwrap();
+    assert_eq!(sealed::open(&sk, b"", &boxed).unwrap(), b"m");
+}
@@ -0,0 +1,126 @@
+use pq_core::kem::Kyber768;
+use pq_core::protocol::PQSession;
+use pq_core::sig::composite::Dilithium2Ed25519;
+use pq_core::sig::dilithium::{
+    Dilithium, Dilithium3, Dilithium3PublicKey, Dilithium3Signature, Dilithium5, Dilithium5PublicKey, DilithiumPublicKey,
+};
+use pq_core::sig::falcon::Falcon512;
+use pq_core::sig::ml_dsa::MlDsa65;
+use pq_core::sig::slh_dsa::SlhDsaShake128f;
+use pq_core::sig::{SignatureAlgorithm, SignatureError, Signer, Verifier};
+
+/// Exercises a scheme purely through the traits.
+fn check_scheme<S: Signer>(scheme: &S) {
+    let (pk, sk) = scheme.keygen().unwrap();
+    assert_eq!(pk.as_ref().len(), scheme.public_key_bytes());
+
+    let signature = scheme.sign(b"generic message", &sk).unwrap();
+    assert!(scheme.verify(b"generic message", &signature, &pk).unwrap());
+    assert!(!scheme.verify(b"other message", &signature, &pk).unwrap());
+
+    assert_eq!(signature.as_ref().len(), scheme.signature_bytes());
+
+    let pk = scheme.public_key_from_bytes(pk.as_ref()).unwrap();
+    let signature = scheme.signature_from_bytes(signature.as_ref()).unwrap();
+    assert!(scheme.verify(b"generic message", &signature, &pk).unwrap());
+
+    assert_eq!(scheme.public_key_from_bytes(&[0u8; 7]).err(), Some(SignatureError::InvalidPublicKey));
+}
+
+#[test]
+fn test_dilithium_through_traits() {
+    let scheme = Dilithium::new();
+    assert_eq!(Verifier::algorithm(&scheme), SignatureAlgorithm::Dilithium2);
+    assert_eq!(Signer::secret_key_bytes(&scheme), Dilithium::secret_key_bytes());
+    check_scheme(&scheme);
+}
+
+#[test]
+fn test_dilithium3_and_dilithium5_through_traits() {
+    let (d3, d5) = (Dilithium3::new(), Dilithium5::new());
+    assert_eq!(Verifier::algorithm(&d3), SignatureAlgorithm::Dilithium3);
+    assert_eq!(Verifier::algorithm(&d5), SignatureAlgorithm::Dilithium5);
+    assert_eq!(
+        (Dilithium3::public_key_bytes(), Dilithium3::secret_key_bytes(), Dilithium3::signature_bytes()),
+        (1952, 4032, 3309)
+    );
+    assert_eq!(
+        (Dilithium5::public_key_bytes(), Dilithium5::secret_key_bytes(), Dilithium5::signature_bytes()),
+        (2592, 4896, 4627)
+    );
+    check_scheme(&d3);
+    check_scheme(&d5);
+}
+
+#[test]
+fn test_keys_of_other_levels_are_rejected() {
+    let (pk2, _) = Dilithium::new().keygen().unwrap();
+    let (pk3, sk3) = Dilithium3::new().keygen().unwrap();
+    let (pk5, _) = Dilithium5::new().keygen().unwrap();
+
+    assert!(DilithiumPublicKey::from_bytes(pk3.as_bytes()).is_err());
+    assert!(Dilithium3PublicKey::from_bytes(pk2.as_bytes()).is_err());
+    assert!(Dilithium3PublicKey::from_bytes(pk5.as_bytes()).is_err());
+    assert!(Dilithium5PublicKey::from_bytes(pk3.as_bytes()).is_err());
+    assert!(matches!(
+        Dilithium3Signature::from_bytes(&[0u8; 2420]),
+        Err(SignatureError::InvalidSignature)
+    ));
+
+    // Level 3 keys seeded from the same bytes are reproducible
+    let seed = [3u8; 32];
+    let (a, _) = Dilithium3::new().keygen_from_seed(&seed).unwrap();
+    let (b, _) = Dilithium3::new().keygen_from_seed(&seed).unwrap();
+    assert_eq!(a.as_bytes(), b.as_bytes());
+    assert_eq!(sk3.as_bytes().len(), Dilithium3::secret_key_bytes());
+
+    // Identity keys never show up in logs
+    assert_eq!(format!("{sk3:?}"), "Dilithium3SecretKey(<4032 bytes redacted>)");
+}
+
+/// Runs a full handshake with `scheme` as both identities, then a message
+/// each way.
+fn session_roundtrip<S: Signer + Clone>(scheme: S) {
+    let mut alice = PQSession::with_signer(Box::new(Kyber768::new()), scheme.clone());
+    let mut bob = PQSession::with_signer(Box::new(Kyber768::new()), scheme.clone());
+
+    let hello = alice.initiate_handshake().unwrap();
+    assert_eq!(hello.sig_pk.as_ref().len(), scheme.public_key_bytes());
+    let reply = bob.process_handshake(hello).unwrap();
+    alice.complete_handshake(reply).unwrap();
+
+    let ct = alice.encrypt(b"to bob");
+    assert_eq!(bob.decrypt(&ct).unwrap(), b"to bob");
+    let ct = bob.encrypt(b"to alice");
+    assert_eq!(alice.decrypt(&ct).unwrap(), b"to alice");
+}
+
+#[test]
+fn test_session_with_every_signature_scheme() {
+    session_roundtrip(Dilithium::new());
+    session_roundtrip(Dilithium5::new());
+    session_roundtrip(MlDsa65::new());
+    session_roundtrip(SlhDsaShake128f::new());
+    session_roundtrip(Falcon512::new());
+    session_roundtrip(Dilithium2Ed25519::new());
+}
+
+#[test]
+fn test_detached_and_attached_signatures() {
+    let sig = Dilithium::new();
+    let (pk, sk) = sig.keygen().unwrap();
+    let message = b"release-1.4.2.tar.gz";
+
+    let detached = sig.sign(message, &sk).unwrap();
+    assert_eq!(detached.as_bytes().len(), Dilithium::signature_bytes());
+
+    let attached = sig.sign_attached(message, &sk).unwrap();
+    assert_eq!(attached.len(), Dilithium::signature_bytes() + message.len());
+    assert_eq!(&attached[..Dilithium::signature_bytes()], detached.as_bytes());
+    assert_eq!(sig.open_attached(&attached, &pk).unwrap(), message);
+
+    let mut tampered = attached.clone();
+    *tampered.last_mut().unwrap() ^= 1;
+    assert_eq!(sig.open_attached(&tampered, &pk), Err(SignatureError::VerificationError));
+    assert_eq!(sig.open_attached(&attached[..100], &pk), Err(SignatureError::InvalidSignature));
+}
@@ -0,0 +1,112 @@
+use pq_core::sig::slh_dsa::{
+    SlhDsaSha2_128s, SlhDsaSha2_192f, SlhDsaSha2_256f, SlhDsaShake128f, SlhDsaShake128fPublicKey,
+    SlhDsaShake128fSignature, SlhDsaShake256s, MAX_CONTEXT_BYTES,
+};
+use pq_core::sig::{SignatureAlgorithm, SignatureError, Signer, Verifier};
+
+#[test]
+fn test_sign_and_verify_with_context() {
+    let scheme = SlhDsaShake128f::new();
+    let (pk, sk) = scheme.keygen().unwrap();
+
+    let signature = scheme.sign_with_context(b"root key", b"ca", &sk).unwrap();
+    assert_eq!(signature.as_bytes().len(), SlhDsaShake128f::signature_bytes());
+    assert!(scheme.verify_with_context(b"root key", b"ca", &signature, &pk).unwrap());
+    assert!(!scheme.verify_with_context(b"root key", b"device", &signature, &pk).unwrap());
+    assert!(!scheme.verify(b"root key", &signature, &pk).unwrap());
+
+    // Hedged signing draws fresh randomness for every signature
+    let again = scheme.sign_with_context(b"root key", b"ca", &sk).unwrap();
+    assert_ne!(signature.as_bytes(), again.as_bytes());
+
+    let long = [0u8; MAX_CONTEXT_BYTES + 1];
+    assert_eq!(scheme.sign_with_context(b"root key", &long, &sk).err(), Some(SignatureError::ContextTooLong));
+    assert_eq!(
+        scheme.verify_with_context(b"root key", &long, &signature, &pk).err(),
+        Some(SignatureError::ContextTooLong)
+    );
+}
+
+#[test]
+fn test_deterministic_signing_and_seeded_keys() {
+    let scheme = SlhDsaShake128f::new_deterministic();
+    let seed = [9u8; 48];
+    assert_eq!(SlhDsaShake128f::seed_bytes(), seed.len());
+
+    let (pk, sk) = scheme.keygen_from_seed(&seed).unwrap();
+    let (pk2, sk2) = scheme.keygen_from_seed(&seed).unwrap();
+    assert_eq!(pk.as_bytes(), pk2.as_bytes());
+    assert_eq!(sk.as_bytes(), sk2.as_bytes());
+    // The secret key ends with the public key
+    assert_eq!(&sk.as_bytes()[32..], pk.as_bytes());
+
+    let signature = scheme.sign(b"message", &sk).unwrap();
+    assert_eq!(signature.as_bytes(), scheme.sign(b"message", &sk2).unwrap().as_bytes());
+    assert_eq!(scheme.keygen_from_seed(&seed[1..]).err(), Some(SignatureError::InvalidSeed));
+}
+
+#[test]
+fn test_tampered_signature_fails() {
+    let scheme = SlhDsaShake128f::new();
+    let (pk, sk) = scheme.keygen().unwrap();
+    let signature = scheme.sign(b"message", &sk).unwrap();
+
+    let mut bytes = signature.as_bytes().to_vec();
+    bytes[100] ^= 1;
+    let tampered = SlhDsaShake128fSignature::from_bytes(&bytes).unwrap();
+    assert!(!scheme.verify(b"message", &tampered, &pk).unwrap());
+
+    assert_eq!(
+        SlhDsaShake128fSignature::from_bytes(&bytes[1..]).err(),
+        Some(SignatureError::InvalidSignature)
+    );
+}
+
+/// Every parameter set reports its sizes through the `Verifier` and
+/// `Signer` traits, so handshake code can budget without knowing the scheme.
+fn sizes<S: Signer>(scheme: &S) -> (usize, usize, usize) {
+    (scheme.public_key_bytes(), scheme.secret_key_bytes(), scheme.signature_bytes())
+}
+
+#[test]
+fn test_parameter_set_sizes() {
+    assert_eq!(sizes(&SlhDsaSha2_128s::new()), (32, 64, 7856));
+    assert_eq!(sizes(&SlhDsaShake128f::new()), (32, 64, 17088));
+    assert_eq!(sizes(&SlhDsaSha2_192f::new()), (48, 96, 35664));
+    assert_eq!(sizes(&SlhDsaShake256s::new()), (64, 128, 29792));
+    assert_eq!(sizes(&SlhDsaSha2_256f::new()), (64, 128, 49856));
+}
+
+#[test]
+fn test_secret_key_debug_is_redacted() {
+    let (_, sk) = SlhDsaShake128f::new().keygen_from_seed(&[9; 48]).unwrap();
+    assert_eq!(format!("{sk:?}"), "SlhDsaShake128fSecretKey(<64 bytes redacted>)");
+}
+
+#[test]
+fn test_keys_are_set_specific() {
+    let (pk128s, _) = SlhDsaSha2_128s::new().keygen_from_seed(&[1; 48]).unwrap();
+    // Same length, different set: accepted by length, rejected by verification
+    let pk = SlhDsaShake128fPublicKey::from_bytes(pk128s.as_bytes()).unwrap();
+    let (_, sk) = SlhDsaShake128f::new().keygen().unwrap();
+    let signature = SlhDsaShake128f::new().sign(b"m", &sk).unwrap();
+    assert!(!SlhDsaShake128f::new().verify(b"m", &signature, &pk).unwrap());
+
+    let (pk256, _) = SlhDsaShake256s::new().keygen_from_seed(&[1; 96]).unwrap();
+    assert_eq!(
+        SlhDsaShake128fPublicKey::from_bytes(pk256.as_bytes()).err(),
+        Some(SignatureError::InvalidPublicKey)
+    );
+
+    assert_eq!(Verifier::algorithm(&SlhDsaShake128f::new()), SignatureAlgorithm::SlhDsaShake128f);
+    assert_eq!(SignatureAlgorithm::SlhDsaSha2_128s.id(), 0x0201);
+    assert_eq!(SignatureAlgorithm::SlhDsaShake256f.id(), 0x020c);
+    assert_eq!(
+        "slh-dsa-shake-128f".parse::<SignatureAlgorithm>().unwrap(),
+        SignatureAlgorithm::SlhDsaShake128f
+    );
+    assert_eq!(
+        SignatureAlgorithm::SlhDsaSha2_128s.oid().map(|oid| oid.to_string()).as_deref(),
+        Some("2.16.840.1.101.3.4.3.20")
+    );
+}
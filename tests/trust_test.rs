@@ -0,0 +1,203 @@
+use pq_core::cert::{CertificateError, CertificateParams, Certificate, KeyUsage, TrustAnchors, Validity};
+use pq_core::kem::{Kem, Kyber512, MlKem768};
+use pq_core::protocol::{PQError, PQSession};
+use pq_core::sig::dilithium::{Dilithium, DilithiumPublicKey, DilithiumSecretKey};
+use pq_core::sig::SignatureAlgorithm;
+use pq_core::trust::{fingerprint, AnySubject, PinnedKeys, TofuStore, TrustError};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+fn identity() -> (DilithiumPublicKey, DilithiumSecretKey) {
+    Dilithium::new().keygen().unwrap()
+}
+
+/// Runs a handshake from a fresh session using `server_identity` to `client`.
+fn connect(client: &mut PQSession, server_identity: &(DilithiumPublicKey, DilithiumSecretKey)) -> Result<(), PQError> {
+    let (pk, sk) = server_identity.clone();
+    let mut server = PQSession::new().with_identity(pk, sk);
+    let hello = client.initiate_handshake()?;
+    let reply = server.process_handshake(hello)?;
+    client.complete_handshake(reply)
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    let nanos = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_nanos();
+    std::env::temp_dir().join(format!("pq-core-{name}-{}-{nanos}", std::process::id()))
+}
+
+#[test]
+fn test_pinned_keys() {
+    let scheme = Dilithium::new();
+    let server = identity();
+    let other = identity();
+    let pins = || PinnedKeys::new().pin("server.example", &scheme, &server.0);
+
+    let mut client = PQSession::new().with_trust_store(pins()).with_expected_peer("server.example");
+    connect(&mut client, &server).unwrap();
+    assert!(client.peer_certificate().is_none());
+
+    let mut client = PQSession::new().with_trust_store(pins()).with_expected_peer("server.example");
+    assert_eq!(connect(&mut client, &other).err(), Some(PQError::IdentityChanged));
+
+    // No pin for this name, and no name at all
+    let mut client = PQSession::new().with_trust_store(pins()).with_expected_peer("other.example");
+    assert_eq!(connect(&mut client, &server).err(), Some(PQError::UnknownIdentity));
+    let mut client = PQSession::new().with_trust_store(pins());
+    assert_eq!(connect(&mut client, &server).err(), Some(PQError::UnknownIdentity));
+
+    // Pinned for any name
+    let mut client = PQSession::new().with_trust_store(PinnedKeys::new().pin_any(&scheme, &server.0));
+    connect(&mut client, &server).unwrap();
+
+    // A fresh key for every session is never trusted
+    let mut client = PQSession::new().with_trust_store(pins()).with_expected_peer("server.example");
+    let mut server = PQSession::new();
+    let reply = server.process_handshake(client.initiate_handshake().unwrap()).unwrap();
+    assert_eq!(client.complete_handshake(reply).err(), Some(PQError::IdentityChanged));
+}
+
+#[test]
+fn test_reply_is_bound_to_the_transcript() {
+    let scheme = Dilithium::new();
+    let server = identity();
+    let pins = || PinnedKeys::new().pin("server.example", &scheme, &server.0);
+    let (pk, sk) = server.clone();
+    let mut genuine = PQSession::new().with_identity(pk, sk);
+
+    // A reply whose ciphertext an attacker replaced with one of their own
+    let mut victim = PQSession::new().with_trust_store(pins()).with_expected_peer("server.example");
+    let hello = victim.initiate_handshake().unwrap();
+    let (forged, _) = Kyber512::new().encaps(&hello.kem_pk).unwrap();
+    let mut reply = genuine.process_handshake(hello).unwrap();
+    reply.ciphertext = forged;
+    assert_eq!(victim.complete_handshake(reply).err(), Some(PQError::InvalidSignature));
+
+    // A genuine reply recorded from an earlier session
+    let mut earlier = PQSession::new();
+    let recorded = genuine.process_handshake(earlier.initiate_handshake().unwrap()).unwrap();
+    let mut victim = PQSession::new().with_trust_store(pins()).with_expected_peer("server.example");
+    victim.initiate_handshake().unwrap();
+    assert_eq!(victim.complete_handshake(recorded).err(), Some(PQError::InvalidSignature));
+
+    // A tampered nonce in the hello
+    let mut client = PQSession::new();
+    let mut hello = client.initiate_handshake().unwrap();
+    hello.nonce ^= 1;
+    assert_eq!(genuine.process_handshake(hello).err(), Some(PQError::InvalidSignature));
+}
+
+#[test]
+fn test_trust_on_first_use() {
+    let path = temp_path("tofu");
+    let server = identity();
+
+    let store = TofuStore::open(&path).unwrap();
+    let mut client = PQSession::new().with_trust_store(store).with_expected_peer("server.example");
+    connect(&mut client, &server).unwrap();
+
+    // Recorded on disk and trusted after reopening
+    let store = TofuStore::open(&path).unwrap();
+    assert_eq!(store.get("server.example"), Some(fingerprint(SignatureAlgorithm::Dilithium2, server.0.as_bytes())));
+    let mut client = PQSession::new().with_trust_store(store).with_expected_peer("server.example");
+    connect(&mut client, &server).unwrap();
+
+    // A changed key is refused until the old one is forgotten
+    let rotated = identity();
+    let mut client = PQSession::new().with_trust_store(TofuStore::open(&path).unwrap()).with_expected_peer("server.example");
+    assert_eq!(connect(&mut client, &rotated).err(), Some(PQError::IdentityChanged));
+
+    let mut store = TofuStore::open(&path).unwrap();
+    store.forget("server.example").unwrap();
+    assert!(TofuStore::open(&path).unwrap().get("server.example").is_none());
+    let mut client = PQSession::new().with_trust_store(store).with_expected_peer("server.example");
+    connect(&mut client, &rotated).unwrap();
+
+    // Without a name there is nothing to record the key under
+    let mut client = PQSession::new().with_trust_store(TofuStore::open(&path).unwrap());
+    assert_eq!(connect(&mut client, &server).err(), Some(PQError::Trust(TrustError::MissingPeerName)));
+
+    std::fs::write(&path, "server.example not-hex\n").unwrap();
+    assert!(TofuStore::open(&path).is_err());
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_unrecorded_key_is_not_trusted() {
+    // The directory does not exist, so nothing can be written
+    let path = temp_path("missing-dir").join("known_peers");
+    let server = identity();
+    let store = Arc::new(Mutex::new(TofuStore::open(&path).unwrap()));
+
+    for _ in 0..2 {
+        let mut client = PQSession::new().with_trust_store(store.clone()).with_expected_peer("server.example");
+        assert!(matches!(connect(&mut client, &server).err(), Some(PQError::Trust(TrustError::Storage(_)))));
+    }
+    assert!(store.lock().unwrap().get("server.example").is_none());
+}
+
+#[test]
+fn test_shared_store() {
+    let path = temp_path("shared");
+    let store = Arc::new(Mutex::new(TofuStore::open(&path).unwrap()));
+    let server = identity();
+
+    let mut first = PQSession::new().with_trust_store(store.clone()).with_expected_peer("server.example");
+    connect(&mut first, &server).unwrap();
+
+    // The second session sees what the first recorded
+    let mut second = PQSession::new().with_trust_store(store.clone()).with_expected_peer("server.example");
+    assert_eq!(connect(&mut second, &identity()).err(), Some(PQError::IdentityChanged));
+    assert!(store.lock().unwrap().get("server.example").is_some());
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_certificate_anchors_require_subject() {
+    let scheme = Dilithium::new();
+    let validity = Validity::for_days(1);
+    let (root_pk, root_sk) = scheme.keygen().unwrap();
+    let root = Certificate::self_signed(
+        &scheme,
+        CertificateParams::new("Root CA", validity, KeyUsage::CERT_SIGN),
+        &root_pk,
+        &root_sk,
+    )
+    .unwrap();
+    let (leaf_pk, leaf_sk) = scheme.keygen().unwrap();
+    let leaf = Certificate::issue(
+        &scheme,
+        CertificateParams::new("server.example", validity, KeyUsage::DIGITAL_SIGNATURE),
+        &leaf_pk,
+        &root,
+        &root_sk,
+    )
+    .unwrap();
+
+    let handshake = |expected: Option<&str>, any_subject: bool| {
+        let anchors = TrustAnchors::new(Dilithium::new()).with_anchor(root.clone());
+        let mut client = PQSession::with_signer(Box::new(MlKem768::new()), Dilithium::new());
+        client = if any_subject { client.with_trust_store(AnySubject(anchors)) } else { client.with_trust_store(anchors) };
+        if let Some(expected) = expected {
+            client = client.with_expected_peer(expected);
+        }
+        let mut server = PQSession::with_signer(Box::new(MlKem768::new()), Dilithium::new())
+            .with_certificate_chain(vec![leaf.clone()], leaf_sk.clone())
+            .unwrap();
+        let reply = server.process_handshake(client.initiate_handshake().unwrap()).unwrap();
+        client.complete_handshake(reply).map(|_| client)
+    };
+
+    let client = handshake(Some("server.example"), false).unwrap();
+    assert_eq!(client.peer_certificate(), Some(&leaf));
+    assert_eq!(
+        handshake(Some("other.example"), false).err(),
+        Some(PQError::Certificate(CertificateError::SubjectMismatch("server.example".into())))
+    );
+
+    // Without a name any certificate under the root would do, so the
+    // subject-agnostic mode has to be asked for
+    assert_eq!(handshake(None, false).err(), Some(PQError::Trust(TrustError::MissingPeerName)));
+    let client = handshake(None, true).unwrap();
+    assert_eq!(client.peer_certificate().map(Certificate::subject), Some("server.example"));
+}
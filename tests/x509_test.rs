@@ -0,0 +1,192 @@
+use pq_core::cert::Validity;
+use pq_core::kem::{Kem, KemAlgorithm, MlKem768};
+use pq_core::sig::dilithium::Dilithium;
+use pq_core::sig::ml_dsa::{MlDsa44, MlDsa44PublicKey, MlDsa65};
+use pq_core::sig::slh_dsa::SlhDsaShake128f;
+use pq_core::sig::SignatureAlgorithm;
+use pq_core::x509::{CertificateAuthority, CertificateRequest, KeyAlgorithm, X509Certificate, X509Error};
+use std::path::PathBuf;
+use x509_cert::der::Encode;
+
+fn fixture_str(name: &str) -> String {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data/openssl").join(name);
+    std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("reading {}: {e}", path.display()))
+}
+
+fn root_ca() -> CertificateAuthority<MlDsa65> {
+    let scheme = MlDsa65::new();
+    let (pk, sk) = scheme.keygen().unwrap();
+    CertificateAuthority::self_signed(scheme, "CN=Test Root CA,O=PQ-Core", Validity::for_days(30), &pk, sk).unwrap()
+}
+
+#[test]
+fn test_self_signed_ca() {
+    let ca = root_ca();
+    let root = ca.certificate();
+    assert!(root.is_ca());
+    assert_eq!(root.subject(), "CN=Test Root CA,O=PQ-Core");
+    assert_eq!(root.issuer(), root.subject());
+    assert_eq!(root.public_key_algorithm().unwrap(), KeyAlgorithm::Signature(SignatureAlgorithm::MlDsa65));
+    assert_eq!(root.signature_algorithm().unwrap(), SignatureAlgorithm::MlDsa65);
+    root.verify_issued_by(root).unwrap();
+
+    let pem = root.to_pem().unwrap();
+    assert!(pem.starts_with("-----BEGIN CERTIFICATE-----"));
+    assert_eq!(&X509Certificate::from_pem(&pem).unwrap(), root);
+    assert_eq!(&X509Certificate::from_der(&root.to_der().unwrap()).unwrap(), root);
+}
+
+#[test]
+fn test_sign_request() {
+    let ca = root_ca();
+    let scheme = MlDsa44::new();
+    let (pk, sk) = scheme.keygen().unwrap();
+    let csr = CertificateRequest::new(&scheme, "CN=node-1,O=Example", &pk, &sk).unwrap();
+    csr.verify().unwrap();
+
+    let pem = csr.to_pem().unwrap();
+    assert!(pem.starts_with("-----BEGIN CERTIFICATE REQUEST-----"));
+    let csr = CertificateRequest::from_pem(&pem).unwrap();
+    assert_eq!(csr.subject(), "CN=node-1,O=Example");
+    assert_eq!(csr.public_key(), pk.as_bytes());
+
+    let validity = Validity::new(1_800_000_000, 1_900_000_000);
+    let certificate = ca.sign_request(&csr, validity).unwrap();
+    certificate.verify_issued_by(ca.certificate()).unwrap();
+    assert!(!certificate.is_ca());
+    assert_eq!(certificate.validity(), validity);
+    assert_eq!(certificate.issuer(), "CN=Test Root CA,O=PQ-Core");
+    assert_eq!(certificate.public_key(), pk.as_bytes());
+
+    // The certified key info is the key's own SubjectPublicKeyInfo
+    let spki = certificate.inner().tbs_certificate.subject_public_key_info.to_der().unwrap();
+    assert_eq!(spki, pk.to_public_key_der().unwrap());
+    assert_eq!(MlDsa44PublicKey::from_public_key_der(&spki).unwrap().as_bytes(), pk.as_bytes());
+    assert_ne!(certificate.serial_number(), ca.certificate().serial_number());
+
+    // Only the CA's key verifies it
+    let other = root_ca();
+    assert!(matches!(certificate.verify_issued_by(other.certificate()), Err(X509Error::BadSignature)));
+    assert!(!certificate.can_issue());
+    assert!(matches!(certificate.verify_issued_by(&certificate), Err(X509Error::NotCa)));
+}
+
+#[test]
+fn test_end_entity_cannot_issue() {
+    let ca = root_ca();
+    let scheme = MlDsa65::new();
+    let (pk, sk) = scheme.keygen().unwrap();
+    let csr = CertificateRequest::new(&scheme, "CN=node-1", &pk, &sk).unwrap();
+    let leaf = ca.sign_request(&csr, Validity::for_days(1)).unwrap();
+
+    // A certificate signed with the leaf's key, naming the leaf as issuer
+    let rogue = CertificateAuthority::self_signed(scheme, "CN=node-1", Validity::for_days(1), &pk, sk).unwrap();
+    let (victim_pk, _) = MlKem768::new().keygen().unwrap();
+    let forged = rogue.issue_kem_certificate("CN=victim", &victim_pk, Validity::for_days(1)).unwrap();
+    assert_eq!(forged.issuer(), leaf.subject());
+    forged.verify_issued_by(rogue.certificate()).unwrap();
+    assert!(matches!(forged.verify_issued_by(&leaf), Err(X509Error::NotCa)));
+}
+
+#[test]
+fn test_request_proof_of_possession() {
+    let ca = root_ca();
+    let scheme = MlDsa44::new();
+    let (pk, _) = scheme.keygen().unwrap();
+    let (_, other_sk) = scheme.keygen().unwrap();
+
+    // Signed by a key other than the one requested
+    let csr = CertificateRequest::new(&scheme, "CN=node-1", &pk, &other_sk).unwrap();
+    assert!(matches!(csr.verify(), Err(X509Error::BadSignature)));
+    assert!(matches!(ca.sign_request(&csr, Validity::for_days(1)), Err(X509Error::BadSignature)));
+
+    assert!(matches!(
+        CertificateRequest::new(&scheme, "not a name", &pk, &other_sk),
+        Err(X509Error::InvalidName(_))
+    ));
+}
+
+#[test]
+fn test_slh_dsa_request() {
+    let ca = root_ca();
+    let scheme = SlhDsaShake128f::new();
+    let (pk, sk) = scheme.keygen().unwrap();
+    let csr = CertificateRequest::new(&scheme, "CN=firmware-signer", &pk, &sk).unwrap();
+    assert_eq!(
+        csr.public_key_algorithm().unwrap(),
+        KeyAlgorithm::Signature(SignatureAlgorithm::SlhDsaShake128f)
+    );
+    let certificate = ca.sign_request(&csr, Validity::for_days(1)).unwrap();
+    certificate.verify_issued_by(ca.certificate()).unwrap();
+}
+
+#[test]
+fn test_kem_certificate() {
+    let ca = root_ca();
+    let (pk, _) = MlKem768::new().keygen().unwrap();
+    let certificate = ca.issue_kem_certificate("CN=node-1 key exchange", &pk, Validity::for_days(1)).unwrap();
+    certificate.verify_issued_by(ca.certificate()).unwrap();
+    assert_eq!(certificate.public_key_algorithm().unwrap(), KeyAlgorithm::Kem(KemAlgorithm::MlKem768));
+    assert_eq!(certificate.kem_public_key().unwrap(), pk);
+
+    assert!(matches!(ca.certificate().kem_public_key(), Err(X509Error::NotKemKey)));
+}
+
+#[test]
+fn test_algorithms_without_oid_are_refused() {
+    let scheme = Dilithium::new();
+    let (pk, sk) = scheme.keygen().unwrap();
+    assert!(matches!(
+        CertificateRequest::new(&scheme, "CN=legacy", &pk, &sk),
+        Err(X509Error::UnsupportedAlgorithm(_))
+    ));
+    assert!(matches!(
+        CertificateAuthority::self_signed(scheme, "CN=legacy", Validity::for_days(1), &pk, sk),
+        Err(X509Error::UnsupportedAlgorithm(_))
+    ));
+}
+
+#[test]
+fn test_existing_ca_certificate() {
+    let ca = root_ca();
+    let scheme = MlDsa44::new();
+    let (pk, sk) = scheme.keygen().unwrap();
+    let csr = CertificateRequest::new(&scheme, "CN=node-1", &pk, &sk).unwrap();
+    let leaf = ca.sign_request(&csr, Validity::for_days(1)).unwrap();
+
+    assert!(matches!(CertificateAuthority::new(scheme, leaf, sk), Err(X509Error::NotCa)));
+
+    let scheme = MlDsa65::new();
+    let (pk, sk) = scheme.keygen().unwrap();
+    let root = CertificateAuthority::self_signed(scheme, "CN=Reloaded", Validity::for_days(1), &pk, sk.clone())
+        .unwrap()
+        .certificate()
+        .clone();
+    let reloaded = CertificateAuthority::new(MlDsa65::new(), X509Certificate::from_der(&root.to_der().unwrap()).unwrap(), sk);
+    assert!(reloaded.is_ok());
+}
+
+#[test]
+fn test_openssl_certificates() {
+    let root = X509Certificate::from_pem(&fixture_str("mldsa65_ca.pem")).unwrap();
+    let leaf = X509Certificate::from_pem(&fixture_str("mldsa44_leaf.pem")).unwrap();
+
+    assert!(root.is_ca());
+    root.verify_issued_by(&root).unwrap();
+    leaf.verify_issued_by(&root).unwrap();
+    // OpenSSL's "/CN=openssl-node/O=Example", in RFC 4514 (reversed) order
+    assert_eq!(leaf.subject(), "O=Example,CN=openssl-node");
+    assert_eq!(leaf.public_key_algorithm().unwrap(), KeyAlgorithm::Signature(SignatureAlgorithm::MlDsa44));
+    assert_eq!(leaf.signature_algorithm().unwrap(), SignatureAlgorithm::MlDsa65);
+}
+
+#[test]
+fn test_sign_openssl_request() {
+    let csr = CertificateRequest::from_pem(&fixture_str("mldsa44_req.pem")).unwrap();
+    csr.verify().unwrap();
+
+    let ca = root_ca();
+    let certificate = ca.sign_request(&csr, Validity::for_days(1)).unwrap();
+    certificate.verify_issued_by(ca.certificate()).unwrap();
+    assert_eq!(certificate.subject(), "O=Example,CN=openssl-node");
+}